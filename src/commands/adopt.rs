@@ -0,0 +1,310 @@
+use super::super::errors;
+use super::*;
+use crate::core::{Repo, Target};
+use crate::git;
+use clap::{App, Arg, ArgMatches};
+use std::path::{Path, PathBuf};
+
+pub struct AdoptCommand {}
+
+impl Command for AdoptCommand {
+    fn name(&self) -> String {
+        String::from("adopt")
+    }
+
+    fn app<'a>(&self) -> App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .about("moves an existing repository into your development directory")
+            .long_about("Moves a repository which was cloned outside of Git-Tool into the correct location within your development directory. The location is determined by matching the repository's `origin` remote against the URL templates of the services in your config file.")
+            .arg(Arg::new("path")
+                    .about("The path to the repository you want to adopt.")
+                    .index(1))
+            .arg(Arg::new("scan")
+                    .long("scan")
+                    .about("adopts every repository found within the provided directory")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .conflicts_with("path"))
+    }
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for AdoptCommand {
+    async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let mut output = core.output().writer();
+
+        match matches.value_of("scan") {
+            Some(dir) => {
+                let dev_dirs: Vec<PathBuf> = core
+                    .config()
+                    .get_dev_directories()
+                    .iter()
+                    .filter_map(|d| d.canonicalize().ok())
+                    .collect();
+
+                for path in find_repositories(&PathBuf::from(dir).canonicalize()?) {
+                    if dev_dirs.iter().any(|d| path.starts_with(d)) {
+                        continue;
+                    }
+
+                    match adopt(core, &path).await {
+                        Ok(repo) => writeln!(
+                            output,
+                            "Adopted {} as {}/{}",
+                            path.display(),
+                            repo.get_domain(),
+                            repo.get_full_name()
+                        )?,
+                        Err(e) => {
+                            writeln!(output, "Skipped {}\n{}\n", path.display(), e.message())?
+                        }
+                    }
+                }
+            }
+            None => {
+                let path = matches.value_of("path").ok_or(errors::user(
+                    "You did not specify the path of the repository you want to adopt.",
+                    "Provide the path to the repository like this: `git-tool adopt ~/Downloads/git-tool`, or use `git-tool adopt --scan ~/Downloads` to adopt every repository in a directory."))?;
+
+                let repo = adopt(core, &PathBuf::from(path)).await?;
+                writeln!(
+                    output,
+                    "Adopted {} as {}/{}",
+                    path,
+                    repo.get_domain(),
+                    repo.get_full_name()
+                )?;
+            }
+        }
+
+        Ok(0)
+    }
+
+    async fn complete(&self, _core: &C, completer: &Completer, _matches: &ArgMatches) {
+        completer.offer("--scan");
+    }
+}
+
+async fn adopt<C: Core>(core: &C, path: &Path) -> Result<Repo, errors::Error> {
+    let path = path.canonicalize()?;
+
+    if !path.join(".git").exists() {
+        return Err(errors::user(
+            &format!("The directory '{}' is not a Git repository.", path.display()),
+            "Make sure that you have provided the path to the root of a Git repository and try again."));
+    }
+
+    if !git::git_remote_list(&path)
+        .await?
+        .iter()
+        .any(|r| r == "origin")
+    {
+        return Err(errors::user(
+            &format!("The repository at '{}' does not have an `origin` remote.", path.display()),
+            "Add an `origin` remote pointing at the repository's hosting service using `git remote add origin $URL` and try again."));
+    }
+
+    let url = git::git_remote_get_url(&path, "origin").await?;
+
+    let name = core
+        .config()
+        .get_services()
+        .find_map(|svc| {
+            svc.get_full_name_for_url(&url)
+                .map(|full_name| format!("{}/{}", svc.get_domain(), full_name))
+        })
+        .ok_or(errors::user(
+            &format!("Could not find a service in your config file which matches the remote URL '{}'.", url),
+            "Make sure that you have a service entry in your config file whose URL templates match this repository's `origin` remote, or add one with `git-tool config add services/NAME`."))?;
+
+    let repo = core.resolver().get_repo(&PathBuf::from(name))?;
+
+    if repo.get_path() == path {
+        return Err(errors::user(
+            &format!(
+                "The repository at '{}' is already in your development directory.",
+                path.display()
+            ),
+            "There is nothing to do here, you can open it with `git-tool open`.",
+        ));
+    }
+
+    if repo.exists() {
+        return Err(errors::user(
+            &format!("The directory '{}' already exists.", repo.get_path().display()),
+            "Git-Tool will not overwrite an existing repository. Please remove or rename the existing directory if you would like to adopt this repository instead."));
+    }
+
+    if let Some(parent) = repo.get_path().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::rename(&path, repo.get_path()).map_err(|e| errors::user_with_internal(
+        &format!("We could not move '{}' to '{}'.", path.display(), repo.get_path().display()),
+        "Make sure that you have permission to move this directory and that it is on the same drive as your development directory. If it isn't, you can move it manually.",
+        e))?;
+
+    Ok(repo)
+}
+
+fn find_repositories(dir: &Path) -> Vec<PathBuf> {
+    if dir.join(".git").exists() {
+        return vec![dir.to_path_buf()];
+    }
+
+    let mut repos = vec![];
+    if let Ok(entries) = dir.read_dir() {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or_default() {
+                repos.extend(find_repositories(&entry.path()));
+            }
+        }
+    }
+
+    repos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder};
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn init_repo(path: &Path, origin: &str) {
+        std::fs::create_dir_all(path).unwrap();
+        git::git_init(path).await.unwrap();
+        git::git_remote_add(path, "origin", origin).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn run() {
+        let cmd = AdoptCommand {};
+        let temp = tempdir().unwrap();
+        let dev_dir = temp.path().join("dev");
+        std::fs::create_dir_all(&dev_dir).unwrap();
+
+        let source = temp.path().join("downloads").join("git-tool");
+        init_repo(&source, "git@github.com:sierrasoftworks/git-tool.git").await;
+
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(&dev_dir))
+            .with_mock_output()
+            .build();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["adopt", source.to_str().unwrap()]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        assert!(!source.exists(), "the original directory should be moved");
+        assert!(
+            dev_dir
+                .join("github.com")
+                .join("sierrasoftworks")
+                .join("git-tool")
+                .join(".git")
+                .exists(),
+            "the repository should be moved into the dev directory"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_existing() {
+        let cmd = AdoptCommand {};
+        let temp = tempdir().unwrap();
+        let dev_dir = temp.path().join("dev");
+        std::fs::create_dir_all(
+            dev_dir
+                .join("github.com")
+                .join("sierrasoftworks")
+                .join("git-tool"),
+        )
+        .unwrap();
+
+        let source = temp.path().join("downloads").join("git-tool");
+        init_repo(&source, "https://github.com/sierrasoftworks/git-tool.git").await;
+
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(&dev_dir))
+            .with_mock_output()
+            .build();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["adopt", source.to_str().unwrap()]);
+
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the command should refuse to overwrite an existing repository");
+        assert!(
+            source.exists(),
+            "the original directory should not be moved"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_scan() {
+        let cmd = AdoptCommand {};
+        let temp = tempdir().unwrap();
+        let dev_dir = temp.path().join("dev");
+        std::fs::create_dir_all(&dev_dir).unwrap();
+
+        let downloads = temp.path().join("downloads");
+        init_repo(
+            &downloads.join("git-tool"),
+            "git@github.com:sierrasoftworks/git-tool.git",
+        )
+        .await;
+        init_repo(
+            &downloads.join("nested").join("opensource"),
+            "git@ssh.dev.azure.com:v3/sierrasoftworks/opensource/test1.git",
+        )
+        .await;
+        init_repo(
+            &downloads.join("unknown"),
+            "https://example.com/unknown/repo.git",
+        )
+        .await;
+
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(&dev_dir))
+            .with_mock_output()
+            .build();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["adopt", "--scan", downloads.to_str().unwrap()]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        assert!(dev_dir
+            .join("github.com")
+            .join("sierrasoftworks")
+            .join("git-tool")
+            .exists());
+        assert!(dev_dir
+            .join("dev.azure.com")
+            .join("sierrasoftworks")
+            .join("opensource")
+            .join("test1")
+            .exists());
+        assert!(
+            downloads.join("unknown").exists(),
+            "repositories which don't match a service should be left in place"
+        );
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains("Skipped"),
+            "the output should mention skipped repositories"
+        );
+    }
+}
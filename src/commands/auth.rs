@@ -14,8 +14,7 @@ impl Command for AuthCommand {
             .long_about("Configures the authentication tokens used by Git-Tool to create and manage your remote repositories.")
             .arg(Arg::new("service")
                 .index(1)
-                .about("the service to configure an access token for")
-                .required(true))
+                .about("the service to configure an access token for"))
             .arg(Arg::new("remove-token")
                 .long("delete")
                 .short('d')
@@ -24,6 +23,103 @@ impl Command for AuthCommand {
                     .long("token")
                     .about("specifies the token to be set (don't use this unless you have to)")
                     .takes_value(true))
+                .arg(Arg::new("web")
+                    .long("web")
+                    .about("signs in using your browser instead of a personal access token")
+                    .conflicts_with_all(&["token", "remove-token"]))
+                .arg(Arg::new("account")
+                    .long("account")
+                    .short('a')
+                    .about("configures the token for one of the service's named accounts")
+                    .takes_value(true))
+            .subcommand(App::new("status")
+                .version("1.0")
+                .about("checks your configured access tokens")
+                .long_about("Checks the access tokens you have configured with the services that issued them, reporting the account they belong to, the scopes they have been granted and when they expire.")
+                .arg(Arg::new("service")
+                    .index(1)
+                    .about("the service whose access token should be checked")))
+    }
+}
+
+impl AuthCommand {
+    async fn status<C: Core>(&self, core: &C, service: Option<&str>) -> Result<i32, errors::Error> {
+        let services: Vec<&core::Service> = match service {
+            Some(name) => vec![core.config().get_service(name).ok_or(errors::user(
+                &format!("Could not find a service entry in your config file for {}.", name),
+                &format!("Ensure that your git-tool configuration has a service entry for this service, or add it with `git-tool config add services/{}`.", name)))?],
+            None => core.config().get_services().map(|s| s.as_ref()).collect(),
+        };
+
+        let online_services = online::services::<C>();
+        let mut output = core.output().writer();
+        let mut healthy = true;
+
+        for svc in services {
+            let online_service = match online_services.iter().find(|s| s.handles(svc)) {
+                Some(s) => s,
+                None if service.is_some() => {
+                    return Err(errors::user(
+                        &format!("Git-Tool is not able to check access tokens for {}.", svc.get_domain()),
+                        "Access tokens can only be checked for services which Git-Tool integrates with, like GitHub."))
+                }
+                None => continue,
+            };
+
+            // Each of the service's accounts has its own token, in addition to the default one.
+            for helpers::ServiceToken {
+                label,
+                token_name,
+                auth_args,
+            } in helpers::get_service_tokens(svc)
+            {
+                writeln!(output, "{}", label)?;
+
+                if core.keychain().get_token(&token_name).is_err() {
+                    healthy = false;
+                    writeln!(
+                        output,
+                        "  No access token configured, add one with `git-tool auth {}`.",
+                        auth_args
+                    )?;
+                    continue;
+                }
+
+                match online_service.token_status(core, &token_name).await {
+                    Ok(status) => {
+                        writeln!(output, "  Account: {}", status.login)?;
+                        writeln!(
+                            output,
+                            "  Scopes:  {}",
+                            status
+                                .scopes
+                                .map(|s| s.join(", "))
+                                .unwrap_or_else(|| "(not reported)".to_string())
+                        )?;
+                        writeln!(
+                            output,
+                            "  Expires: {}",
+                            status.expires.unwrap_or_else(|| "never".to_string())
+                        )?;
+
+                        for scope in status.missing_scopes {
+                            healthy = false;
+                            writeln!(
+                                output,
+                                "  Warning: the token is missing the '{}' scope, which Git-Tool needs to create repositories.",
+                                scope
+                            )?;
+                        }
+                    }
+                    Err(e) => {
+                        healthy = false;
+                        writeln!(output, "  Error: {}", e.message())?;
+                    }
+                }
+            }
+        }
+
+        Ok(if healthy { 0 } else { 1 })
     }
 }
 
@@ -33,12 +129,61 @@ impl<C: Core> CommandRunnable<C> for AuthCommand {
     where
         C: Core,
     {
+        if let Some(("status", args)) = matches.subcommand() {
+            return self.status(core, args.value_of("service")).await;
+        }
+
         let service = matches.value_of("service").ok_or(errors::user(
             "You have not provided the name of the service you wish to authenticate.",
             "Please provide the name of the service when running this command: `git-tool auth github.com`."))?;
 
+        let token_name = match matches.value_of("account") {
+            Some(account) => {
+                let svc = core.config().get_service(service).ok_or(errors::user(
+                    &format!("Could not find a service entry in your config file for {}.", service),
+                    &format!("Ensure that your git-tool configuration has a service entry for this service, or add it with `git-tool config add services/{}`.", service)))?;
+
+                svc.get_account(account).ok_or(errors::user(
+                    &format!("Could not find an account named '{}' for {} in your config file.", account, service),
+                    "Add the account to the `accounts` list for this service in your config file and try again."))?
+                    .get_token_name(svc)
+            }
+            None => service.to_string(),
+        };
+
         if matches.is_present("remove-token") {
-            core.keychain().remove_token(service)?;
+            core.keychain().remove_token(&token_name)?;
+        } else if matches.is_present("web") {
+            let svc = core.config().get_service(service).ok_or(errors::user(
+                &format!("Could not find a service entry in your config file for {}.", service),
+                &format!("Ensure that your git-tool configuration has a service entry for this service, or add it with `git-tool config add services/{}`.", service)))?;
+
+            let flow = online::services::<C>()
+                .iter()
+                .find(|s| s.handles(svc))
+                .and_then(|s| s.device_code_flow(svc))
+                .ok_or(errors::user(
+                    &format!("Git-Tool cannot sign in to {} using your browser.", service),
+                    &format!("Make sure that this service supports browser sign in and that its config entry has an `oauthClientId`, or use a personal access token with `git-tool auth {}` instead.", service)))?;
+
+            let token = flow
+                .authenticate(core, |code| {
+                    writeln!(
+                        core.output().writer(),
+                        "Open {} in your browser and enter the code {} to sign in.",
+                        code.verification_uri,
+                        code.user_code
+                    )?;
+                    Ok(())
+                })
+                .await?;
+
+            core.keychain().set_token(&token_name, &token)?;
+            writeln!(
+                core.output().writer(),
+                "Signed in to {} successfully.",
+                service
+            )?;
         } else {
             let token = match matches.value_of("token") {
                 Some(token) => token.to_string(),
@@ -48,13 +193,16 @@ impl<C: Core> CommandRunnable<C> for AuthCommand {
                     e))?
             };
 
-            core.keychain().set_token(service, &token)?;
+            core.keychain().set_token(&token_name, &token)?;
         }
 
         Ok(0)
     }
 
-    async fn complete(&self, core: &C, completer: &Completer, _matches: &ArgMatches) {
+    async fn complete(&self, core: &C, completer: &Completer, matches: &ArgMatches) {
+        if matches.subcommand().is_none() {
+            completer.offer_many(vec!["status", "--delete", "--web"]);
+        }
         completer.offer_many(core.config().get_services().map(|s| s.get_domain()));
     }
 }
@@ -87,6 +235,51 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn run_store_account() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    accounts:
+      - name: work
+        namespaces: ["work-org"]
+"#,
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let cmd = AuthCommand {};
+        let args = cmd.app().get_matches_from(vec![
+            "auth",
+            "github.com",
+            "--account",
+            "work",
+            "--token",
+            "12345",
+        ]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {
+                assert_eq!(
+                    core.keychain().get_token("github.com/work").unwrap(),
+                    "12345"
+                );
+                assert!(core.keychain().get_token("github.com").is_err());
+            }
+            Err(err) => panic!("{}", err.message()),
+        }
+    }
+
     #[tokio::test]
     async fn run_delete() {
         let cfg = Config::default();
@@ -109,4 +302,108 @@ mod tests {
             Err(err) => panic!(err.message()),
         }
     }
+
+    #[tokio::test]
+    async fn run_web() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    oauthClientId: test-client
+"#,
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .with_http_connector(
+                crate::online::service::device_code::mocks::DeviceCodeSuccessFlow::default(),
+            )
+            .build();
+
+        let cmd = AuthCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["auth", "github.com", "--web"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {
+                assert_eq!(
+                    core.keychain().get_token("github.com").unwrap(),
+                    "test-token"
+                );
+                assert!(core.output().to_string().contains("ABCD-1234"));
+            }
+            Err(err) => panic!("{}", err.message()),
+        }
+    }
+
+    #[tokio::test]
+    async fn run_status() {
+        let cfg = Config::default();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|k| k.set_token("github.com", "test_token").unwrap())
+            .with_http_connector(crate::online::service::github::mocks::TokenStatusFlow::default())
+            .build();
+
+        let cmd = AuthCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["auth", "status", "github.com"]);
+        match cmd.run(&core, &args).await {
+            Ok(code) => {
+                let output = core.output().to_string();
+                assert!(output.contains("Account: test"), "{}", output);
+                assert!(output.contains("read:user, gist"), "{}", output);
+                assert!(output.contains("2021-08-01 00:00:00 UTC"), "{}", output);
+                assert!(output.contains("'repo' scope"), "{}", output);
+                assert_eq!(code, 1, "missing scopes should be reported as a failure");
+            }
+            Err(err) => panic!("{}", err.message()),
+        }
+    }
+
+    #[tokio::test]
+    async fn run_status_no_token() {
+        let cfg = Config::default();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let cmd = AuthCommand {};
+        let args = cmd.app().get_matches_from(vec!["auth", "status"]);
+        assert_eq!(cmd.run(&core, &args).await.unwrap(), 1);
+        assert!(core
+            .output()
+            .to_string()
+            .contains("No access token configured"));
+    }
+
+    #[tokio::test]
+    async fn run_web_unsupported() {
+        let cfg = Config::default();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let cmd = AuthCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["auth", "github.com", "--web"]);
+        cmd.run(&core, &args)
+            .await
+            .expect_err("browser sign in requires an OAuth client ID");
+    }
 }
@@ -1,7 +1,8 @@
 use super::super::errors;
 use super::*;
-use crate::core::Target;
+use crate::core::{Repo, Target};
 use crate::git;
+use crate::search;
 use crate::tasks::*;
 use clap::{App, Arg};
 
@@ -25,27 +26,93 @@ impl Command for BranchCommand {
                     .index(1)
                     .about("The name of the branch you want to checkout"),
             )
+            .arg(
+                Arg::new("fetch")
+                    .long("fetch")
+                    .about("fetch the latest branches from your remotes first."),
+            )
+            .arg(
+                Arg::new("prune")
+                    .long("prune")
+                    .about("deletes local branches which have been merged into the default branch, or whose upstream is gone.")
+                    .conflicts_with("branch"),
+            )
+            .arg(
+                Arg::new("no-fetch")
+                    .long("no-fetch")
+                    .about("prunes branches without fetching the latest branches from your remotes first.")
+                    .requires("prune"),
+            )
+            .arg(
+                Arg::new("all")
+                    .long("all")
+                    .about("prunes branches in every repository matching the provided filter.")
+                    .value_name("FILTER")
+                    .takes_value(true)
+                    .min_values(0)
+                    .requires("prune"),
+            )
+            .arg(
+                Arg::new("yes")
+                    .long("yes")
+                    .short('y')
+                    .about("deletes branches without asking for confirmation first."),
+            )
     }
 }
 
 #[async_trait]
 impl<C: Core> CommandRunnable<C> for BranchCommand {
     async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        if matches.is_present("prune") {
+            return self.prune(core, matches).await;
+        }
+
         let repo = core.resolver().get_current_repo()?;
+        let git = git::backend(core.config());
+
+        if matches.is_present("fetch") {
+            git::git_fetch(&repo.get_path()).await?;
+        }
 
         match matches.value_of("branch") {
             Some(branch) => {
-                let task = tasks::GitCheckout { branch };
+                let remote_only = git
+                    .branches(&repo.get_path())
+                    .await?
+                    .iter()
+                    .any(|b| b.name == branch && !b.is_local && b.remote.is_some());
+
+                if remote_only {
+                    let task = tasks::GitSwitch {
+                        branch: branch.to_string(),
+                        create_if_missing: true,
+                    };
 
-                task.apply_repo(core, &repo).await?;
+                    task.apply_repo(core, &repo).await?;
+                } else {
+                    let task = tasks::GitCheckout { branch };
+
+                    task.apply_repo(core, &repo).await?;
+                }
             }
             None => {
-                let branches = git::git_branches(&repo.get_path()).await?;
-                let current_branch = git::git_current_branch(&repo.get_path()).await?;
+                let branches = git.branches(&repo.get_path()).await?;
+                let current_branch = git.current_branch(&repo.get_path()).await?;
 
                 for branch in branches {
-                    let prefix = if branch == current_branch { "* " } else { "  " };
-                    writeln!(core.output().writer(), "{}{}", prefix, branch)?;
+                    let prefix = if branch.name == current_branch {
+                        "* "
+                    } else {
+                        "  "
+                    };
+                    writeln!(
+                        core.output().writer(),
+                        "{}{} {}",
+                        prefix,
+                        branch.name,
+                        branch.marker()
+                    )?;
                 }
             }
         };
@@ -55,12 +122,121 @@ impl<C: Core> CommandRunnable<C> for BranchCommand {
 
     async fn complete(&self, core: &C, completer: &Completer, _matches: &ArgMatches) {
         if let Ok(repo) = core.resolver().get_current_repo() {
-            if let Ok(branches) = git::git_branches(&repo.get_path()).await {
-                completer.offer_many(branches);
+            completer.offer_many(vec!["--fetch", "--prune", "--no-fetch", "--all", "--yes"]);
+            if let Ok(branches) = git::backend(core.config()).branches(&repo.get_path()).await {
+                completer.offer_many(branches.into_iter().map(|b| b.name));
+            }
+        }
+    }
+}
+impl BranchCommand {
+    async fn prune<C: Core>(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let mut output = core.output().writer();
+
+        let repos = if matches.is_present("all") {
+            let filter = matches.value_of("all").unwrap_or_default();
+            core.resolver()
+                .get_repos()?
+                .into_iter()
+                .filter(|r| {
+                    search::matches(&format!("{}/{}", r.get_domain(), r.get_full_name()), filter)
+                })
+                .collect()
+        } else {
+            vec![core.resolver().get_current_repo()?]
+        };
+
+        for repo in repos {
+            let name = format!("{}/{}", repo.get_domain(), repo.get_full_name());
+
+            // Branches whose upstream is gone can only be found once we've fetched from their remote.
+            if !matches.is_present("no-fetch") {
+                if let Err(e) = git::git_fetch(&repo.get_path()).await {
+                    writeln!(output, "Skipped {}\n{}\n", name, e.message())?;
+                    continue;
+                }
+            }
+
+            let branches = match prunable_branches(core, &repo).await {
+                Ok(branches) if branches.is_empty() => continue,
+                Ok(branches) => branches,
+                Err(e) => {
+                    writeln!(output, "Skipped {}\n{}\n", name, e.message())?;
+                    continue;
+                }
+            };
+
+            writeln!(output, "{}", name)?;
+            for (branch, reason) in branches.iter() {
+                writeln!(output, "  - {} ({})", branch, reason)?;
+            }
+
+            if !matches.is_present("yes")
+                && !helpers::confirm(
+                    core,
+                    &format!("Delete {} branches from {}?", branches.len(), name),
+                )?
+            {
+                continue;
+            }
+
+            for (branch, _) in branches {
+                if let Err(e) = git::git_branch_delete(&repo.get_path(), &branch).await {
+                    writeln!(
+                        output,
+                        "Could not delete {} from {}\n{}\n",
+                        branch,
+                        name,
+                        e.message()
+                    )?;
+                }
             }
         }
+
+        Ok(0)
     }
 }
+
+/// Finds the local branches in a repository which can be safely removed, along with the
+/// reason they were selected. The current and default branches are never included.
+async fn prunable_branches<C: Core>(
+    core: &C,
+    repo: &Repo,
+) -> Result<Vec<(String, String)>, errors::Error> {
+    let current_branch = git::git_current_branch(&repo.get_path())
+        .await
+        .unwrap_or_default();
+    let default_branch = git::git_default_branch(&repo.get_path()).await?;
+
+    // Your local copy of the default branch may be behind (or missing), so we check against
+    // the remote's copy of it whenever there is one.
+    let remote_default = format!("origin/{}", default_branch);
+    let target = match git::backend(core.config())
+        .rev_parse(
+            &repo.get_path(),
+            &format!("refs/remotes/{}", remote_default),
+        )
+        .await
+    {
+        Ok(_) => remote_default,
+        Err(_) => default_branch.clone(),
+    };
+
+    let mut branches: Vec<(String, String)> = vec![];
+    for branch in git::git_merged_branches(&repo.get_path(), &target).await? {
+        branches.push((branch, format!("merged into {}", target)));
+    }
+
+    for branch in git::git_gone_branches(&repo.get_path()).await? {
+        if !branches.iter().any(|(b, _)| b == &branch) {
+            branches.push((branch, "upstream is gone".to_string()));
+        }
+    }
+
+    branches.retain(|(b, _)| b != &current_branch && b != &default_branch);
+    Ok(branches)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -99,6 +275,74 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn list_branches_with_remotes() {
+        let cmd = BranchCommand {};
+
+        let temp = tempfile::tempdir().unwrap();
+
+        let repo: Repo = core::Repo::new(
+            "github.com/sierrasoftworks/test-git-branch-command",
+            temp.path().join("repo"),
+        );
+
+        let core = core::CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .with_mock_output()
+            .with_mock_resolver(|r| r.set_repo(repo.clone()))
+            .build();
+
+        sequence![
+            tasks::GitInit {},
+            tasks::GitRemote { name: "origin" },
+            tasks::GitCheckout { branch: "main" },
+            tasks::WriteFile {
+                path: "README.md".into(),
+                content: "This is an example README file.",
+            },
+            tasks::GitAdd {
+                paths: vec!["README.md"],
+            },
+            tasks::GitCommit {
+                message: "Add README.md",
+                paths: vec!["README.md"],
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .unwrap();
+
+        let sha = git::git_rev_parse(&repo.get_path(), "HEAD").await.unwrap();
+        git::git_update_ref(&repo.get_path(), "refs/remotes/origin/main", &sha)
+            .await
+            .unwrap();
+        git::git_update_ref(&repo.get_path(), "refs/remotes/origin/feature", &sha)
+            .await
+            .unwrap();
+
+        let args: ArgMatches = cmd.app().get_matches_from(vec!["branch"]);
+        cmd.run(&core, &args).await.unwrap();
+
+        assert_eq!(
+            core.output().to_string(),
+            "  feature (origin)\n* main (local, origin)\n"
+        );
+
+        let args: ArgMatches = cmd.app().get_matches_from(vec!["branch", "feature"]);
+        cmd.run(&core, &args).await.unwrap();
+
+        assert_eq!(
+            git::git_current_branch(&repo.get_path()).await.unwrap(),
+            "feature"
+        );
+        assert_eq!(
+            git::git_rev_parse(&repo.get_path(), "feature@{upstream}")
+                .await
+                .expect("the new branch should track the remote branch"),
+            sha
+        );
+    }
+
     #[tokio::test]
     async fn checkout_branch_outside_repo() {
         let cmd = BranchCommand {};
@@ -122,4 +366,184 @@ mod tests {
             .await
             .expect_err("this command should not have succeeded");
     }
+
+    #[tokio::test]
+    async fn prune_merged_branches() {
+        let cmd = BranchCommand {};
+
+        let temp = tempfile::tempdir().unwrap();
+
+        let repo: Repo = core::Repo::new(
+            "github.com/sierrasoftworks/test-git-branch-prune",
+            temp.path().join("repo"),
+        );
+
+        let core = core::CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .with_mock_output()
+            .with_mock_resolver(|r| r.set_repo(repo.clone()))
+            .build();
+
+        sequence![
+            tasks::GitInit {},
+            tasks::GitCheckout { branch: "main" },
+            tasks::WriteFile {
+                path: "README.md".into(),
+                content: "This is an example README file.",
+            },
+            tasks::GitAdd {
+                paths: vec!["README.md"],
+            },
+            tasks::GitCommit {
+                message: "Add README.md",
+                paths: vec!["README.md"],
+            },
+            tasks::GitCheckout {
+                branch: "feature/merged"
+            },
+            tasks::GitCheckout {
+                branch: "feature/unmerged"
+            },
+            tasks::WriteFile {
+                path: "CHANGES.md".into(),
+                content: "This change hasn't been merged yet.",
+            },
+            tasks::GitAdd {
+                paths: vec!["CHANGES.md"],
+            },
+            tasks::GitCommit {
+                message: "Add CHANGES.md",
+                paths: vec!["CHANGES.md"],
+            },
+            tasks::GitSwitch {
+                branch: "main".into(),
+                create_if_missing: false,
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .unwrap();
+
+        let args: ArgMatches = cmd
+            .app()
+            .get_matches_from(vec!["branch", "--prune", "--yes"]);
+        cmd.run(&core, &args).await.unwrap();
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains("feature/merged (merged into main)"),
+            "the output should list the pruned branch: {}",
+            output
+        );
+
+        let branches: Vec<String> = git::git_branches(&repo.get_path())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(branches, vec!["feature/unmerged", "main"]);
+    }
+
+    #[tokio::test]
+    async fn prune_against_remote_default_branch() {
+        let cmd = BranchCommand {};
+
+        let temp = tempfile::tempdir().unwrap();
+
+        let repo: Repo = core::Repo::new(
+            "github.com/sierrasoftworks/test-git-branch-prune-remote",
+            temp.path().join("repo"),
+        );
+
+        let core = core::CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .with_mock_output()
+            .with_mock_resolver(|r| r.set_repo(repo.clone()))
+            .build();
+
+        sequence![
+            tasks::GitInit {},
+            tasks::GitCheckout { branch: "main" },
+            tasks::WriteFile {
+                path: "README.md".into(),
+                content: "This is an example README file.",
+            },
+            tasks::GitAdd {
+                paths: vec!["README.md"],
+            },
+            tasks::GitCommit {
+                message: "Add README.md",
+                paths: vec!["README.md"],
+            },
+            tasks::GitCheckout {
+                branch: "feature/merged"
+            },
+            tasks::WriteFile {
+                path: "CHANGES.md".into(),
+                content: "This change has been merged on the remote.",
+            },
+            tasks::GitAdd {
+                paths: vec!["CHANGES.md"],
+            },
+            tasks::GitCommit {
+                message: "Add CHANGES.md",
+                paths: vec!["CHANGES.md"],
+            },
+            tasks::GitCheckout {
+                branch: "feature/checked-out"
+            },
+            tasks::GitSwitch {
+                branch: "main".into(),
+                create_if_missing: false,
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .unwrap();
+
+        // The remote's main branch has moved on, but your local copy of it hasn't.
+        let sha = git::git_rev_parse(&repo.get_path(), "feature/merged")
+            .await
+            .unwrap();
+        git::git_update_ref(&repo.get_path(), "refs/remotes/origin/main", &sha)
+            .await
+            .unwrap();
+
+        // Branches which are checked out in a worktree can't be deleted.
+        git::git_worktree_add(
+            &repo.get_path(),
+            &temp.path().join("worktree"),
+            "feature/checked-out",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let args: ArgMatches =
+            cmd.app()
+                .get_matches_from(vec!["branch", "--prune", "--no-fetch", "--yes"]);
+        cmd.run(&core, &args).await.unwrap();
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains("feature/merged (merged into origin/main)"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("Could not delete feature/checked-out from github.com/sierrasoftworks/test-git-branch-prune-remote"),
+            "{}",
+            output
+        );
+
+        let branches: Vec<String> = git::git_branches(&repo.get_path())
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|b| b.is_local)
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(branches, vec!["feature/checked-out", "main"]);
+    }
 }
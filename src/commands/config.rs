@@ -1,8 +1,12 @@
 use super::async_trait;
+use super::core::{
+    config_schema, format_value, registry_entry_schema, unified_diff, Config, InstalledEntry,
+    YamlDocument, YamlPath,
+};
 use super::Command;
 use super::*;
 use clap::{App, Arg, ArgMatches};
-use online::registry::Registry;
+use online::registry::{EntryConfig, Registry};
 
 pub struct ConfigCommand {}
 
@@ -15,13 +19,34 @@ impl Command for ConfigCommand {
         App::new(self.name().as_str())
             .version("1.0")
             .about("manage your Git-Tool configuration file")
-            .long_about("This tool allows you to easily make changes to your Git-Tool config file.")
+            .long_about("This tool allows you to easily make changes to your Git-Tool config file. Changes are shown as a diff and only written once you confirm them, with a backup kept so that you can undo them with `gt config undo`.")
+
+            .subcommand(App::new("show")
+                .version("1.0")
+                .about("shows your current configuration, or a config template")
+                .long_about("Shows the configuration Git-Tool is using, which is built by layering your system-wide config file, your own config file and any `GITTOOL_*` environment variables over Git-Tool's defaults. If you provide the ID of a config template, its details are shown instead.")
+                .arg(Arg::new("id")
+                    .index(1)
+                    .about("the id of the config template you want to see"))
+                .arg(Arg::new("origin")
+                    .long("origin")
+                    .about("shows where each of your config values came from")
+                    .conflicts_with("id")))
 
             .subcommand(App::new("list")
                 .version("1.0")
                 .visible_alias("ls")
                 .about("list available config templates")
-                .long_about("Gets the list of config templates which are available through the Git-Tool registry."))
+                .long_about("Gets the list of config templates which are available through your registries, marking those which are already in your config."))
+
+            .subcommand(App::new("search")
+                .version("1.0")
+                .about("searches the available config templates")
+                .long_about("Searches the names and descriptions of the config templates which are available through your registries.")
+                .arg(Arg::new("term")
+                    .index(1)
+                    .about("the term to search for")
+                    .required(true)))
 
             .subcommand(App::new("add")
                 .version("1.0")
@@ -34,7 +59,48 @@ impl Command for ConfigCommand {
                 .arg(Arg::new("force")
                     .long("force")
                     .short('f')
-                    .about("overwrites any existing entries with those from the template.")))
+                    .about("overwrites any existing entries with those from the template."))
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
+
+            .subcommand(App::new("remove")
+                .version("1.0")
+                .visible_alias("rm")
+                .about("removes a configuration template from your config file")
+                .long_about("Removes the apps and services which were added to your config file by a configuration template.")
+                .arg(Arg::new("id")
+                    .index(1)
+                    .about("the id of the configuration template you want to remove")
+                    .required(true))
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
+
+            .subcommand(App::new("upgrade")
+                .version("1.0")
+                .about("upgrades the configuration templates in your config file")
+                .long_about("Checks whether any of the configuration templates you have added with `gt config add` have been updated in their registries, showing you the changes and applying them to your config file.")
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
+
+            .subcommand(App::new("profiles")
+                .version("1.0")
+                .about("lists the profiles in your config file")
+                .long_about("Lists the profiles in your config file, marking the one which is being used. Profiles are chosen with the --profile option, the GITTOOL_PROFILE environment variable or automatically when you are within their dev or scratchpad directory."))
+
+            .subcommand(App::new("schema")
+                .version("1.0")
+                .about("prints the JSON Schema for your config file")
+                .long_about("Prints the JSON Schema which describes Git-Tool's config file, which you can use to have your editor validate and autocomplete it. Use --entry to get the schema for registry entries instead.")
+                .arg(Arg::new("entry")
+                    .long("entry")
+                    .about("prints the schema for registry entries instead of your config file")))
+
+            .subcommand(App::new("undo")
+                .version("1.0")
+                .about("undoes the last change made to your config file")
+                .long_about("Restores the backup of your config file which Git-Tool keeps whenever `gt config` changes it. Running it again will redo the change.")
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
 
             .subcommand(App::new("alias")
                 .version("1.0")
@@ -49,7 +115,44 @@ impl Command for ConfigCommand {
                     .index(1))
                 .arg(Arg::new("repo")
                     .about("the fully qualified repository name")
-                    .index(2)))
+                    .index(2))
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
+
+            .subcommand(App::new("get")
+                .version("1.0")
+                .about("gets a value from your config file")
+                .long_about("Gets the value at a specific path in your config, like `features.http_transport` or `apps[code].args`.")
+                .arg(Arg::new("path")
+                    .about("the path of the config value you want to get")
+                    .index(1)
+                    .required(true)))
+
+            .subcommand(App::new("set")
+                .version("1.0")
+                .about("sets a value in your config file")
+                .long_about("Sets the value at a specific path in your config file, like `features.http_transport` or `apps[code].args`, while preserving the formatting and comments in the rest of the file. Values are parsed as YAML, so you can provide lists like `[., --new-window]`.")
+                .arg(Arg::new("path")
+                    .about("the path of the config value you want to set")
+                    .index(1)
+                    .required(true))
+                .arg(Arg::new("value")
+                    .about("the value you want to set, written as YAML")
+                    .index(2)
+                    .required(true))
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
+
+            .subcommand(App::new("unset")
+                .version("1.0")
+                .about("removes a value from your config file")
+                .long_about("Removes the value at a specific path in your config file, like `features.http_transport` or `apps[code]`, while preserving the formatting and comments in the rest of the file.")
+                .arg(Arg::new("path")
+                    .about("the path of the config value you want to remove")
+                    .index(1)
+                    .required(true))
+                .arg(yes_arg())
+                .arg(dry_run_arg()))
     }
 }
 
@@ -58,11 +161,111 @@ impl<C: Core> CommandRunnable<C> for ConfigCommand {
     async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
         match matches.subcommand() {
             Some(("list", _args)) => {
-                let registry = crate::online::GitHubRegistry;
+                let registry = crate::online::ConfiguredRegistry;
 
-                let entries = registry.get_entries(core).await?;
-                for entry in entries {
-                    writeln!(core.output().writer(), "{}", entry)?;
+                let (ids, failures) = registry.get_available_entries(core).await;
+
+                // Entries which can't be fetched are still listed, we just can't tell whether you added them by hand.
+                let entries =
+                    futures::future::join_all(ids.iter().map(|id| registry.get_entry(core, id)))
+                        .await;
+
+                let mut output = core.output().writer();
+                for (id, entry) in ids.iter().zip(entries) {
+                    if is_installed(core, id, entry.ok().as_ref()) {
+                        writeln!(output, "{} (installed)", id)?;
+                    } else {
+                        writeln!(output, "{}", id)?;
+                    }
+                }
+
+                self.warn_unavailable(core, failures)?;
+            }
+            Some(("search", args)) => {
+                let term = args.value_of("term").unwrap_or_default();
+                let registry = crate::online::ConfiguredRegistry;
+
+                let (ids, failures) = registry.get_available_entries(core).await;
+
+                // Each entry is fetched separately, so we fetch them all at once and skip any which fail.
+                let entries =
+                    futures::future::join_all(ids.iter().map(|id| registry.get_entry(core, id)))
+                        .await;
+
+                let mut output = core.output().writer();
+                for (id, entry) in ids.iter().zip(entries) {
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(err) => {
+                            writeln!(
+                                output,
+                                "Warning: could not get the details of {}.\n{}",
+                                id, err
+                            )?;
+                            continue;
+                        }
+                    };
+
+                    if !entry.matches(term) {
+                        continue;
+                    }
+
+                    if is_installed(core, id, Some(&entry)) {
+                        writeln!(output, "{} (installed)", id)?;
+                    } else {
+                        writeln!(output, "{}", id)?;
+                    }
+                    writeln!(output, "  {}: {}", entry.name, entry.description)?;
+                }
+
+                self.warn_unavailable(core, failures)?;
+            }
+            Some(("show", args)) if args.is_present("id") => {
+                let id = args.value_of("id").unwrap_or_default();
+                let registry = crate::online::ConfiguredRegistry;
+                let entry = registry.get_entry(core, id).await?;
+
+                let mut output = core.output().writer();
+                writeln!(output, "{} ({})", entry.name, id)?;
+                writeln!(output, "{}", entry.description)?;
+                writeln!(
+                    output,
+                    "Installed: {}",
+                    if is_installed(core, id, Some(&entry)) {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                )?;
+
+                for config in entry.configs.iter() {
+                    writeln!(output)?;
+                    writeln!(
+                        output,
+                        "Platform: {}{}",
+                        config.platform,
+                        if config.is_compatible() {
+                            ""
+                        } else {
+                            " (not compatible with this system)"
+                        }
+                    )?;
+
+                    if let Some(app) = &config.app {
+                        writeln!(output, "  App:      {}", app.name)?;
+                        writeln!(output, "  Command:  {} {}", app.command, app.args.join(" "))?;
+                        for env in app.environment.iter() {
+                            writeln!(output, "  Env:      {}", env)?;
+                        }
+                    }
+
+                    if let Some(svc) = &config.service {
+                        writeln!(output, "  Service:  {}", svc.domain)?;
+                        writeln!(output, "  Pattern:  {}", svc.pattern)?;
+                        writeln!(output, "  Website:  {}", svc.website)?;
+                        writeln!(output, "  Git URL:  {}", svc.git_url)?;
+                        writeln!(output, "  HTTP URL: {}", svc.http_url)?;
+                    }
                 }
             }
             Some(("add", args)) => {
@@ -71,59 +274,154 @@ impl<C: Core> CommandRunnable<C> for ConfigCommand {
                     "",
                 ))?;
 
-                let registry = crate::online::GitHubRegistry;
-                let entry = registry.get_entry(core, id).await?;
+                let registry = crate::online::ConfiguredRegistry;
+                let (id, entry) = registry.find_entry(core, id).await?;
 
                 writeln!(core.output().writer(), "Applying {}", entry.name)?;
                 writeln!(core.output().writer(), "> {}", entry.description)?;
 
-                let mut cfg = core.config().clone();
+                let cfg = core.config().get_base();
+                let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                let mut installed = InstalledEntry::new(&id, &entry.version);
                 for ec in entry.configs {
                     if ec.is_compatible() {
-                        cfg = cfg.apply_template(ec, args.is_present("force"))?;
+                        installed = record_template(installed, &ec);
+                        cfg.apply_template(&mut doc, ec, args.is_present("force"))?;
                     }
                 }
+                cfg.add_installed(&mut doc, &installed)?;
+
+                self.validate(core, &doc)?;
+                return self.apply(core, args, &doc.to_string()).await;
+            }
+            Some(("remove", args)) => {
+                let id = args.value_of("id").unwrap_or_default();
+
+                let cfg = core.config().get_base();
+                let installed = cfg
+                    .get_installed()
+                    .find(|e| {
+                        e.get_id() == id
+                            || e.get_id().split_once(':').map(|(_, entry_id)| entry_id) == Some(id)
+                    })
+                    .cloned()
+                    .ok_or_else(|| errors::user(
+                        &format!("The config template {} is not in your config file.", id),
+                        "You can see the templates which are in your config file by running `gt config list`.",
+                    ))?;
+
+                // Entries which were added before we recorded their apps and services are looked up
+                // in their registry instead.
+                let installed =
+                    if installed.get_apps().is_empty() && installed.get_services().is_empty() {
+                        let registry = crate::online::ConfiguredRegistry;
+                        let (_, entry) = registry.find_entry(core, installed.get_id()).await?;
 
-                match cfg.get_config_file() {
-                    Some(path) => {
-                        tokio::fs::write(&path, cfg.to_string()?).await?;
+                        entry
+                            .configs
+                            .iter()
+                            .filter(|ec| ec.is_compatible())
+                            .fold(installed, record_template)
+                    } else {
+                        installed
+                    };
+
+                writeln!(core.output().writer(), "Removing {}", installed.get_id())?;
+
+                let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                cfg.remove_installed(&mut doc, &installed)?;
+
+                self.validate(core, &doc)?;
+                return self.apply(core, args, &doc.to_string()).await;
+            }
+            Some(("upgrade", args)) => {
+                let registry = crate::online::ConfiguredRegistry;
+
+                let cfg = core.config().get_base();
+                let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                let mut upgraded = 0;
+                for installed in cfg.get_installed() {
+                    let (id, entry) = match registry.find_entry(core, installed.get_id()).await {
+                        Ok(found) => found,
+                        Err(err) => {
+                            writeln!(
+                                core.output().writer(),
+                                "Warning: could not check {} for updates.\n{}",
+                                installed.get_id(),
+                                err
+                            )?;
+                            continue;
+                        }
+                    };
+
+                    if entry.version == installed.get_version() {
+                        continue;
                     }
-                    None => {
-                        writeln!(core.output().writer(), "{}", cfg.to_string()?)?;
+
+                    // We keep track of the apps and services from earlier versions too, since
+                    // upgrading doesn't remove them from your config.
+                    let mut upgrade = InstalledEntry::new(&id, &entry.version);
+                    for app in installed.get_apps() {
+                        upgrade = upgrade.with_app(app);
+                    }
+                    for svc in installed.get_services() {
+                        upgrade = upgrade.with_service(svc);
+                    }
+
+                    for ec in entry.configs {
+                        if ec.is_compatible() {
+                            upgrade = record_template(upgrade, &ec);
+                            cfg.apply_template(&mut doc, ec, true)?;
+                        }
                     }
+                    cfg.add_installed(&mut doc, &upgrade)?;
+
+                    writeln!(
+                        core.output().writer(),
+                        "Upgrading {} from {} to {}",
+                        id,
+                        installed.get_version(),
+                        entry.version
+                    )?;
+
+                    upgraded += 1;
+                }
+
+                if upgraded == 0 {
+                    writeln!(
+                        core.output().writer(),
+                        "All of your config templates are up to date."
+                    )?;
+                    return Ok(0);
                 }
+
+                self.validate(core, &doc)?;
+                return self.apply(core, args, &doc.to_string()).await;
             }
             Some(("alias", args)) => match args.value_of("alias") {
                 Some(alias) => {
-                    if args.is_present("delete") {
-                        let mut cfg = core.config().clone();
-                        cfg.remove_alias(alias);
+                    let path = YamlPath::parse("aliases")?.child(alias);
 
-                        match cfg.get_config_file() {
-                            Some(path) => {
-                                tokio::fs::write(&path, cfg.to_string()?).await?;
-                            }
-                            None => {
-                                writeln!(core.output().writer(), "{}", cfg.to_string()?)?;
-                            }
+                    if args.is_present("delete") {
+                        let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                        if !doc.unset(&path)? {
+                            return Err(errors::user(
+                                &format!("The alias '{}' is not in your config file.", alias),
+                                "You can see where each of your aliases came from by running `gt config show --origin`.",
+                            ));
                         }
 
-                        return Ok(0);
+                        self.validate(core, &doc)?;
+                        return self.apply(core, args, &doc.to_string()).await;
                     }
 
                     match args.value_of("repo") {
                         Some(repo) => {
-                            let mut cfg = core.config().clone();
-                            cfg.add_alias(alias, repo);
+                            let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                            doc.set(&path, &serde_yaml::Value::String(repo.to_string()))?;
 
-                            match cfg.get_config_file() {
-                                Some(path) => {
-                                    tokio::fs::write(&path, cfg.to_string()?).await?;
-                                }
-                                None => {
-                                    writeln!(core.output().writer(), "{}", cfg.to_string()?)?;
-                                }
-                            }
+                            self.validate(core, &doc)?;
+                            return self.apply(core, args, &doc.to_string()).await;
                         }
                         None => {
                             let mut output = core.output().writer();
@@ -145,6 +443,128 @@ impl<C: Core> CommandRunnable<C> for ConfigCommand {
                     }
                 }
             },
+            Some(("profiles", _args)) => {
+                let mut output = core.output().writer();
+                for profile in core.config().get_profiles() {
+                    if Some(profile.get_name()) == core.config().get_profile() {
+                        writeln!(output, "{} (active)", profile.get_name())?;
+                    } else {
+                        writeln!(output, "{}", profile.get_name())?;
+                    }
+                }
+            }
+            Some(("show", args)) if args.is_present("origin") => {
+                let mut output = core.output().writer();
+                for (path, value, origin) in core.config().get_origins()? {
+                    writeln!(
+                        output,
+                        "{} = {}  # {}",
+                        path,
+                        serde_json::to_string(&value)?,
+                        origin
+                    )?;
+                }
+            }
+            Some(("get", args)) => {
+                let path = YamlPath::parse(args.value_of("path").unwrap_or_default())?;
+                let value = serde_yaml::to_value(core.config())?;
+
+                match path.get(&value) {
+                    Some(value) => {
+                        writeln!(core.output().writer(), "{}", format_value(value)?)?;
+                    }
+                    None => {
+                        return Err(errors::user(
+                            &format!("The config path '{}' is not set in your config.", path),
+                            "Make sure that you have spelled the path correctly, you can see your current config by running `gt config`.",
+                        ));
+                    }
+                }
+            }
+            Some(("set", args)) => {
+                let path = YamlPath::parse(args.value_of("path").unwrap_or_default())?;
+                let raw_value = args.value_of("value").unwrap_or_default();
+                let value = serde_yaml::from_str(raw_value)
+                    .unwrap_or_else(|_| serde_yaml::Value::String(raw_value.to_string()));
+
+                let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                doc.set(&path, &value)?;
+
+                self.validate(core, &doc)?;
+                // Your other config layers may override this value, so we check it against your config file alone.
+                let cfg = Config::default().with_user_file(&doc.to_string())?;
+                let expected = match &value {
+                    serde_yaml::Value::Null => None,
+                    serde_yaml::Value::Sequence(s) if s.is_empty() => None,
+                    serde_yaml::Value::Mapping(m) if m.is_empty() => None,
+                    value => Some(value),
+                };
+
+                let cfg_value = serde_yaml::to_value(&cfg)?;
+                let actual = path.get(&cfg_value).filter(|v| match v {
+                    serde_yaml::Value::Null => false,
+                    serde_yaml::Value::Sequence(s) => !s.is_empty(),
+                    serde_yaml::Value::Mapping(m) => !m.is_empty(),
+                    _ => true,
+                });
+
+                if actual != expected {
+                    return Err(errors::user(
+                        &format!("The config path '{}' is not a setting that Git-Tool supports, or does not accept the value '{}'.", path, raw_value),
+                        "Make sure that you have spelled the path correctly and that the value has the right type, you can see your current config by running `gt config`.",
+                    ));
+                }
+
+                return self.apply(core, args, &doc.to_string()).await;
+            }
+            Some(("unset", args)) => {
+                let path = YamlPath::parse(args.value_of("path").unwrap_or_default())?;
+
+                let mut doc = YamlDocument::new(&self.get_source(core).await?);
+                if !doc.unset(&path)? {
+                    return Err(errors::user(
+                        &format!("The config path '{}' is not set in your config file.", path),
+                        "Make sure that you have spelled the path correctly, you can see your current config by running `gt config`.",
+                    ));
+                }
+
+                self.validate(core, &doc)?;
+                return self.apply(core, args, &doc.to_string()).await;
+            }
+            Some(("schema", args)) => {
+                let schema = if args.is_present("entry") {
+                    registry_entry_schema()
+                } else {
+                    config_schema()
+                };
+
+                writeln!(
+                    core.output().writer(),
+                    "{}",
+                    serde_json::to_string_pretty(&schema)?
+                )?;
+            }
+            Some(("undo", args)) => {
+                let path = core.config().get_config_file().ok_or_else(|| {
+                    errors::user(
+                        "You do not have a config file, so there are no changes to undo.",
+                        "Make sure that you are running Git-Tool with the --config option or the GITTOOL_CONFIG environment variable set.",
+                    )
+                })?;
+
+                let backup = tokio::fs::read_to_string(get_backup_file(&path))
+                    .await
+                    .map_err(|e| {
+                        errors::user_with_internal(
+                            "There is no previous version of your config file to restore.",
+                            "Git-Tool keeps a backup of your config file whenever `gt config` changes it, so you can only undo changes made that way.",
+                            e,
+                        )
+                    })?;
+
+                self.validate(core, &YamlDocument::new(&backup))?;
+                return self.apply(core, args, &backup).await;
+            }
             _ => {
                 writeln!(core.output().writer(), "{}", core.config().to_string()?)?;
             }
@@ -155,9 +575,16 @@ impl<C: Core> CommandRunnable<C> for ConfigCommand {
 
     async fn complete(&self, core: &C, completer: &Completer, matches: &ArgMatches) {
         match matches.subcommand() {
-            Some(("list", _)) => {}
+            Some(("list", _)) | Some(("search", _)) | Some(("profiles", _)) => {}
+            Some(("remove", _)) => {
+                completer.offer_many(core.config().get_installed().map(|e| e.get_id()));
+            }
+            Some(("upgrade", _)) | Some(("undo", _)) => {
+                completer.offer("--yes");
+                completer.offer("--dry-run");
+            }
             Some(("add", _)) => {
-                let registry = online::GitHubRegistry;
+                let registry = online::ConfiguredRegistry;
                 match registry.get_entries(core).await {
                     Ok(entries) => {
                         completer.offer_many(entries);
@@ -184,18 +611,167 @@ impl<C: Core> CommandRunnable<C> for ConfigCommand {
                     }
                 }
             }
+            Some(("show", args)) => {
+                if !args.is_present("id") {
+                    completer.offer("--origin");
+                    if let Ok(entries) = online::ConfiguredRegistry.get_entries(core).await {
+                        completer.offer_many(entries);
+                    }
+                }
+            }
+            Some(("schema", _)) => {
+                completer.offer("--entry");
+            }
+            Some(("get", _)) | Some(("set", _)) | Some(("unset", _)) => {}
             _ => {
-                completer.offer_many(vec!["list", "add", "alias"]);
+                completer.offer_many(vec![
+                    "show", "list", "search", "add", "remove", "upgrade", "undo", "profiles",
+                    "schema", "alias", "get", "set", "unset",
+                ]);
+            }
+        }
+    }
+}
+
+impl ConfigCommand {
+    async fn get_source<C: Core>(&self, core: &C) -> Result<String, errors::Error> {
+        match core.config().get_config_file() {
+            Some(path) => Ok(tokio::fs::read_to_string(&path).await?),
+            None => core.config().get_base().to_string(),
+        }
+    }
+
+    /// Lets you know about any registries which could not be read, without stopping the entries
+    /// from your other registries from being shown.
+    fn warn_unavailable<C: Core>(
+        &self,
+        core: &C,
+        failures: Vec<(String, errors::Error)>,
+    ) -> Result<(), errors::Error> {
+        let mut output = core.output().writer();
+        for (name, err) in failures {
+            writeln!(
+                output,
+                "Warning: could not get the entries from the '{}' registry.\n{}",
+                name, err
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that your config would still be valid, once layered with your system-wide config
+    /// and any environment variable overrides, if your config file were replaced with `doc`.
+    fn validate<C: Core>(&self, core: &C, doc: &YamlDocument) -> Result<Config, errors::Error> {
+        core.config().get_base().with_user_file(&doc.to_string()).map_err(|e| {
+            errors::user_with_internal(
+                "The change you made would leave you with an invalid config file.",
+                "Make sure that the value you provided has the right type for the setting you are changing.",
+                e,
+            )
+        })
+    }
+
+    /// Shows the changes that would be made to your config file and, unless this is a dry run,
+    /// writes them once you have confirmed them (keeping a backup of the previous version).
+    async fn apply<C: Core>(
+        &self,
+        core: &C,
+        args: &ArgMatches,
+        content: &str,
+    ) -> Result<i32, errors::Error> {
+        let current = self.get_source(core).await?;
+        let mut output = core.output().writer();
+
+        let (current_name, new_name) = match core.config().get_config_file() {
+            Some(path) => (
+                format!("{} (current)", path.display()),
+                format!("{} (updated)", path.display()),
+            ),
+            None => ("current config".to_string(), "updated config".to_string()),
+        };
+
+        let diff = unified_diff(&current, content, &current_name, &new_name);
+        if diff.is_empty() {
+            writeln!(output, "There are no changes to make to your config.")?;
+            return Ok(0);
+        }
+
+        write!(output, "{}", diff)?;
+
+        if args.is_present("dry-run") {
+            return Ok(0);
+        }
+
+        match core.config().get_config_file() {
+            Some(path) => {
+                if !args.is_present("yes")
+                    && !helpers::confirm(core, "Apply these changes to your config file?")?
+                {
+                    writeln!(output, "Your config file has not been changed.")?;
+                    return Ok(0);
+                }
+
+                tokio::fs::write(get_backup_file(&path), &current).await?;
+                tokio::fs::write(&path, content).await?;
+            }
+            None => {
+                writeln!(output, "{}", content)?;
             }
         }
+
+        Ok(0)
+    }
+}
+
+/// Gets the path of the backup which is kept of your config file's previous contents whenever
+/// `gt config` changes it, which `gt config undo` restores.
+fn get_backup_file(config_file: &std::path::Path) -> std::path::PathBuf {
+    let mut path = config_file.as_os_str().to_owned();
+    path.push(".bak");
+    std::path::PathBuf::from(path)
+}
+
+/// Determines whether a registry entry is in your config, either because you added it with
+/// `gt config add` or because you already have its apps or services.
+fn is_installed<C: Core>(core: &C, id: &str, entry: Option<&online::registry::Entry>) -> bool {
+    core.config().get_installed_entry(id).is_some()
+        || entry
+            .map(|e| e.is_installed(core.config()))
+            .unwrap_or_default()
+}
+
+/// Records the app and service which a registry template adds to your config.
+fn record_template(installed: InstalledEntry, template: &EntryConfig) -> InstalledEntry {
+    let installed = match &template.app {
+        Some(app) => installed.with_app(&app.name),
+        None => installed,
+    };
+
+    match &template.service {
+        Some(svc) => installed.with_service(&svc.domain),
+        None => installed,
     }
 }
 
+fn yes_arg<'a>() -> Arg<'a> {
+    Arg::new("yes")
+        .long("yes")
+        .short('y')
+        .about("writes the changes to your config file without asking for confirmation first.")
+}
+
+fn dry_run_arg<'a>() -> Arg<'a> {
+    Arg::new("dry-run")
+        .long("dry-run")
+        .about("shows the changes that would be made to your config file without writing them.")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::core::{Config, CoreBuilder};
+    use super::core::CoreBuilder;
     use super::*;
-    use crate::test::get_dev_dir;
+    use crate::test::{get_dev_dir, get_repo_root};
     use clap::ArgMatches;
     use complete::helpers::test_completions_with_config;
 
@@ -250,145 +826,200 @@ mod tests {
         );
     }
 
+    fn local_registry_config() -> Config {
+        Config::from_str(&format!(
+            r#"
+directory: /dev
+registries:
+  - name: git-tool
+    type: directory
+    path: "{}"
+"#,
+            get_repo_root()
+                .join("registry")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\")
+        ))
+        .unwrap()
+    }
+
     #[tokio::test]
-    async fn run_add_no_file() {
-        let cfg = Config::from_str("directory: /dev").unwrap();
+    async fn run_list_installed() {
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: /dev
+registries:
+  - name: git-tool
+    type: directory
+    path: "{}"
+installed:
+  - id: "git-tool:apps/bash"
+    version: 1.0.0
+"#,
+            get_repo_root()
+                .join("registry")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\")
+        ))
+        .unwrap();
         let core = CoreBuilder::default()
             .with_config(&cfg)
             .with_mock_output()
             .build();
 
         let cmd = ConfigCommand {};
-        let args = cmd
-            .app()
-            .get_matches_from(vec!["config", "add", "apps/bash"]);
+        let args = cmd.app().get_matches_from(vec!["config", "list"]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
-            Err(err) => panic!(err.message()),
+            Err(err) => panic!("{}", err.message()),
         }
 
         let output = core.output().to_string();
-        println!("{}", output);
         assert!(
-            output.contains("name: bash\n"),
-            "the output should contain the new config"
+            output.contains("git-tool:apps/bash (installed)\n"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("git-tool:services/github (installed)\n"),
+            "{}",
+            output
         );
+        assert!(output.contains("git-tool:apps/zsh\n"), "{}", output);
     }
 
     #[tokio::test]
-    async fn run_add_with_file() {
+    async fn run_list_unavailable_registry() {
         let temp = tempfile::tempdir().unwrap();
-        tokio::fs::write(
-            temp.path().join("config.yml"),
-            Config::default().to_string().unwrap(),
-        )
-        .await
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: /dev
+registries:
+  - name: git-tool
+    type: directory
+    path: "{}"
+  - name: acme
+    type: directory
+    path: "{}"
+"#,
+            get_repo_root()
+                .join("registry")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\"),
+            temp.path()
+                .join("missing")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\")
+        ))
         .unwrap();
-
-        let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
         let core = CoreBuilder::default()
             .with_config(&cfg)
             .with_mock_output()
             .build();
 
         let cmd = ConfigCommand {};
-        let args = cmd
-            .app()
-            .get_matches_from(vec!["config", "add", "apps/bash"]);
+        let args = cmd.app().get_matches_from(vec!["config", "list"]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
-            Err(err) => panic!(err.message()),
+            Err(err) => panic!("{}", err.message()),
         }
 
         let output = core.output().to_string();
+        assert!(output.contains("git-tool:apps/bash\n"), "{}", output);
         assert!(
-            output.contains("Applying Bash\n> "),
-            "the output should describe what is being done"
-        );
-
-        let new_cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
-        assert!(
-            new_cfg.get_app("bash").is_some(),
-            "the app should be added to the config file"
+            output.contains("Warning: could not get the entries from the 'acme' registry.\n"),
+            "{}",
+            output
         );
     }
 
     #[tokio::test]
-    async fn run_alias_list() {
-        let cfg = Config::from_str(
-            r#"
-directory: /dev
-
-aliases:
-  test1: example.com/tests/test1
-  test2: example.com/tests/test2
-"#,
-        )
-        .unwrap();
+    async fn run_search() {
         let core = CoreBuilder::default()
-            .with_config(&cfg)
+            .with_config(&local_registry_config())
             .with_mock_output()
             .build();
 
         let cmd = ConfigCommand {};
-        let args = cmd.app().get_matches_from(vec!["config", "alias"]);
+        let args = cmd.app().get_matches_from(vec!["config", "search", "BASH"]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
-            Err(err) => panic!(err.message()),
+            Err(err) => panic!("{}", err.message()),
         }
 
         let output = core.output().to_string();
-        println!("{}", output);
-        assert!(
-            output.contains("test1 = example.com/tests/test1\n"),
-            "the output should contain the aliases"
-        );
         assert!(
-            output.contains("test2 = example.com/tests/test2\n"),
-            "the output should contain the aliases"
+            output.contains(
+                "git-tool:apps/bash\n  Bash: Launches the bash shell within a project directory.\n"
+            ),
+            "{}",
+            output
         );
+        assert!(!output.contains("services/github"), "{}", output);
     }
 
     #[tokio::test]
-    async fn run_alias_info() {
-        let cfg = Config::from_str(
-            r#"
-directory: /dev
-
-aliases:
-  test1: example.com/tests/test1
-  test2: example.com/tests/test2
-"#,
-        )
-        .unwrap();
+    async fn run_show_entry() {
         let core = CoreBuilder::default()
-            .with_config(&cfg)
+            .with_config(&local_registry_config())
             .with_mock_output()
             .build();
 
         let cmd = ConfigCommand {};
-        let args = cmd.app().get_matches_from(vec!["config", "alias", "test1"]);
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "show", "services/github"]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
-            Err(err) => panic!(err.message()),
+            Err(err) => panic!("{}", err.message()),
         }
 
         let output = core.output().to_string();
-        println!("{}", output);
         assert!(
-            output.contains("test1 = example.com/tests/test1\n"),
-            "the output should contain the alias"
+            output.starts_with("GitHub (services/github)\nAdds support for managing GitHub repositories through Git-Tool.\nInstalled: yes\n\nPlatform: any\n  Service:  github.com\n  Pattern:  */*\n"),
+            "{}",
+            output
         );
     }
 
     #[tokio::test]
-    async fn run_alias_add() {
-        let temp = tempfile::tempdir().unwrap();
-        tokio::fs::write(
+    async fn run_add_no_file() {
+        let cfg = Config::from_str("directory: /dev").unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "add", "apps/bash"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!(err.message()),
+        }
+
+        let output = core.output().to_string();
+        println!("{}", output);
+        assert!(
+            output.contains("name: bash\n"),
+            "the output should contain the new config"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_add_with_file() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
             temp.path().join("config.yml"),
             Config::default().to_string().unwrap(),
         )
@@ -396,16 +1027,333 @@ aliases:
         .unwrap();
 
         let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "add", "apps/bash", "--yes"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!(err.message()),
+        }
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains("Applying Bash\n> "),
+            "the output should describe what is being done"
+        );
+
+        let new_cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+        assert!(
+            new_cfg.get_app("bash").is_some(),
+            "the app should be added to the config file"
+        );
+    }
 
+    async fn run_with_file(config_file: &std::path::Path, args: Vec<&str>) -> String {
+        let cfg = Config::from_file(config_file).unwrap();
         let core = CoreBuilder::default()
             .with_config(&cfg)
             .with_mock_output()
             .build();
 
         let cmd = ConfigCommand {};
-        let args =
-            cmd.app()
-                .get_matches_from(vec!["config", "alias", "test", "example.com/tests/test"]);
+        let args = cmd.app().get_matches_from(args);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        core.output().to_string()
+    }
+
+    #[tokio::test]
+    async fn run_upgrade_and_remove() {
+        let temp = tempfile::tempdir().unwrap();
+        let registry = temp.path().join("registry");
+        std::fs::create_dir_all(registry.join("apps")).unwrap();
+
+        let write_entry = |version: &str, command: &str| {
+            std::fs::write(
+                registry.join("apps").join("test.yaml"),
+                format!(
+                    "name: Test\ndescription: A test app.\nversion: {}\nconfigs:\n  - platform: any\n    app:\n      name: test\n      command: {}\n",
+                    version, command
+                ),
+            )
+            .unwrap();
+        };
+        write_entry("1.0.0", "test-v1");
+
+        let config_file = temp.path().join("config.yml");
+        std::fs::write(
+            &config_file,
+            format!(
+                "directory: /dev\nregistries:\n  - name: local\n    type: directory\n    path: \"{}\"\n",
+                registry.to_str().unwrap().replace("\\", "\\\\")
+            ),
+        )
+        .unwrap();
+
+        run_with_file(&config_file, vec!["config", "add", "apps/test", "--yes"]).await;
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert_eq!(
+            cfg.get_installed_entry("local:apps/test")
+                .map(|e| e.get_version()),
+            Some("1.0.0")
+        );
+
+        let output = run_with_file(&config_file, vec!["config", "upgrade"]).await;
+        assert_eq!(output, "All of your config templates are up to date.\n");
+
+        write_entry("1.1.0", "test-v2");
+        let output = run_with_file(&config_file, vec!["config", "upgrade", "--yes"]).await;
+        assert_eq!(
+            output,
+            format!(
+                "Upgrading local:apps/test from 1.0.0 to 1.1.0\n--- {path} (current)\n+++ {path} (updated)\n@@ -7,9 +7,9 @@\n   - name: shell\n     command: bash\n   - name: test\n-    command: test-v1\n+    command: test-v2\n installed:\n   - id: \"local:apps/test\"\n-    version: 1.0.0\n+    version: 1.1.0\n     apps:\n       - test\n",
+                path = config_file.display()
+            )
+        );
+
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert_eq!(
+            cfg.get_app("test").map(|a| a.get_command()),
+            Some("test-v2")
+        );
+        assert_eq!(
+            cfg.get_installed_entry("local:apps/test")
+                .map(|e| e.get_version()),
+            Some("1.1.0")
+        );
+
+        run_with_file(
+            &config_file,
+            vec!["config", "remove", "local:apps/test", "--yes"],
+        )
+        .await;
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert!(cfg.get_app("test").is_none());
+        assert_eq!(cfg.get_installed().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn run_upgrade_unavailable_entry() {
+        let temp = tempfile::tempdir().unwrap();
+        let registry = temp.path().join("registry");
+        std::fs::create_dir_all(registry.join("apps")).unwrap();
+        std::fs::write(
+            registry.join("apps").join("test.yaml"),
+            "name: Test\ndescription: A test app.\nversion: 1.1.0\nconfigs:\n  - platform: any\n    app:\n      name: test\n      command: test-v2\n",
+        )
+        .unwrap();
+
+        let config_file = temp.path().join("config.yml");
+        std::fs::write(
+            &config_file,
+            format!(
+                "directory: /dev\nregistries:\n  - name: local\n    type: directory\n    path: \"{}\"\napps:\n  - name: test\n    command: test-v1\ninstalled:\n  - id: \"local:apps/missing\"\n    version: 1.0.0\n  - id: \"local:apps/test\"\n    version: 1.0.0\n",
+                registry.to_str().unwrap().replace("\\", "\\\\")
+            ),
+        )
+        .unwrap();
+
+        let output = run_with_file(&config_file, vec!["config", "upgrade", "--yes"]).await;
+        assert!(
+            output.contains("Warning: could not check local:apps/missing for updates.\n"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("Upgrading local:apps/test from 1.0.0 to 1.1.0\n"),
+            "{}",
+            output
+        );
+
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert_eq!(
+            cfg.get_app("test").map(|a| a.get_command()),
+            Some("test-v2")
+        );
+        assert_eq!(
+            cfg.get_installed_entry("local:apps/missing")
+                .map(|e| e.get_version()),
+            Some("1.0.0")
+        );
+    }
+
+    #[tokio::test]
+    async fn run_remove_not_installed() {
+        let temp = tempfile::tempdir().unwrap();
+        let registry = temp.path().join("registry");
+        std::fs::create_dir_all(registry.join("apps")).unwrap();
+        std::fs::write(
+            registry.join("apps").join("test.yaml"),
+            "name: Test\ndescription: A test app.\nversion: 1.0.0\nconfigs:\n  - platform: any\n    app:\n      name: test\n      command: test\n",
+        )
+        .unwrap();
+
+        // Templates which were not added with `gt config add` are left alone.
+        let config_file = temp.path().join("config.yml");
+        std::fs::write(
+            &config_file,
+            format!(
+                "directory: /dev\nregistries:\n  - name: local\n    type: directory\n    path: \"{}\"\napps:\n  - name: test\n    command: test\n",
+                registry.to_str().unwrap().replace("\\", "\\\\")
+            ),
+        )
+        .unwrap();
+
+        let cfg = Config::from_file(&config_file).unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "remove", "local:apps/test", "--yes"]);
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the template was not added with gt config add");
+
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert!(cfg.get_app("test").is_some());
+    }
+
+    #[tokio::test]
+    async fn run_remove_missing_from_registry() {
+        let temp = tempfile::tempdir().unwrap();
+        let registry = temp.path().join("registry");
+        std::fs::create_dir_all(registry.join("apps")).unwrap();
+
+        // The entry has since been removed from its registry, but we know what it added.
+        let config_file = temp.path().join("config.yml");
+        std::fs::write(
+            &config_file,
+            format!(
+                "directory: /dev\nregistries:\n  - name: local\n    type: directory\n    path: \"{}\"\napps:\n  - name: shell\n    command: bash\n  - name: test\n    command: test\ninstalled:\n  - id: \"local:apps/test\"\n    version: 1.0.0\n    apps:\n      - test\n",
+                registry.to_str().unwrap().replace("\\", "\\\\")
+            ),
+        )
+        .unwrap();
+
+        let output =
+            run_with_file(&config_file, vec!["config", "remove", "apps/test", "--yes"]).await;
+        assert!(
+            output.starts_with("Removing local:apps/test\n"),
+            "{}",
+            output
+        );
+
+        let cfg = Config::from_file(&config_file).unwrap();
+        assert!(cfg.get_app("test").is_none());
+        assert!(cfg.get_app("shell").is_some());
+        assert_eq!(cfg.get_installed().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn run_alias_list() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+
+aliases:
+  test1: example.com/tests/test1
+  test2: example.com/tests/test2
+"#,
+        )
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd.app().get_matches_from(vec!["config", "alias"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!(err.message()),
+        }
+
+        let output = core.output().to_string();
+        println!("{}", output);
+        assert!(
+            output.contains("test1 = example.com/tests/test1\n"),
+            "the output should contain the aliases"
+        );
+        assert!(
+            output.contains("test2 = example.com/tests/test2\n"),
+            "the output should contain the aliases"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_alias_info() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+
+aliases:
+  test1: example.com/tests/test1
+  test2: example.com/tests/test2
+"#,
+        )
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd.app().get_matches_from(vec!["config", "alias", "test1"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!(err.message()),
+        }
+
+        let output = core.output().to_string();
+        println!("{}", output);
+        assert!(
+            output.contains("test1 = example.com/tests/test1\n"),
+            "the output should contain the alias"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_alias_add() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            temp.path().join("config.yml"),
+            Config::default().to_string().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd.app().get_matches_from(vec![
+            "config",
+            "alias",
+            "test",
+            "example.com/tests/test",
+            "--yes",
+        ]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
@@ -449,7 +1397,7 @@ aliases:
         let cmd = ConfigCommand {};
         let args = cmd
             .app()
-            .get_matches_from(vec!["config", "alias", "-d", "test"]);
+            .get_matches_from(vec!["config", "alias", "-d", "test", "--yes"]);
 
         match cmd.run(&core, &args).await {
             Ok(_) => {}
@@ -488,4 +1436,348 @@ aliases:
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn run_show_origin() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            temp.path().join("config.yml"),
+            "directory: /dev\nfeatures:\n  http_transport: true\n",
+        )
+        .await
+        .unwrap();
+
+        let cfg = Config::load(
+            None,
+            Some(&temp.path().join("config.yml")),
+            vec![(
+                "GITTOOL_FEATURE_NATIVE_CLONE".to_string(),
+                "true".to_string(),
+            )],
+            None,
+            None,
+        )
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "show", "--origin"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains(&format!(
+                "features.http_transport = true  # user ({})\n",
+                temp.path().join("config.yml").display()
+            )),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains(
+                "features.native_clone = true  # environment (GITTOOL_FEATURE_NATIVE_CLONE)\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("features.create_remote = true  # default\n"),
+            "{}",
+            output
+        );
+    }
+
+    #[tokio::test]
+    async fn run_get() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+apps:
+  - name: code
+    command: code
+    args:
+      - .
+"#,
+        )
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "get", "apps[code].args"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        assert_eq!(core.output().to_string(), "- \".\"\n");
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "get", "apps[shell].args"]);
+        assert!(
+            cmd.run(&core, &args).await.is_err(),
+            "missing values should return an error"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_set_with_file() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            temp.path().join("config.yml"),
+            r#"# My Git-Tool config
+directory: /dev
+
+features:
+  # Use HTTPS instead of SSH
+  http_transport: false
+"#,
+        )
+        .await
+        .unwrap();
+
+        let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd.app().get_matches_from(vec![
+            "config",
+            "set",
+            "features.http_transport",
+            "true",
+            "--yes",
+        ]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let content = tokio::fs::read_to_string(temp.path().join("config.yml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            content,
+            r#"# My Git-Tool config
+directory: /dev
+
+features:
+  # Use HTTPS instead of SSH
+  http_transport: true
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn run_set_invalid() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(temp.path().join("config.yml"), "directory: /dev\n")
+            .await
+            .unwrap();
+
+        let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        for (path, value) in [
+            ("features.unknown_feature", "true"),
+            ("features.http_transport", "not-a-bool"),
+            ("keychain.backend", "unknown"),
+        ] {
+            let args = cmd
+                .app()
+                .get_matches_from(vec!["config", "set", path, value]);
+            assert!(
+                cmd.run(&core, &args).await.is_err(),
+                "setting {} to {} should fail",
+                path,
+                value
+            );
+        }
+
+        let args =
+            cmd.app()
+                .get_matches_from(vec!["config", "set", "keychain.backend", "file", "--yes"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let content = tokio::fs::read_to_string(temp.path().join("config.yml"))
+            .await
+            .unwrap();
+        assert_eq!(content, "directory: /dev\nkeychain:\n  backend: file\n");
+    }
+
+    #[tokio::test]
+    async fn run_unset() {
+        let temp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            temp.path().join("config.yml"),
+            r#"directory: /dev
+
+aliases:
+  # My blog
+  blog: github.com/sierrasoftworks/blog
+  gt: github.com/sierrasoftworks/git-tool
+"#,
+        )
+        .await
+        .unwrap();
+
+        let cfg = Config::from_file(&temp.path().join("config.yml")).unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = ConfigCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "unset", "aliases.gt", "--yes"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let content = tokio::fs::read_to_string(temp.path().join("config.yml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            content,
+            r#"directory: /dev
+
+aliases:
+  # My blog
+  blog: github.com/sierrasoftworks/blog
+"#
+        );
+
+        // Your dev directory may be provided by your system-wide config instead.
+        tokio::fs::write(temp.path().join("system.yml"), "directory: /src\n")
+            .await
+            .unwrap();
+        let cfg = Config::load(
+            Some(&temp.path().join("system.yml")),
+            Some(&temp.path().join("config.yml")),
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["config", "unset", "directory", "--yes"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let content = tokio::fs::read_to_string(temp.path().join("config.yml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            content,
+            r#"
+aliases:
+  # My blog
+  blog: github.com/sierrasoftworks/blog
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn run_dry_run_and_undo() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_file = temp.path().join("config.yml");
+        tokio::fs::write(&config_file, "directory: /dev\n")
+            .await
+            .unwrap();
+
+        let output = run_with_file(
+            &config_file,
+            vec![
+                "config",
+                "alias",
+                "gt",
+                "github.com/sierrasoftworks/git-tool",
+                "--dry-run",
+            ],
+        )
+        .await;
+        assert_eq!(
+            output,
+            format!(
+                "--- {path} (current)\n+++ {path} (updated)\n@@ -1,1 +1,3 @@\n directory: /dev\n+aliases:\n+  gt: github.com/sierrasoftworks/git-tool\n",
+                path = config_file.display()
+            )
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(&config_file).await.unwrap(),
+            "directory: /dev\n",
+            "a dry run should not change the config file"
+        );
+
+        let output = run_with_file(
+            &config_file,
+            vec!["config", "set", "features.http_transport", "true", "--yes"],
+        )
+        .await;
+        assert_eq!(
+            output,
+            format!(
+                "--- {path} (current)\n+++ {path} (updated)\n@@ -1,1 +1,3 @@\n directory: /dev\n+features:\n+  http_transport: true\n",
+                path = config_file.display()
+            )
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(temp.path().join("config.yml.bak"))
+                .await
+                .unwrap(),
+            "directory: /dev\n",
+            "the previous config should have been backed up"
+        );
+
+        run_with_file(&config_file, vec!["config", "undo", "--yes"]).await;
+        assert_eq!(
+            tokio::fs::read_to_string(&config_file).await.unwrap(),
+            "directory: /dev\n",
+            "the change should have been undone"
+        );
+
+        run_with_file(&config_file, vec!["config", "undo", "--yes"]).await;
+        assert_eq!(
+            tokio::fs::read_to_string(&config_file).await.unwrap(),
+            "directory: /dev\nfeatures:\n  http_transport: true\n",
+            "undoing the undo should redo the change"
+        );
+    }
 }
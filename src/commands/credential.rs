@@ -0,0 +1,263 @@
+use super::*;
+use clap::Arg;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+pub struct CredentialCommand {}
+
+impl Command for CredentialCommand {
+    fn name(&self) -> String {
+        String::from("credential")
+    }
+
+    fn app<'a>(&self) -> clap::App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .about("acts as a Git credential helper")
+            .long_about("Implements Git's credential helper protocol using the access tokens you have stored with `git-tool auth`. Git-Tool configures this automatically for repositories cloned using the `http_transport` feature, or you can enable it yourself with `git config --global credential.helper \"!git-tool credential\"`.")
+            .arg(Arg::new("operation")
+                .index(1)
+                .about("the credential operation requested by Git")
+                .possible_values(&["get", "store", "erase"])
+                .required(true))
+    }
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for CredentialCommand {
+    async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let operation = matches.value_of("operation").unwrap_or_default();
+
+        let stdin = std::io::stdin();
+        let request = read_request(stdin.lock())?;
+
+        respond(core, operation, &request)?;
+
+        Ok(0)
+    }
+
+    async fn complete(&self, _core: &C, completer: &Completer, _matches: &ArgMatches) {
+        completer.offer_many(vec!["get", "store", "erase"]);
+    }
+}
+
+/// Reads the `key=value` attributes which Git provides to a credential helper,
+/// stopping at the first blank line.
+fn read_request<R: BufRead>(input: R) -> Result<BTreeMap<String, String>, errors::Error> {
+    let mut request = BTreeMap::new();
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            break;
+        }
+
+        if let Some(idx) = line.find('=') {
+            request.insert(line[..idx].to_string(), line[idx + 1..].to_string());
+        }
+    }
+
+    Ok(request)
+}
+
+fn respond<C: Core>(
+    core: &C,
+    operation: &str,
+    request: &BTreeMap<String, String>,
+) -> Result<(), errors::Error> {
+    // Git will fall back to its other credential helpers if we don't respond, so we
+    // only handle requests for services which are present in the user's config.
+    let service = match request
+        .get("host")
+        .and_then(|host| core.config().get_service(host))
+    {
+        Some(service) => service,
+        None => return Ok(()),
+    };
+
+    // When Git provides the repository's path (with `credential.useHttpPath`), we use it to
+    // select the token for the account which owns that namespace.
+    let token_name = request
+        .get("path")
+        .and_then(|path| {
+            let path = path.trim_matches('/');
+            path.rfind('/')
+                .and_then(|idx| service.get_account_for_namespace(&path[..idx]))
+        })
+        .map(|account| account.get_token_name(service))
+        .unwrap_or_else(|| service.get_domain());
+
+    match operation {
+        "get" => {
+            if let Ok(token) = core.keychain().get_token(&token_name) {
+                let mut output = core.output().writer();
+                writeln!(
+                    output,
+                    "username={}",
+                    request
+                        .get("username")
+                        .map(|u| u.as_str())
+                        .unwrap_or("git-tool")
+                )?;
+                writeln!(output, "password={}", token)?;
+            }
+        }
+        "store" => {
+            // The password may have come from another credential helper or a prompt, so we
+            // never let it replace the token Git-Tool also uses to talk to the service's API.
+            if let Some(password) = request.get("password") {
+                if core.keychain().get_token(&token_name).is_err() {
+                    core.keychain().set_token(&token_name, password)?;
+                }
+            }
+        }
+        "erase" => {
+            // We only remove the token if it is the one Git was trying to use, to
+            // avoid discarding a token which has since been replaced.
+            let current = core.keychain().get_token(&token_name).ok();
+            if current.is_some() && current.as_ref() == request.get("password") {
+                core.keychain().remove_token(&token_name)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder};
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let request = read_request(
+            "protocol=https\nhost=github.com\nusername=test\n\nignored=true\n".as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(request.get("protocol"), Some(&"https".to_string()));
+        assert_eq!(request.get("host"), Some(&"github.com".to_string()));
+        assert_eq!(request.get("username"), Some(&"test".to_string()));
+        assert_eq!(request.get("ignored"), None);
+    }
+
+    #[test]
+    fn run_get() {
+        let core = CoreBuilder::default()
+            .with_config(&Config::default())
+            .with_mock_output()
+            .with_mock_keychain(|k| {
+                k.set_token("github.com", "test-token").unwrap();
+            })
+            .build();
+
+        let request = read_request("protocol=https\nhost=github.com\n".as_bytes()).unwrap();
+        respond(&core, "get", &request).unwrap();
+
+        assert_eq!(
+            core.output().to_string(),
+            "username=git-tool\npassword=test-token\n"
+        );
+    }
+
+    #[test]
+    fn run_get_account() {
+        let config = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    accounts:
+      - name: work
+        namespaces: ["work-org"]
+"#,
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&config)
+            .with_mock_output()
+            .with_mock_keychain(|k| {
+                k.set_token("github.com", "personal-token").unwrap();
+                k.set_token("github.com/work", "work-token").unwrap();
+            })
+            .build();
+
+        let request =
+            read_request("protocol=https\nhost=github.com\npath=work-org/service.git\n".as_bytes())
+                .unwrap();
+        respond(&core, "get", &request).unwrap();
+
+        assert_eq!(
+            core.output().to_string(),
+            "username=git-tool\npassword=work-token\n"
+        );
+    }
+
+    #[test]
+    fn run_get_unknown_host() {
+        let core = CoreBuilder::default()
+            .with_config(&Config::default())
+            .with_mock_output()
+            .with_mock_keychain(|k| {
+                k.set_token("github.com", "test-token").unwrap();
+            })
+            .build();
+
+        let request = read_request("protocol=https\nhost=example.com\n".as_bytes()).unwrap();
+        respond(&core, "get", &request).unwrap();
+
+        assert_eq!(core.output().to_string(), "");
+    }
+
+    #[test]
+    fn run_store_erase() {
+        let core = CoreBuilder::default()
+            .with_config(&Config::default())
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let request = read_request(
+            "protocol=https\nhost=github.com\nusername=test\npassword=new-token\n".as_bytes(),
+        )
+        .unwrap();
+
+        respond(&core, "store", &request).unwrap();
+        assert_eq!(
+            core.keychain().get_token("github.com").unwrap(),
+            "new-token"
+        );
+
+        respond(&core, "erase", &request).unwrap();
+        assert!(core.keychain().get_token("github.com").is_err());
+    }
+
+    #[test]
+    fn run_store_existing_token() {
+        let core = CoreBuilder::default()
+            .with_config(&Config::default())
+            .with_mock_output()
+            .with_mock_keychain(|k| {
+                k.set_token("github.com", "api-token").unwrap();
+            })
+            .build();
+
+        let request = read_request(
+            "protocol=https\nhost=github.com\nusername=test\npassword=other-password\n".as_bytes(),
+        )
+        .unwrap();
+
+        respond(&core, "store", &request).unwrap();
+        assert_eq!(
+            core.keychain().get_token("github.com").unwrap(),
+            "api-token"
+        );
+    }
+}
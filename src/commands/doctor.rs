@@ -0,0 +1,441 @@
+use super::*;
+use crate::core::{Repo, Scratchpad, Service, Target};
+use crate::git;
+use clap::{App, ArgMatches};
+use std::path::{Path, PathBuf};
+
+/// The oldest version of Git which supports all of the commands Git-Tool uses (like `git switch`).
+const MIN_GIT_VERSION: (u32, u32) = (2, 23);
+
+pub struct DoctorCommand {}
+
+impl Command for DoctorCommand {
+    fn name(&self) -> String {
+        String::from("doctor")
+    }
+
+    fn app<'a>(&self) -> App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .about("checks that your environment is set up correctly")
+            .long_about("Checks your Git-Tool config and environment for common problems, like a missing development directory, services whose templates cannot be rendered, apps which are not on your PATH, an old version of Git, invalid access tokens and an SSH agent without any keys.")
+    }
+}
+
+enum CheckResult {
+    Pass(String),
+    Warn(errors::Error),
+    Fail(errors::Error),
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for DoctorCommand {
+    async fn run(&self, core: &C, _matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let mut checks = vec![("Config file".to_string(), self.check_config(core))];
+        checks.extend(self.check_dev_directories(core));
+
+        for svc in core.config().get_services() {
+            checks.push((
+                format!("Service {}", svc.get_domain()),
+                self.check_service(svc),
+            ));
+        }
+
+        for app in core.config().get_apps() {
+            checks.push((format!("App {}", app.get_name()), self.check_app(core, app)));
+        }
+
+        checks.push(("Git".to_string(), self.check_git().await));
+        checks.extend(self.check_tokens(core).await);
+        checks.push(("SSH agent".to_string(), self.check_ssh_agent(core).await));
+
+        let mut output = core.output().writer();
+        let mut healthy = true;
+
+        for (name, result) in checks {
+            match result {
+                CheckResult::Pass(message) => {
+                    writeln!(output, "[pass] {}: {}", name, message)?;
+                }
+                CheckResult::Warn(err) => {
+                    writeln!(output, "[warn] {}: {}", name, err.description())?;
+                    writeln!(output, "       Advice: {}", err.advice())?;
+                }
+                CheckResult::Fail(err) => {
+                    healthy = false;
+                    writeln!(output, "[fail] {}: {}", name, err.description())?;
+                    writeln!(output, "       Advice: {}", err.advice())?;
+                }
+            }
+        }
+
+        Ok(if healthy { 0 } else { 1 })
+    }
+
+    async fn complete(&self, _core: &C, _completer: &Completer, _matches: &ArgMatches) {}
+}
+
+impl DoctorCommand {
+    fn check_config<C: Core>(&self, core: &C) -> CheckResult {
+        match core.config().get_config_file() {
+            Some(path) => CheckResult::Pass(format!("using {}", path.display())),
+            None => CheckResult::Warn(errors::user(
+                "You are not using a config file, so Git-Tool is using its default configuration.",
+                "Create a config file and set the GITTOOL_CONFIG environment variable to its path, as described in the README.",
+            )),
+        }
+    }
+
+    fn check_dev_directories<C: Core>(&self, core: &C) -> Vec<(String, CheckResult)> {
+        core.config()
+            .get_dev_directories()
+            .into_iter()
+            .enumerate()
+            .map(|(i, dir)| {
+                // Only your primary dev directory comes from the `directory` field, the rest are
+                // listed under `directories`.
+                let field = if i == 0 { "directory" } else { "directories" };
+                (
+                    "Dev directory".to_string(),
+                    self.check_dev_directory(dir, field),
+                )
+            })
+            .collect()
+    }
+
+    fn check_dev_directory(&self, dir: &Path, field: &str) -> CheckResult {
+        if dir.components().count() == 0 {
+            return CheckResult::Fail(errors::user(
+                "You have not configured a development directory.",
+                "Set the `directory` field in your config file to the folder you keep your repositories in.",
+            ));
+        }
+
+        if !dir.is_dir() {
+            return CheckResult::Fail(errors::user(
+                &format!("Your development directory '{}' does not exist.", dir.display()),
+                &format!("Create the directory, or update the `{}` field in your config file to point to the folder you keep your repositories in.", field),
+            ));
+        }
+
+        let probe = dir.join(format!(".git-tool-doctor-{}", std::process::id()));
+        match std::fs::write(&probe, "").and_then(|_| std::fs::remove_file(&probe)) {
+            Ok(_) => CheckResult::Pass(format!("{} exists and is writable", dir.display())),
+            Err(err) => CheckResult::Fail(errors::user_with_internal(
+                &format!(
+                    "Your development directory '{}' is not writable.",
+                    dir.display()
+                ),
+                "Make sure that you have permission to create files in your development directory.",
+                err,
+            )),
+        }
+    }
+
+    fn check_service(&self, svc: &Service) -> CheckResult {
+        // We build a sample repo with as many namespace segments as the service's pattern expects.
+        let segments = svc.get_pattern().split('/').count().max(2);
+        let mut full_name = vec![svc.get_domain()];
+        full_name.extend((1..segments).map(|i| format!("namespace{}", i)));
+        full_name.push("repo".to_string());
+
+        let repo = Repo::new(&full_name.join("/"), PathBuf::new());
+
+        let rendered = svc
+            .get_website(&repo)
+            .and_then(|_| svc.get_git_url(&repo))
+            .and_then(|_| svc.get_http_url(&repo));
+
+        match rendered {
+            Ok(_) => CheckResult::Pass("templates render correctly".to_string()),
+            Err(err) => CheckResult::Fail(errors::user_with_internal(
+                &format!(
+                    "The URL templates for {} could not be rendered.",
+                    svc.get_domain()
+                ),
+                "Check the `website`, `gitUrl` and `httpUrl` templates for this service in your config file.",
+                err,
+            )),
+        }
+    }
+
+    fn check_app<C: Core>(&self, core: &C, app: &crate::core::App) -> CheckResult {
+        let scratchpad = Scratchpad::new("2020w01", core.config().get_scratch_directory());
+
+        let command = match app.get_program(scratchpad.template_context(core.config())) {
+            Ok(command) => command,
+            Err(err) => {
+                return CheckResult::Fail(errors::user_with_internal(
+                    &format!(
+                        "The command for the '{}' app could not be rendered.",
+                        app.get_name()
+                    ),
+                    "Check the `command` template for this app in your config file.",
+                    err,
+                ))
+            }
+        };
+
+        match find_on_path(&command) {
+            Some(path) => CheckResult::Pass(format!("{} found at {}", command, path.display())),
+            None => CheckResult::Fail(errors::user(
+                &format!("The command '{}' used by the '{}' app could not be found.", command, app.get_name()),
+                "Make sure that the application is installed and that its folder is on your PATH, or use the full path to it in your config file.",
+            )),
+        }
+    }
+
+    async fn check_git(&self) -> CheckResult {
+        let version = match git::git_version().await {
+            Ok(version) => version,
+            Err(err) => {
+                return CheckResult::Fail(errors::user_with_internal(
+                    "We could not run Git.",
+                    "Make sure that Git is installed and that it is on your PATH.",
+                    err,
+                ))
+            }
+        };
+
+        let mut parts = version
+            .split('.')
+            .map(|p| p.parse::<u32>().unwrap_or_default());
+        let installed = (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+        );
+
+        if installed >= MIN_GIT_VERSION {
+            CheckResult::Pass(format!("version {}", version))
+        } else {
+            CheckResult::Fail(errors::user(
+                &format!(
+                    "You have Git {} installed, but Git-Tool needs Git {}.{} or newer.",
+                    version, MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+                ),
+                "Please update Git to the latest version.",
+            ))
+        }
+    }
+
+    async fn check_tokens<C: Core>(&self, core: &C) -> Vec<(String, CheckResult)> {
+        let online_services = online::services::<C>();
+        let mut results = vec![];
+
+        for svc in core.config().get_services() {
+            let online_service = match online_services.iter().find(|s| s.handles(svc)) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            for helpers::ServiceToken {
+                label,
+                token_name,
+                auth_args,
+            } in helpers::get_service_tokens(svc)
+            {
+                let name = format!("Access token {}", label);
+
+                if core.keychain().get_token(&token_name).is_err() {
+                    results.push((name, CheckResult::Warn(errors::user(
+                        "No access token has been configured, so Git-Tool will not be able to create repositories for you.",
+                        &format!("Add an access token with `git-tool auth {}`.", auth_args),
+                    ))));
+                    continue;
+                }
+
+                let result = match online_service.token_status(core, &token_name).await {
+                    Ok(status) if status.missing_scopes.is_empty() => {
+                        CheckResult::Pass(format!("valid for {}", status.login))
+                    }
+                    Ok(status) => CheckResult::Warn(errors::user(
+                        &format!(
+                            "The access token for {} is missing the {} scope(s).",
+                            status.login,
+                            status.missing_scopes.join(", ")
+                        ),
+                        &format!(
+                            "Create a new access token with these scopes and add it with `git-tool auth {}`.",
+                            auth_args
+                        ),
+                    )),
+                    Err(err) => CheckResult::Fail(err),
+                };
+
+                results.push((name, result));
+            }
+        }
+
+        results
+    }
+
+    async fn check_ssh_agent<C: Core>(&self, core: &C) -> CheckResult {
+        if core.config().get_features().use_http_transport() {
+            return CheckResult::Pass("not needed when using HTTP transport".to_string());
+        }
+
+        if std::env::var_os("SSH_AUTH_SOCK").is_none() && !cfg!(windows) {
+            return CheckResult::Warn(errors::user(
+                "No SSH agent is running, so you may be prompted for your SSH key's passphrase when cloning.",
+                "Start an SSH agent with `eval $(ssh-agent)` and add your key with `ssh-add`, or enable the `http_transport` feature.",
+            ));
+        }
+
+        match tokio::process::Command::new("ssh-add")
+            .arg("-l")
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {
+                let keys = String::from_utf8_lossy(&output.stdout).lines().count();
+                CheckResult::Pass(format!("{} key(s) loaded", keys))
+            }
+            Ok(output) if output.status.code() == Some(1) => CheckResult::Warn(errors::user(
+                "Your SSH agent does not have any keys loaded.",
+                "Add your SSH key to the agent with `ssh-add`.",
+            )),
+            Ok(output) => CheckResult::Warn(errors::user_with_internal(
+                "We could not connect to your SSH agent.",
+                "Make sure that your SSH agent is running and that SSH_AUTH_SOCK points to it.",
+                errors::detailed_message(String::from_utf8_lossy(&output.stderr).trim()),
+            )),
+            Err(err) => CheckResult::Warn(errors::user_with_internal(
+                "We could not run `ssh-add` to check your SSH agent.",
+                "Make sure that OpenSSH is installed and that it is on your PATH, or enable the `http_transport` feature.",
+                err,
+            )),
+        }
+    }
+}
+
+/// Finds the executable for a command, searching the directories on your PATH if it is not a path itself.
+fn find_on_path(command: &str) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return if path.is_file() {
+            Some(path.to_path_buf())
+        } else {
+            None
+        };
+    }
+
+    let extensions: Vec<String> = if cfg!(windows) {
+        let mut exts: Vec<String> = std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|e| e.to_string())
+            .collect();
+        exts.insert(0, String::new());
+        exts
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", command, ext)))
+        })
+        .find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder};
+    use super::*;
+
+    #[tokio::test]
+    async fn run() {
+        let temp = tempfile::tempdir().unwrap();
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: "{}"
+services:
+  - domain: github.com
+    website: "https://{{{{ .Service.Domain }}}}/{{{{ .Repo.FullName }}}}"
+    httpUrl: "https://{{{{ .Service.Domain }}}}/{{{{ .Repo.FullName }}}}.git"
+    gitUrl: "git@{{{{ .Service.Domain }}}}:{{{{ .Repo.FullName }}}}.git"
+    pattern: "*/*"
+  - domain: broken.example.com
+    website: "https://{{{{ .Service.Domain"
+    httpUrl: "https://{{{{ .Service.Domain }}}}/{{{{ .Repo.FullName }}}}.git"
+    gitUrl: "git@{{{{ .Service.Domain }}}}:{{{{ .Repo.FullName }}}}.git"
+    pattern: "*/*/*"
+apps:
+  - name: shell
+    command: git
+  - name: missing
+    command: git-tool-missing-command
+features:
+  http_transport: true
+"#,
+            temp.path().to_str().unwrap().replace("\\", "\\\\")
+        ))
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let cmd = DoctorCommand {};
+        let args = cmd.app().get_matches_from(vec!["doctor"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(status) => assert_eq!(status, 1, "the broken config should fail"),
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let output = core.output().to_string();
+        println!("{}", output);
+
+        assert!(output.contains("[warn] Config file: "));
+        assert!(output.contains(&format!(
+            "[pass] Dev directory: {} exists and is writable\n",
+            temp.path().display()
+        )));
+        assert!(output.contains("[pass] Service github.com: templates render correctly\n"));
+        assert!(output.contains("[fail] Service broken.example.com: "));
+        assert!(output.contains("[pass] App shell: git found at "));
+        assert!(output.contains(
+            "[fail] App missing: The command 'git-tool-missing-command' used by the 'missing' app could not be found.\n       Advice: "
+        ));
+        assert!(output.contains("[pass] Git: version "));
+        assert!(
+            output.contains("[warn] Access token github.com: No access token has been configured")
+        );
+        assert!(output.contains("[pass] SSH agent: not needed when using HTTP transport\n"));
+    }
+
+    #[tokio::test]
+    async fn run_missing_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let cfg = Config::from_str(&format!(
+            "directory: {}\ndirectories:\n  - {}\n",
+            temp.path().display(),
+            temp.path().join("missing").display()
+        ))
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .with_mock_keychain(|_| {})
+            .build();
+
+        let cmd = DoctorCommand {};
+        let results = cmd.check_dev_directories(&core);
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0].1, CheckResult::Pass(_)));
+        match &results[1].1 {
+            CheckResult::Fail(err) => assert!(
+                err.advice().contains("`directories` field"),
+                "{}",
+                err.message()
+            ),
+            _ => panic!("the missing directory should fail the check"),
+        }
+    }
+}
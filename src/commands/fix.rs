@@ -15,7 +15,7 @@ impl Command for FixCommand {
             .version("1.0")
             .about("fixes the remote configuration for a repository")
             .visible_alias("i")
-            .long_about("Updates the remote configuration for a repository to match its directory location.")
+            .long_about("Updates the remote configuration for a repository to match its directory location, and re-applies the Git configuration for its service and account.")
             .arg(Arg::new("repo")
                     .about("The name of the repository to fix.")
                     .index(1))
@@ -35,6 +35,7 @@ impl<C: Core> CommandRunnable<C> for FixCommand {
     async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
         let tasks = sequence![
             GitRemote { name: "origin" },
+            GitConfig {},
             CreateRemote {
                 enabled: !matches.is_present("no-create-remote")
             }
@@ -1,5 +1,6 @@
 use crate::core::*;
 use crate::errors;
+use std::io::Write;
 
 pub enum LaunchTarget<'a> {
     AppAndTarget(&'a App, &'a str),
@@ -44,6 +45,43 @@ pub fn get_launch_app<'a, C: Core>(
     }
 }
 
+/// Asks the user to confirm an action, returning `true` only if they answer yes.
+pub fn confirm<C: Core>(core: &C, prompt: &str) -> Result<bool, errors::Error> {
+    let mut output = core.output().writer();
+    write!(output, "{} [y/N] ", prompt)?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// An access token which Git-Tool uses for a service, along with how to describe it and the
+/// arguments you would pass to `git-tool auth` to set it.
+pub struct ServiceToken {
+    pub label: String,
+    pub token_name: String,
+    pub auth_args: String,
+}
+
+/// Gets the access tokens used for a service: its default token and one for each of its accounts.
+pub fn get_service_tokens(svc: &Service) -> Vec<ServiceToken> {
+    let mut tokens = vec![ServiceToken {
+        label: svc.get_domain(),
+        token_name: svc.get_domain(),
+        auth_args: svc.get_domain(),
+    }];
+
+    tokens.extend(svc.get_accounts().map(|a| ServiceToken {
+        label: format!("{} ({})", svc.get_domain(), a.get_name()),
+        token_name: a.get_token_name(svc),
+        auth_args: format!("{} --account {}", svc.get_domain(), a.get_name()),
+    }));
+
+    tokens
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
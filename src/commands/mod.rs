@@ -12,11 +12,14 @@ use crate::{
     core::{Core, DefaultCore, KeyChain, Launcher, Output, Resolver},
 };
 
+mod adopt;
 mod apps;
 mod auth;
 mod branch;
 mod complete;
 mod config;
+mod credential;
+mod doctor;
 mod fix;
 mod helpers;
 mod ignore;
@@ -24,11 +27,14 @@ mod info;
 mod list;
 mod new;
 mod open;
+mod registry;
+mod relocate;
 mod scratch;
 mod services;
 mod shell_init;
 mod switch;
 mod update;
+mod worktree;
 
 pub trait Command: Send + Sync {
     fn name(&self) -> String;
@@ -47,21 +53,27 @@ pub fn default_commands() -> Vec<Arc<dyn CommandRunnable<DefaultCore>>> {
 
 pub fn commands<C: Core>() -> Vec<Arc<dyn CommandRunnable<C>>> {
     vec![
+        Arc::new(adopt::AdoptCommand {}),
         Arc::new(apps::AppsCommand {}),
         Arc::new(auth::AuthCommand {}),
         Arc::new(branch::BranchCommand {}),
         Arc::new(complete::CompleteCommand {}),
         Arc::new(config::ConfigCommand {}),
+        Arc::new(credential::CredentialCommand {}),
+        Arc::new(doctor::DoctorCommand {}),
         Arc::new(fix::FixCommand {}),
         Arc::new(info::InfoCommand {}),
         Arc::new(ignore::IgnoreCommand {}),
         Arc::new(list::ListCommand {}),
         Arc::new(new::NewCommand {}),
         Arc::new(open::OpenCommand {}),
+        Arc::new(registry::RegistryCommand {}),
+        Arc::new(relocate::RelocateCommand {}),
         Arc::new(scratch::ScratchCommand {}),
         Arc::new(services::ServicesCommand {}),
         Arc::new(shell_init::ShellInitCommand {}),
         Arc::new(update::UpdateCommand {}),
         Arc::new(switch::SwitchCommand {}),
+        Arc::new(worktree::WorktreeCommand {}),
     ]
 }
@@ -0,0 +1,121 @@
+use super::*;
+use clap::{App, Arg, ArgMatches};
+use online::registry::{lint_registry, FileRegistry};
+use std::path::PathBuf;
+
+pub struct RegistryCommand {}
+
+impl Command for RegistryCommand {
+    fn name(&self) -> String {
+        String::from("registry")
+    }
+
+    fn app<'a>(&self) -> App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .about("manage a registry of config templates")
+            .long_about("Tools for maintaining a registry of the app and service templates which can be added to your config with `gt config add`.")
+            .subcommand(App::new("lint")
+                .version("1.0")
+                .about("checks the entries in a registry for problems")
+                .long_about("Loads each of the entries in a registry directory, checking that they have all of their required fields and a known platform, that their templates can be rendered and that no two entries add the same app or service.")
+                .arg(Arg::new("path")
+                    .index(1)
+                    .about("the path to the registry directory (defaults to ./registry)")))
+    }
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for RegistryCommand {
+    async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        match matches.subcommand() {
+            Some(("lint", args)) => {
+                let path = PathBuf::from(args.value_of("path").unwrap_or("registry"));
+                if !path.is_dir() {
+                    return Err(errors::user(
+                        &format!("The registry directory '{}' does not exist.", path.display()),
+                        "Make sure that you are running this command from the root of your registry's repository, or provide the path to its registry directory.",
+                    ));
+                }
+
+                let registry = FileRegistry::new(path);
+                let (count, issues) = lint_registry(core, &registry).await?;
+
+                let mut output = core.output().writer();
+                for issue in issues.iter() {
+                    writeln!(output, "- {}", issue)?;
+                }
+
+                if issues.is_empty() {
+                    writeln!(output, "All {} registry entries are valid.", count)?;
+                    Ok(0)
+                } else {
+                    writeln!(
+                        output,
+                        "Found {} problems in {} registry entries.",
+                        issues.len(),
+                        count
+                    )?;
+                    Ok(1)
+                }
+            }
+            _ => Err(errors::user(
+                "You did not specify which registry command you want to run.",
+                "Try running `gt registry lint` to check the entries in your registry.",
+            )),
+        }
+    }
+
+    async fn complete(&self, _core: &C, completer: &Completer, matches: &ArgMatches) {
+        if matches.subcommand().is_none() {
+            completer.offer("lint");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder};
+    use super::*;
+    use crate::test::get_repo_root;
+
+    #[tokio::test]
+    async fn run_lint() {
+        let cfg = Config::from_str("directory: /dev").unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let cmd = RegistryCommand {};
+        let args = cmd.app().get_matches_from(vec![
+            "registry",
+            "lint",
+            get_repo_root().join("registry").to_str().unwrap(),
+        ]);
+
+        match cmd.run(&core, &args).await {
+            Ok(code) => assert_eq!(code, 0, "{}", core.output().to_string()),
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let output = core.output().to_string();
+        assert!(
+            output.starts_with("All ") && output.ends_with(" registry entries are valid.\n"),
+            "{}",
+            output
+        );
+    }
+
+    #[tokio::test]
+    async fn run_lint_missing() {
+        let core = CoreBuilder::default().with_mock_output().build();
+
+        let cmd = RegistryCommand {};
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["registry", "lint", "/missing/registry"]);
+
+        assert!(cmd.run(&core, &args).await.is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use super::super::errors;
+use super::*;
+use crate::core::Target;
+use crate::git;
+use clap::{App, Arg, ArgMatches};
+use std::path::{Path, PathBuf};
+
+pub struct RelocateCommand {}
+
+impl Command for RelocateCommand {
+    fn name(&self) -> String {
+        String::from("relocate")
+    }
+
+    fn app<'a>(&self) -> App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .about("moves a repository into another of your development directories")
+            .long_about("Moves a repository between the development directories listed in your config file, like from the fast drive you keep your active work on to the larger one you keep the rest of your repositories on. If you don't provide a directory, the repository is moved into your primary development directory.")
+            .arg(Arg::new("repo")
+                    .about("The name of the repository you want to move.")
+                    .index(1)
+                    .required(true))
+            .arg(Arg::new("directory")
+                    .about("The development directory you want to move the repository into.")
+                    .index(2))
+    }
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for RelocateCommand {
+    async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let mut output = core.output().writer();
+
+        let name = matches.value_of("repo").ok_or(errors::user(
+            "You didn't specify the repository you want to move.",
+            "Provide the name of the repository like this: `git-tool relocate github.com/sierrasoftworks/git-tool /mnt/archive/dev`."))?;
+
+        let repo = core.resolver().get_best_repo(name)?;
+        if !repo.exists() {
+            return Err(errors::user(
+                &format!("The repository {}/{} has not been cloned yet.", repo.get_domain(), repo.get_full_name()),
+                "Only repositories which are already in one of your development directories can be moved, you can clone this one with `git-tool open`."));
+        }
+
+        let dirs = core.config().get_dev_directories();
+        let target = match matches.value_of("directory") {
+            Some(dir) => find_dev_directory(&dirs, Path::new(dir)).ok_or(errors::user(
+                &format!(
+                    "The directory '{}' is not one of your development directories.",
+                    dir
+                ),
+                &format!(
+                    "Choose one of the development directories from your config file: {}.",
+                    describe(&dirs)
+                ),
+            ))?,
+            None => dirs[0],
+        };
+
+        let current = dirs
+            .iter()
+            .filter(|dir| repo.get_path().starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+            .ok_or(errors::system(
+                &format!("We could not find the development directory which '{}' is in.", repo.get_path().display()),
+                "Please report this issue to us on GitHub, including the command you ran, so that we can troubleshoot the problem."))?;
+
+        if *current == target {
+            return Err(errors::user(
+                &format!(
+                    "The repository {}/{} is already in '{}'.",
+                    repo.get_domain(),
+                    repo.get_full_name(),
+                    target.display()
+                ),
+                "There is nothing to do here, you can open it with `git-tool open`.",
+            ));
+        }
+
+        let relative_path = repo.get_path().strip_prefix(current).map(|p| p.to_path_buf()).map_err(|e| errors::system_with_internal(
+            "We were unable to determine where the repository is within your development directory.",
+            "Please report this issue to us on GitHub, including the command you ran, so that we can troubleshoot the problem.",
+            e))?;
+        let destination = target.join(relative_path);
+
+        if destination.exists() {
+            return Err(errors::user(
+                &format!("The directory '{}' already exists.", destination.display()),
+                "Git-Tool will not overwrite an existing repository. Please remove or rename the existing directory if you would like to move this repository there instead."));
+        }
+
+        // Worktrees record the absolute path of the repository they belong to, so moving it would break them.
+        if repo.get_path().join(".git").is_dir()
+            && git::git_worktree_list(&repo.get_path()).await?.len() > 1
+        {
+            return Err(errors::user(
+                &format!("The repository {}/{} has worktrees which would be broken by moving it.", repo.get_domain(), repo.get_full_name()),
+                "Remove its worktrees with `git-tool worktree remove` before moving it, and add them again afterwards."));
+        }
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        crate::fs::move_dir(&repo.get_path(), &destination).map_err(|e| errors::user_with_internal(
+            &format!("We could not move '{}' to '{}'.", repo.get_path().display(), destination.display()),
+            "Make sure that you have permission to move this directory and that none of its files are open in another program.",
+            e))?;
+
+        writeln!(
+            output,
+            "Moved {}/{} to {}",
+            repo.get_domain(),
+            repo.get_full_name(),
+            destination.display()
+        )?;
+
+        Ok(0)
+    }
+
+    async fn complete(&self, core: &C, completer: &Completer, matches: &ArgMatches) {
+        if matches.is_present("repo") {
+            completer.offer_many(
+                core.config()
+                    .get_dev_directories()
+                    .iter()
+                    .map(|d| d.display().to_string()),
+            );
+            return;
+        }
+
+        if let Ok(repos) = core.resolver().get_repos() {
+            completer.offer_many(
+                repos
+                    .iter()
+                    .map(|r| format!("{}/{}", r.get_domain(), r.get_full_name())),
+            );
+        }
+    }
+}
+
+fn find_dev_directory<'a>(dirs: &[&'a Path], dir: &Path) -> Option<&'a Path> {
+    let canonical = dir.canonicalize().unwrap_or_else(|_| PathBuf::from(dir));
+
+    dirs.iter()
+        .find(|d| **d == dir || d.canonicalize().map(|d| d == canonical).unwrap_or_default())
+        .copied()
+}
+
+fn describe(dirs: &[&Path]) -> String {
+    dirs.iter()
+        .map(|d| format!("'{}'", d.display()))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder};
+    use super::*;
+    use tempfile::tempdir;
+
+    fn get_config(temp: &Path) -> Config {
+        Config::from_str(&format!(
+            "directory: {}\ndirectories:\n  - {}\n",
+            temp.join("ssd").display(),
+            temp.join("hdd").display()
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn run() {
+        let cmd = RelocateCommand {};
+        let temp = tempdir().unwrap();
+        let ssd = temp.path().join("ssd");
+        let hdd = temp.path().join("hdd");
+
+        let repo = ssd
+            .join("github.com")
+            .join("sierrasoftworks")
+            .join("git-tool");
+        std::fs::create_dir_all(&repo).unwrap();
+        git::git_init(&repo).await.unwrap();
+        std::fs::create_dir_all(&hdd).unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec![
+            "relocate",
+            "github.com/sierrasoftworks/git-tool",
+            hdd.to_str().unwrap(),
+        ]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        assert!(!repo.exists(), "the repository should be moved");
+        assert!(hdd
+            .join("github.com")
+            .join("sierrasoftworks")
+            .join("git-tool")
+            .join(".git")
+            .exists());
+        assert!(core
+            .output()
+            .to_string()
+            .contains("Moved github.com/sierrasoftworks/git-tool"));
+
+        let found = core
+            .resolver()
+            .get_best_repo("github.com/sierrasoftworks/git-tool")
+            .unwrap();
+        assert_eq!(
+            found.get_path(),
+            hdd.join("github.com")
+                .join("sierrasoftworks")
+                .join("git-tool")
+        );
+
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the repository is already in the target directory");
+
+        // Without a directory, the repository is moved back to your primary dev directory.
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["relocate", "github.com/sierrasoftworks/git-tool"]);
+
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        assert!(repo.join(".git").exists());
+    }
+
+    #[tokio::test]
+    async fn run_unknown_directory() {
+        let cmd = RelocateCommand {};
+        let temp = tempdir().unwrap();
+
+        let repo = temp
+            .path()
+            .join("ssd")
+            .join("github.com")
+            .join("sierrasoftworks")
+            .join("git-tool");
+        std::fs::create_dir_all(&repo).unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec![
+            "relocate",
+            "github.com/sierrasoftworks/git-tool",
+            temp.path().join("elsewhere").to_str().unwrap(),
+        ]);
+
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the target should be one of your dev directories");
+        assert!(repo.exists(), "the repository should not be moved");
+    }
+}
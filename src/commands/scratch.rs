@@ -1,7 +1,16 @@
 use super::async_trait;
+use super::online::gitignore;
 use super::*;
-use super::{core::Target, tasks, tasks::Task, Command};
+use super::{
+    core::{Repo, RetentionAction, RetentionPolicy, Scratchpad, Target},
+    tasks,
+    tasks::Task,
+    Command,
+};
+use crate::git;
 use clap::{App, Arg, ArgMatches};
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 pub struct ScratchCommand {}
 
@@ -25,12 +34,237 @@ impl Command for ScratchCommand {
                     .about("The name of the scratchpad to open.")
                     .index(2),
             )
+            .subcommand(
+                App::new("list")
+                    .version("1.0")
+                    .visible_alias("ls")
+                    .about("lists your scratchpads")
+                    .long_about("Lists your scratchpads along with their size and when they were last modified, including those which have been archived."),
+            )
+            .subcommand(
+                App::new("rm")
+                    .version("1.0")
+                    .about("removes scratchpads")
+                    .long_about("Removes scratchpads, and everything in them, once you have confirmed it.")
+                    .arg(
+                        Arg::new("scratchpad")
+                            .about("The names of the scratchpads to remove.")
+                            .multiple(true)
+                            .required(true),
+                    )
+                    .arg(yes_arg()),
+            )
+            .subcommand(
+                App::new("archive")
+                    .version("1.0")
+                    .about("compresses scratchpads which you no longer need")
+                    .long_about("Compresses scratchpads into `$NAME.tar.gz` files in your scratchpads directory (using `tar`), removing the original folders.")
+                    .arg(
+                        Arg::new("scratchpad")
+                            .about("The names of the scratchpads to archive.")
+                            .multiple(true)
+                            .required(true),
+                    ),
+            )
+            .subcommand(
+                App::new("prune")
+                    .version("1.0")
+                    .about("archives or removes old scratchpads")
+                    .long_about("Archives or removes the scratchpads which haven't been modified within the retention period set by the `scratchpad_retention` section of your config file (or by --weeks). Your current scratchpad is always kept. This also happens automatically whenever a new scratchpad is created, if you have configured a retention policy.")
+                    .arg(
+                        Arg::new("weeks")
+                            .long("weeks")
+                            .about("The number of weeks to keep scratchpads for, instead of the one from your config file.")
+                            .value_name("WEEKS")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        Arg::new("delete")
+                            .long("delete")
+                            .about("removes old scratchpads instead of archiving them."),
+                    )
+                    .arg(
+                        Arg::new("dry-run")
+                            .long("dry-run")
+                            .about("shows the scratchpads which would be pruned without changing them."),
+                    )
+                    .arg(yes_arg()),
+            )
+            .subcommand(
+                App::new("promote")
+                    .version("1.0")
+                    .about("turns a scratchpad into a new repository")
+                    .long_about("Moves the contents of a scratchpad into a new repository, setting up its remote (and creating it on supported services) in the same way as `git-tool new` before making an initial commit of everything in it.")
+                    .arg(
+                        Arg::new("scratchpad")
+                            .about("The name of the scratchpad to promote.")
+                            .index(1)
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("repo")
+                            .about("The name of the repository to create.")
+                            .index(2)
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("ignore")
+                            .long("ignore")
+                            .short('i')
+                            .about("adds a .gitignore file for these languages, using the gitignore.io API.")
+                            .value_name("LANGUAGE")
+                            .takes_value(true)
+                            .multiple_occurrences(true)
+                            .use_delimiter(true),
+                    )
+                    .arg(
+                        Arg::new("open")
+                            .long("open")
+                            .short('o')
+                            .about("opens the repository in your default application after it is created."),
+                    )
+                    .arg(
+                        Arg::new("no-create-remote")
+                            .long("no-create-remote")
+                            .short('R')
+                            .about("prevent the creation of a remote repository (on supported services)"),
+                    ),
+            )
     }
 }
 
 #[async_trait]
 impl<C: Core> CommandRunnable<C> for ScratchCommand {
     async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        match matches.subcommand() {
+            Some(("list", _args)) => self.list(core),
+            Some(("rm", args)) => {
+                let scratchpads = get_existing(core, args)?;
+
+                let mut output = core.output().writer();
+                for scratchpad in scratchpads.iter() {
+                    writeln!(output, "{}", describe(scratchpad))?;
+                }
+
+                if !args.is_present("yes")
+                    && !helpers::confirm(
+                        core,
+                        &format!("Remove {} scratchpads?", scratchpads.len()),
+                    )?
+                {
+                    return Ok(0);
+                }
+
+                for scratchpad in scratchpads.iter() {
+                    remove(scratchpad)?;
+                    writeln!(output, "Removed {}", scratchpad.get_name())?;
+                }
+
+                Ok(0)
+            }
+            Some(("archive", args)) => {
+                let mut output = core.output().writer();
+                for scratchpad in get_existing(core, args)?.iter() {
+                    let path = archive(scratchpad).await?;
+                    writeln!(
+                        output,
+                        "Archived {} to {}",
+                        scratchpad.get_name(),
+                        path.display()
+                    )?;
+                }
+
+                Ok(0)
+            }
+            Some(("prune", args)) => {
+                let mut policy = match (core.config().get_scratch_retention(), args.value_of("weeks")) {
+                    (_, Some(weeks)) => RetentionPolicy::new(
+                        weeks.parse().map_err(|e| errors::user_with_internal(
+                            &format!("The number of weeks '{}' is not valid.", weeks),
+                            "Provide the number of weeks you want to keep your scratchpads for as a whole number, like `--weeks 8`.",
+                            e))?,
+                        core.config().get_scratch_retention().map(|p| p.get_action()).unwrap_or_default(),
+                    ),
+                    (Some(policy), None) => policy.clone(),
+                    (None, None) => return Err(errors::user(
+                        "You have not configured how long your scratchpads should be kept for.",
+                        "Add a `scratchpad_retention` section with the number of `weeks` to keep them for to your config file, or use `gt scratch prune --weeks 8`.")),
+                };
+
+                if args.is_present("delete") {
+                    policy = RetentionPolicy::new(policy.get_weeks(), RetentionAction::Delete);
+                }
+
+                let expired = get_expired(core, &policy)?;
+                if expired.is_empty() {
+                    writeln!(core.output().writer(), "There are no scratchpads to prune.")?;
+                    return Ok(0);
+                }
+
+                {
+                    let mut output = core.output().writer();
+                    for scratchpad in expired.iter() {
+                        writeln!(output, "{}", describe(scratchpad))?;
+                    }
+                }
+
+                if args.is_present("dry-run") {
+                    return Ok(0);
+                }
+
+                let prompt = match policy.get_action() {
+                    RetentionAction::Archive => format!("Archive {} scratchpads?", expired.len()),
+                    RetentionAction::Delete => format!("Remove {} scratchpads?", expired.len()),
+                };
+
+                if !args.is_present("yes") && !helpers::confirm(core, &prompt)? {
+                    return Ok(0);
+                }
+
+                prune(core, &policy, &expired).await?;
+                Ok(0)
+            }
+            Some(("promote", args)) => self.promote(core, args).await,
+            _ => self.open(core, matches).await,
+        }
+    }
+
+    async fn complete(&self, core: &C, completer: &Completer, matches: &ArgMatches) {
+        match matches.subcommand() {
+            Some(("list", _)) => {}
+            Some(("prune", _)) => {
+                completer.offer_many(vec!["--weeks", "--delete", "--dry-run", "--yes"]);
+            }
+            Some(("promote", args)) => {
+                completer.offer_many(vec!["--ignore", "--open", "--no-create-remote"]);
+                if !args.is_present("scratchpad") {
+                    if let Ok(pads) = core.resolver().get_scratchpads() {
+                        completer.offer_many(pads.iter().map(|p| p.get_name()));
+                    }
+                }
+            }
+            Some(("rm", _)) | Some(("archive", _)) => {
+                if let Ok(pads) = core.resolver().get_scratchpads() {
+                    completer.offer_many(pads.iter().map(|p| p.get_name()));
+                }
+            }
+            _ => {
+                completer.offer_many(vec!["list", "rm", "archive", "prune", "promote"]);
+                completer.offer_many(core.config().get_apps().map(|a| a.get_name()));
+
+                match core.resolver().get_scratchpads() {
+                    Ok(pads) => {
+                        completer.offer_many(pads.iter().map(|p| p.get_name()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl ScratchCommand {
+    async fn open<C: Core>(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
         let (app, scratchpad) = match helpers::get_launch_app(
             core,
             matches.value_of("app"),
@@ -58,6 +292,11 @@ impl<C: Core> CommandRunnable<C> for ScratchCommand {
         };
 
         if !scratchpad.exists() {
+            // Creating a new scratchpad is a good time to clean up the old ones.
+            if let Some(policy) = core.config().get_scratch_retention() {
+                auto_prune(core, policy, &scratchpad).await?;
+            }
+
             let task = tasks::NewFolder {};
             task.apply_scratchpad(core, &scratchpad).await?;
         }
@@ -66,16 +305,424 @@ impl<C: Core> CommandRunnable<C> for ScratchCommand {
         return Ok(status);
     }
 
-    async fn complete(&self, core: &C, completer: &Completer, _matches: &ArgMatches) {
-        completer.offer_many(core.config().get_apps().map(|a| a.get_name()));
+    async fn promote<C: Core>(&self, core: &C, args: &ArgMatches) -> Result<i32, errors::Error> {
+        let scratchpad = core
+            .resolver()
+            .get_scratchpad(args.value_of("scratchpad").unwrap_or_default())?;
+        if !scratchpad.exists() {
+            return Err(errors::user(
+                &format!("The scratchpad '{}' does not exist.", scratchpad.get_name()),
+                "You can see your scratchpads by running `git-tool scratch list`.",
+            ));
+        }
+
+        let repo = core
+            .resolver()
+            .get_best_repo(args.value_of("repo").unwrap_or_default())?;
+        if repo.exists() {
+            return Err(errors::user(
+                &format!("The directory '{}' already exists.", repo.get_path().display()),
+                "Git-Tool will not overwrite an existing repository. Please choose a different name for the repository you want to create.",
+            ));
+        }
+
+        // We fetch the .gitignore file before moving anything so that a language which can't be found
+        // doesn't leave the scratchpad half promoted.
+        let languages: Vec<&str> = args.values_of("ignore").into_iter().flatten().collect();
+        let gitignore = if languages.is_empty() {
+            None
+        } else {
+            let existing = tokio::fs::read_to_string(scratchpad.get_path().join(".gitignore"))
+                .await
+                .unwrap_or_default();
+            Some(gitignore::add_or_update(core, &existing, languages).await?)
+        };
+
+        let is_repo = scratchpad.get_path().join(".git").exists();
+
+        if let Some(parent) = repo.get_path().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        crate::fs::move_dir(&scratchpad.get_path(), &repo.get_path()).map_err(|e| errors::user_with_internal(
+            &format!("We could not move '{}' to '{}'.", scratchpad.get_path().display(), repo.get_path().display()),
+            "Make sure that you have permission to move this directory and that none of its files are open in another program.",
+            e))?;
+
+        // Once the files have been moved, stopping part way would leave them in a repository which isn't
+        // fully set up, so we let you know where they are and how to finish the job.
+        if let Err(err) = self
+            .setup_promoted(core, args, &repo, is_repo, gitignore)
+            .await
+        {
+            writeln!(
+                core.output().writer(),
+                "The files from {} have been moved to '{}', but we couldn't finish setting it up as a repository. Once you've fixed the problem below, run `git-tool fix {}/{}` to configure it and commit your changes.",
+                scratchpad.get_name(),
+                repo.get_path().display(),
+                repo.get_domain(),
+                repo.get_full_name()
+            )?;
+
+            return Err(err);
+        }
+
+        writeln!(
+            core.output().writer(),
+            "Promoted {} to {}/{}",
+            scratchpad.get_name(),
+            repo.get_domain(),
+            repo.get_full_name()
+        )?;
+
+        if args.is_present("open") || core.config().get_features().open_new_repo_in_default_app() {
+            let app = core.config().get_default_app().ok_or(errors::user(
+                "No default application available.",
+                "Make sure that you add an app to your config file using 'git-tool config add apps/bash' or similar."))?;
+
+            let status = core.launcher().run(app, &repo).await?;
+            return Ok(status);
+        }
+
+        Ok(0)
+    }
+
+    async fn setup_promoted<C: Core>(
+        &self,
+        core: &C,
+        args: &ArgMatches,
+        repo: &Repo,
+        is_repo: bool,
+        gitignore: Option<String>,
+    ) -> Result<(), errors::Error> {
+        // Scratchpads which you've already been using Git in keep their history and branches.
+        if is_repo {
+            tasks::GitConfig {}.apply_repo(core, repo).await?;
+        } else {
+            tasks::GitInit {}.apply_repo(core, repo).await?;
+        }
+
+        tasks::GitRemote { name: "origin" }
+            .apply_repo(core, repo)
+            .await?;
 
-        match core.resolver().get_scratchpads() {
-            Ok(pads) => {
-                completer.offer_many(pads.iter().map(|p| p.get_name()));
+        if !is_repo {
+            tasks::GitCheckout { branch: "main" }
+                .apply_repo(core, repo)
+                .await?;
+        }
+
+        if let Some(content) = gitignore {
+            tasks::WriteFile {
+                path: PathBuf::from(".gitignore"),
+                content: &content,
+            }
+            .apply_repo(core, repo)
+            .await?;
+        }
+
+        if !git::backend(core.config())
+            .status(&repo.get_path())
+            .await?
+            .is_empty()
+        {
+            tasks::GitAdd { paths: vec!["."] }
+                .apply_repo(core, repo)
+                .await?;
+            tasks::GitCommit {
+                message: "Initial commit",
+                paths: vec![],
             }
-            _ => {}
+            .apply_repo(core, repo)
+            .await?;
+        }
+
+        tasks::CreateRemote {
+            enabled: !args.is_present("no-create-remote"),
         }
+        .apply_repo(core, repo)
+        .await?;
+
+        Ok(())
     }
+
+    fn list<C: Core>(&self, core: &C) -> Result<i32, errors::Error> {
+        let dir = core.config().get_scratch_directory();
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut entries = vec![];
+        for scratchpad in core.resolver().get_scratchpads()? {
+            entries.push((scratchpad.get_name(), scratchpad.get_path(), ""));
+        }
+
+        for entry in dir.read_dir()? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(ARCHIVE_EXTENSION))
+                .map(|n| n.to_string());
+
+            if let Some(name) = name {
+                if path.is_file() {
+                    entries.push((name, path, "  (archived)"));
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut output = core.output().writer();
+        for (name, path, note) in entries {
+            let (size, modified) = crate::fs::get_usage(&path)?;
+            writeln!(
+                output,
+                "{:<12} {:>9}  {}{}",
+                name,
+                format_size(size),
+                format_time(modified),
+                note
+            )?;
+        }
+
+        Ok(0)
+    }
+}
+
+const ARCHIVE_EXTENSION: &str = ".tar.gz";
+
+fn yes_arg<'a>() -> Arg<'a> {
+    Arg::new("yes")
+        .long("yes")
+        .short('y')
+        .about("makes the changes without asking for confirmation first.")
+}
+
+/// Gets the scratchpads named in a command's arguments, making sure that each of them exists.
+fn get_existing<C: Core>(core: &C, args: &ArgMatches) -> Result<Vec<Scratchpad>, errors::Error> {
+    let mut scratchpads = vec![];
+    for name in args.values_of("scratchpad").into_iter().flatten() {
+        let scratchpad = core.resolver().get_scratchpad(name)?;
+        if !scratchpad.exists() {
+            return Err(errors::user(
+                &format!("The scratchpad '{}' does not exist.", name),
+                "You can see your scratchpads by running `git-tool scratch list`.",
+            ));
+        }
+
+        scratchpads.push(scratchpad);
+    }
+
+    Ok(scratchpads)
+}
+
+/// Gets the scratchpads which have outlived a retention policy, other than the current one.
+fn get_expired<C: Core>(
+    core: &C,
+    policy: &RetentionPolicy,
+) -> Result<Vec<Scratchpad>, errors::Error> {
+    if !core.config().get_scratch_directory().is_dir() {
+        return Ok(vec![]);
+    }
+
+    let current = core.resolver().get_current_scratchpad()?;
+    let now = SystemTime::now();
+
+    let mut expired = vec![];
+    for scratchpad in core.resolver().get_scratchpads()? {
+        if scratchpad.get_name() == current.get_name() {
+            continue;
+        }
+
+        let (_, modified) = crate::fs::get_usage(&scratchpad.get_path())?;
+        if policy.is_expired(modified, now) {
+            expired.push(scratchpad);
+        }
+    }
+
+    expired.sort_by_key(|p| p.get_name());
+    Ok(expired)
+}
+
+async fn prune<C: Core>(
+    core: &C,
+    policy: &RetentionPolicy,
+    scratchpads: &[Scratchpad],
+) -> Result<(), errors::Error> {
+    let mut output = core.output().writer();
+    for scratchpad in scratchpads.iter() {
+        match policy.get_action() {
+            RetentionAction::Archive => {
+                let path = archive(scratchpad).await?;
+                writeln!(
+                    output,
+                    "Archived {} to {}",
+                    scratchpad.get_name(),
+                    path.display()
+                )?;
+            }
+            RetentionAction::Delete => {
+                remove(scratchpad)?;
+                writeln!(output, "Removed {}", scratchpad.get_name())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Cleans up your old scratchpads when a new one is created. Problems are reported without
+/// stopping the new scratchpad from being opened, and scratchpads are only ever removed once
+/// you have confirmed it with `gt scratch prune`.
+async fn auto_prune<C: Core>(
+    core: &C,
+    policy: &RetentionPolicy,
+    opening: &Scratchpad,
+) -> Result<(), errors::Error> {
+    let mut output = core.output().writer();
+
+    let expired: Vec<Scratchpad> = match get_expired(core, policy) {
+        Ok(expired) => expired
+            .into_iter()
+            .filter(|p| p.get_name() != opening.get_name())
+            .collect(),
+        Err(e) => {
+            writeln!(
+                output,
+                "Warning: We could not check for old scratchpads: {}",
+                e
+            )?;
+            return Ok(());
+        }
+    };
+
+    if expired.is_empty() {
+        return Ok(());
+    }
+
+    match policy.get_action() {
+        RetentionAction::Archive => {
+            for scratchpad in expired.iter() {
+                match archive(scratchpad).await {
+                    Ok(path) => writeln!(
+                        output,
+                        "Archived {} to {}",
+                        scratchpad.get_name(),
+                        path.display()
+                    )?,
+                    Err(e) => writeln!(
+                        output,
+                        "Warning: could not archive {}.\n{}",
+                        scratchpad.get_name(),
+                        e
+                    )?,
+                }
+            }
+        }
+        RetentionAction::Delete => {
+            writeln!(
+                output,
+                "You have {} scratchpads which are older than your retention policy, run `gt scratch prune` to remove them.",
+                expired.len()
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(scratchpad: &Scratchpad) -> Result<(), errors::Error> {
+    std::fs::remove_dir_all(scratchpad.get_path()).map_err(|e| {
+        errors::user_with_internal(
+            &format!("We could not remove the scratchpad '{}'.", scratchpad.get_name()),
+            "Make sure that you have permission to remove it and that none of its files are open in another program.",
+            e,
+        )
+    })
+}
+
+/// Compresses a scratchpad into an archive alongside it, removing the original folder.
+async fn archive(scratchpad: &Scratchpad) -> Result<PathBuf, errors::Error> {
+    let path = scratchpad.get_path();
+    let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    let archive = dir.join(format!("{}{}", scratchpad.get_name(), ARCHIVE_EXTENSION));
+
+    if archive.exists() {
+        return Err(errors::user(
+            &format!("The archive '{}' already exists.", archive.display()),
+            "Git-Tool will not overwrite an existing archive. Please move or rename it if you would like to archive this scratchpad again.",
+        ));
+    }
+
+    let result = tokio::process::Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&dir)
+        .arg(scratchpad.get_name())
+        .output()
+        .await
+        .map_err(|e| {
+            errors::user_with_internal(
+                &format!(
+                    "We could not run `tar` to archive the scratchpad '{}'.",
+                    scratchpad.get_name()
+                ),
+                "Make sure that `tar` is installed and available on your $PATH.",
+                e,
+            )
+        })?;
+
+    if !result.status.success() {
+        std::fs::remove_file(&archive).ok();
+        return Err(errors::system(
+            &format!(
+                "We could not archive the scratchpad '{}': {}",
+                scratchpad.get_name(),
+                String::from_utf8_lossy(&result.stderr).trim()
+            ),
+            "Make sure that you have permission to read the scratchpad and to create files in your scratchpads directory.",
+        ));
+    }
+
+    remove(scratchpad)?;
+    Ok(archive)
+}
+
+fn describe(scratchpad: &Scratchpad) -> String {
+    match crate::fs::get_usage(&scratchpad.get_path()) {
+        Ok((size, modified)) => format!(
+            "{} ({}, last modified {})",
+            scratchpad.get_name(),
+            format_size(size),
+            format_time(modified)
+        ),
+        Err(_) => scratchpad.get_name(),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    let units = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, units[unit])
+}
+
+fn format_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
 }
 
 #[cfg(test)]
@@ -355,4 +1002,368 @@ apps:
             Err(err) => panic!(err.message()),
         }
     }
+
+    #[tokio::test]
+    async fn run_new_scratchpad_auto_prune() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        std::fs::create_dir_all(scratch.join("2020w02")).unwrap();
+        std::fs::write(scratch.join("2020w02.tar.gz"), "").unwrap();
+
+        let get_core = |action: &str| {
+            CoreBuilder::default()
+                .with_config(
+                    &Config::from_str(&format!(
+                        "directory: {}\nscratchpads: {}\nscratchpad_retention:\n  weeks: 0\n  action: {}\n",
+                        temp.path().display(),
+                        scratch.display(),
+                        action
+                    ))
+                    .unwrap(),
+                )
+                .with_mock_output()
+                .with_mock_launcher(|_| {})
+                .build()
+        };
+
+        // Scratchpads are only removed once you have confirmed it with `gt scratch prune`.
+        let core = get_core("delete");
+        let args = cmd.app().get_matches_from(vec!["scratch", "2020w07"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(scratch.join("2020w01").exists());
+        assert!(core.output().to_string().contains("`gt scratch prune`"));
+        assert_eq!(core.launcher().launches.lock().await.len(), 1);
+
+        // Failing to archive one scratchpad doesn't stop the others, or the new one from opening.
+        let core = get_core("archive");
+        let args = cmd.app().get_matches_from(vec!["scratch", "2020w08"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(scratch.join("2020w01.tar.gz").is_file());
+        assert!(scratch.join("2020w02").exists());
+        assert!(scratch.join("2020w07.tar.gz").is_file());
+        assert!(core
+            .output()
+            .to_string()
+            .contains("Warning: could not archive 2020w02."));
+        assert_eq!(core.launcher().launches.lock().await.len(), 1);
+    }
+
+    fn get_config(temp: &std::path::Path) -> Config {
+        Config::from_str(&format!(
+            "directory: {}\nscratchpads: {}\n",
+            temp.display(),
+            temp.join("scratch").display()
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn run_list() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w02")).unwrap();
+        std::fs::write(scratch.join("2020w02").join("notes.md"), "a".repeat(2048)).unwrap();
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        std::fs::write(scratch.join("2019w50.tar.gz"), "archive").unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec!["scratch", "list"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let output = core.output().to_string();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", output);
+        assert!(lines[0].starts_with("2019w50 ") && lines[0].ends_with("(archived)"));
+        assert!(lines[1].starts_with("2020w01 ") && lines[1].contains(" 0 B "));
+        assert!(lines[2].starts_with("2020w02 ") && lines[2].contains(" 2.0 KB "));
+    }
+
+    #[tokio::test]
+    async fn run_rm_and_archive() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        std::fs::create_dir_all(scratch.join("2020w02")).unwrap();
+        std::fs::write(scratch.join("2020w02").join("notes.md"), "notes").unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["scratch", "rm", "2020w01", "--yes"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(!scratch.join("2020w01").exists());
+
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the scratchpad has already been removed");
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["scratch", "archive", "2020w02"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(!scratch.join("2020w02").exists());
+        assert!(scratch.join("2020w02.tar.gz").is_file());
+        assert!(core.output().to_string().contains("Archived 2020w02 to "));
+    }
+
+    #[tokio::test]
+    async fn run_prune() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        std::fs::create_dir_all(scratch.join("2020w02")).unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec!["scratch", "prune"]);
+        cmd.run(&core, &args)
+            .await
+            .expect_err("a retention period is required");
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["scratch", "prune", "--weeks", "8", "--yes"]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(scratch.join("2020w01").exists());
+        assert!(core
+            .output()
+            .to_string()
+            .contains("There are no scratchpads to prune."));
+
+        let args = cmd.app().get_matches_from(vec![
+            "scratch",
+            "prune",
+            "--weeks",
+            "0",
+            "--delete",
+            "--dry-run",
+        ]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(scratch.join("2020w01").exists());
+
+        let args = cmd.app().get_matches_from(vec![
+            "scratch", "prune", "--weeks", "0", "--delete", "--yes",
+        ]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+        assert!(!scratch.join("2020w01").exists());
+        assert!(!scratch.join("2020w02").exists());
+        assert!(core.output().to_string().contains("Removed 2020w01"));
+    }
+
+    #[tokio::test]
+    async fn run_promote() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01").join("src")).unwrap();
+        std::fs::write(
+            scratch.join("2020w01").join("src").join("main.rs"),
+            "fn main() {}",
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec![
+            "scratch",
+            "promote",
+            "2020w01",
+            "github.com/test/promoted",
+            "--no-create-remote",
+        ]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let repo = core
+            .resolver()
+            .get_best_repo("github.com/test/promoted")
+            .unwrap();
+        assert!(repo.valid());
+        assert!(!scratch.join("2020w01").exists());
+        assert!(repo.get_path().join("src").join("main.rs").exists());
+        assert!(git::git_status(&repo.get_path()).await.unwrap().is_empty());
+        assert_eq!(
+            git::git_remote_get_url(&repo.get_path(), "origin")
+                .await
+                .unwrap(),
+            "git@github.com:test/promoted.git"
+        );
+        assert!(core
+            .output()
+            .to_string()
+            .contains("Promoted 2020w01 to github.com/test/promoted"));
+
+        // A repository which already exists should never be overwritten.
+        std::fs::create_dir_all(scratch.join("2020w02")).unwrap();
+        let args = cmd.app().get_matches_from(vec![
+            "scratch",
+            "promote",
+            "2020w02",
+            "github.com/test/promoted",
+            "--no-create-remote",
+        ]);
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the repository already exists");
+        assert!(scratch.join("2020w02").exists());
+    }
+
+    #[tokio::test]
+    async fn run_promote_existing_repo() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        git::git_init(&scratch.join("2020w01")).await.unwrap();
+        std::fs::write(scratch.join("2020w01").join("README.md"), "# Test").unwrap();
+
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: {}
+scratchpads: {}
+services:
+  - domain: github.com
+    website: "https://{{{{ .Service.Domain }}}}/{{{{ .Repo.FullName }}}}"
+    httpUrl: "https://{{{{ .Service.Domain }}}}/{{{{ .Repo.FullName }}}}.git"
+    gitUrl: "git@{{{{ .Service.Domain }}}}:{{{{ .Repo.FullName }}}}.git"
+    pattern: "*/*"
+    gitConfig:
+      - key: core.autocrlf
+        value: "input"
+"#,
+            temp.path().display(),
+            scratch.display()
+        ))
+        .unwrap();
+        let core = CoreBuilder::default()
+            .with_config(&cfg)
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec![
+            "scratch",
+            "promote",
+            "2020w01",
+            "github.com/test/promoted",
+            "--no-create-remote",
+        ]);
+        match cmd.run(&core, &args).await {
+            Ok(_) => {}
+            Err(err) => panic!("{}", err.message()),
+        }
+
+        let repo = core
+            .resolver()
+            .get_best_repo("github.com/test/promoted")
+            .unwrap();
+        let output = std::process::Command::new("git")
+            .current_dir(repo.get_path())
+            .args(["config", "--local", "--get", "core.autocrlf"])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "input");
+    }
+
+    #[tokio::test]
+    async fn run_promote_failure() {
+        let cmd = ScratchCommand {};
+        let temp = tempfile::tempdir().unwrap();
+        let scratch = temp.path().join("scratch");
+        std::fs::create_dir_all(scratch.join("2020w01")).unwrap();
+        std::fs::write(
+            scratch.join("2020w01").join(".git"),
+            format!("gitdir: {}", temp.path().join("missing").display()),
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&get_config(temp.path()))
+            .with_mock_output()
+            .build();
+
+        let args = cmd.app().get_matches_from(vec![
+            "scratch",
+            "promote",
+            "2020w01",
+            "github.com/test/promoted",
+            "--no-create-remote",
+        ]);
+        cmd.run(&core, &args)
+            .await
+            .expect_err("the repository can't be set up");
+
+        let repo = core
+            .resolver()
+            .get_best_repo("github.com/test/promoted")
+            .unwrap();
+        assert!(repo.get_path().join(".git").exists());
+
+        let output = core.output().to_string();
+        assert!(
+            output.contains(&format!(
+                "The files from 2020w01 have been moved to '{}'",
+                repo.get_path().display()
+            )),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("`git-tool fix github.com/test/promoted`"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
 }
@@ -30,8 +30,12 @@ impl Command for SwitchCommand {
                     .long("no-create")
                     .about("don't create the branch if it doesn't exist."),
             )
+            .arg(
+                Arg::new("fetch")
+                    .long("fetch")
+                    .about("fetch the latest branches from your remotes before switching."),
+            )
     }
-
 }
 
 #[async_trait]
@@ -39,6 +43,10 @@ impl<C: Core> CommandRunnable<C> for SwitchCommand {
     async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
         let repo = core.resolver().get_current_repo()?;
 
+        if matches.is_present("fetch") {
+            git::git_fetch(&repo.get_path()).await?;
+        }
+
         match matches.value_of("branch") {
             Some(branch) => {
                 let task = tasks::GitSwitch {
@@ -49,9 +57,16 @@ impl<C: Core> CommandRunnable<C> for SwitchCommand {
                 task.apply_repo(core, &repo).await?;
             }
             None => {
-                let branches = git::git_branches(&repo.get_path()).await?;
+                let branches = git::backend(core.config())
+                    .branches(&repo.get_path())
+                    .await?;
                 for branch in branches {
-                    println!("{}", branch);
+                    writeln!(
+                        core.output().writer(),
+                        "{} {}",
+                        branch.name,
+                        branch.marker()
+                    )?;
                 }
             }
         };
@@ -61,9 +76,10 @@ impl<C: Core> CommandRunnable<C> for SwitchCommand {
 
     async fn complete(&self, core: &C, completer: &Completer, _matches: &ArgMatches) {
         if let Ok(repo) = core.resolver().get_current_repo() {
-            completer.offer("--create");
-            if let Ok(branches) = git::git_branches(&repo.get_path()).await {
-                completer.offer_many(branches);
+            completer.offer("--no-create");
+            completer.offer("--fetch");
+            if let Ok(branches) = git::backend(core.config()).branches(&repo.get_path()).await {
+                completer.offer_many(branches.into_iter().map(|b| b.name));
             }
         }
     }
@@ -173,7 +189,9 @@ mod tests {
 
         assert!(repo.valid(), "the repository should exist and be valid");
 
-        let args: ArgMatches = cmd.app().get_matches_from(vec!["switch","-N", "feature/test"]);
+        let args: ArgMatches = cmd
+            .app()
+            .get_matches_from(vec!["switch", "-N", "feature/test"]);
         cmd.run(&core, &args)
             .await
             .expect("this command should have succeeded");
@@ -228,7 +246,9 @@ mod tests {
 
         assert!(repo.valid(), "the repository should exist and be valid");
 
-        let args: ArgMatches = cmd.app().get_matches_from(vec!["switch","-N", "feature/test2"]);
+        let args: ArgMatches = cmd
+            .app()
+            .get_matches_from(vec!["switch", "-N", "feature/test2"]);
         cmd.run(&core, &args)
             .await
             .expect_err("this command should not have succeeded");
@@ -254,9 +274,7 @@ mod tests {
 
         assert!(repo.valid(), "the repository should exist and be valid");
 
-        let args: ArgMatches = cmd
-            .app()
-            .get_matches_from(vec!["switch", "feature/test"]);
+        let args: ArgMatches = cmd.app().get_matches_from(vec!["switch", "feature/test"]);
         cmd.run(&core, &args).await.unwrap();
 
         assert!(repo.valid(), "the repository should still be valid");
@@ -0,0 +1,309 @@
+use super::super::errors;
+use super::*;
+use crate::core::Target;
+use crate::git;
+use clap::{App, Arg, ArgMatches};
+use std::path::{Path, PathBuf};
+
+pub struct WorktreeCommand {}
+
+impl Command for WorktreeCommand {
+    fn name(&self) -> String {
+        String::from("worktree")
+    }
+
+    fn app<'a>(&self) -> App<'a> {
+        App::new(self.name().as_str())
+            .version("1.0")
+            .visible_alias("wt")
+            .about("manage worktrees for the current repository")
+            .long_about("Manages additional working trees for the current repository, allowing you to have several branches checked out at once. Worktrees are created alongside the repository as `$REPO@$BRANCH`, with any slashes in the branch name written as `%2F`.")
+
+            .subcommand(App::new("add")
+                .version("1.0")
+                .about("creates a worktree for a branch")
+                .long_about("Creates a new worktree for the given branch alongside the current repository. If the branch does not exist, it will be created from your current HEAD.")
+                .arg(Arg::new("branch")
+                    .index(1)
+                    .about("the name of the branch you want to check out in the worktree")
+                    .required(true)))
+
+            .subcommand(App::new("list")
+                .version("1.0")
+                .visible_alias("ls")
+                .about("lists the worktrees for the current repository"))
+
+            .subcommand(App::new("remove")
+                .version("1.0")
+                .visible_alias("rm")
+                .about("removes the worktree for a branch")
+                .arg(Arg::new("branch")
+                    .index(1)
+                    .about("the name of the branch whose worktree you want to remove")
+                    .required(true))
+                .arg(Arg::new("force")
+                    .long("force")
+                    .short('f')
+                    .about("removes the worktree even if it contains uncommitted changes")))
+    }
+}
+
+#[async_trait]
+impl<C: Core> CommandRunnable<C> for WorktreeCommand {
+    async fn run(&self, core: &C, matches: &ArgMatches) -> Result<i32, errors::Error> {
+        let repo = core.resolver().get_current_repo()?;
+        let mut output = core.output().writer();
+
+        let worktrees = git::git_worktree_list(&repo.get_path()).await?;
+
+        match matches.subcommand() {
+            Some(("add", args)) => {
+                let branch = args.value_of("branch").ok_or(errors::user(
+                    "You did not provide the name of the branch you want to create a worktree for.",
+                    "Provide the name of the branch like this: `git-tool worktree add feature/my-branch`."))?;
+
+                let main = worktrees.first().ok_or(errors::system(
+                    "Git did not report the main worktree for this repository.",
+                    "Please report this issue to us on GitHub so that we can investigate further.",
+                ))?;
+
+                if let Some(existing) = worktrees
+                    .iter()
+                    .skip(1)
+                    .find(|w| w.branch.as_deref() == Some(branch))
+                {
+                    return Err(errors::user(
+                        &format!("The branch '{}' is already checked out in the worktree at '{}'.", branch, existing.path.display()),
+                        &format!("You can use the existing worktree, or remove it with `git-tool worktree remove {}`.", branch)));
+                }
+
+                let path = worktree_path(&main.path, branch);
+                if let Some(other) = worktrees.iter().find(|w| w.path == path) {
+                    return Err(errors::user(
+                        &format!("The directory '{}' is already used by the worktree for '{}'.", path.display(), other.branch.as_deref().unwrap_or("(detached)")),
+                        "Remove the other worktree with `git-tool worktree remove`, or move it somewhere else with `git worktree move`."));
+                }
+
+                if path.exists() {
+                    return Err(errors::user(
+                        &format!("The directory '{}' already exists.", path.display()),
+                        "Git-Tool will not overwrite an existing directory. Please remove or rename it if you would like to create the worktree there instead."));
+                }
+
+                let existing = git::backend(core.config())
+                    .branches(&repo.get_path())
+                    .await?
+                    .into_iter()
+                    .find(|b| b.name == branch);
+
+                match existing {
+                    Some(git::Branch { is_local: true, .. }) => {
+                        git::git_worktree_add(&repo.get_path(), &path, branch, None).await?
+                    }
+                    Some(git::Branch {
+                        remote: Some(remote),
+                        ..
+                    }) => {
+                        git::git_worktree_add(
+                            &repo.get_path(),
+                            &path,
+                            branch,
+                            Some(&format!("{}/{}", remote, branch)),
+                        )
+                        .await?
+                    }
+                    _ => {
+                        git::git_worktree_add(&repo.get_path(), &path, branch, Some("HEAD")).await?
+                    }
+                }
+
+                writeln!(output, "{}", path.display())?;
+            }
+            Some(("remove", args)) => {
+                let branch = args.value_of("branch").ok_or(errors::user(
+                    "You did not provide the name of the branch whose worktree you want to remove.",
+                    "Provide the name of the branch like this: `git-tool worktree remove feature/my-branch`."))?;
+
+                let worktree = worktrees
+                    .iter()
+                    .skip(1)
+                    .find(|w| w.branch.as_deref() == Some(branch))
+                    .ok_or(errors::user(
+                        &format!("Could not find a worktree for the branch '{}'.", branch),
+                        "Use `git-tool worktree list` to see the worktrees for this repository.",
+                    ))?;
+
+                git::git_worktree_remove(
+                    &repo.get_path(),
+                    &worktree.path,
+                    args.is_present("force"),
+                )
+                .await?;
+            }
+            _ => {
+                for worktree in worktrees {
+                    writeln!(
+                        output,
+                        "{} {}",
+                        worktree.branch.unwrap_or_else(|| "(detached)".to_string()),
+                        worktree.path.display()
+                    )?;
+                }
+            }
+        }
+
+        Ok(0)
+    }
+
+    async fn complete(&self, core: &C, completer: &Completer, matches: &ArgMatches) {
+        match matches.subcommand() {
+            Some(("add", _)) => {
+                if let Ok(repo) = core.resolver().get_current_repo() {
+                    if let Ok(branches) =
+                        git::backend(core.config()).branches(&repo.get_path()).await
+                    {
+                        completer.offer_many(branches.into_iter().map(|b| b.name));
+                    }
+                }
+            }
+            Some(("remove", _)) => {
+                completer.offer("--force");
+                if let Ok(repo) = core.resolver().get_current_repo() {
+                    if let Ok(worktrees) = git::git_worktree_list(&repo.get_path()).await {
+                        completer
+                            .offer_many(worktrees.into_iter().skip(1).filter_map(|w| w.branch));
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                completer.offer_many(vec!["add", "list", "remove"]);
+            }
+        }
+    }
+}
+
+/// Gets the path at which the worktree for a branch should be created, which is a
+/// sibling of the repository's main worktree named `$REPO@$BRANCH`. Slashes in the branch
+/// name are percent-encoded so that each branch gets its own directory.
+fn worktree_path(main: &Path, branch: &str) -> PathBuf {
+    let name = main
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    main.with_file_name(format!(
+        "{}@{}",
+        name,
+        branch.replace('%', "%25").replace('/', "%2F")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::core::{Config, CoreBuilder, Repo};
+    use super::*;
+    use crate::tasks::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_worktree_path() {
+        assert_eq!(
+            worktree_path(
+                Path::new("/dev/github.com/sierrasoftworks/git-tool"),
+                "feature/test"
+            ),
+            PathBuf::from("/dev/github.com/sierrasoftworks/git-tool@feature%2Ftest")
+        );
+
+        // Branches which only differ by their slashes must not share a directory.
+        assert_ne!(
+            worktree_path(
+                Path::new("/dev/github.com/sierrasoftworks/git-tool"),
+                "feature/x"
+            ),
+            worktree_path(
+                Path::new("/dev/github.com/sierrasoftworks/git-tool"),
+                "feature-x"
+            )
+        );
+        assert_ne!(
+            worktree_path(
+                Path::new("/dev/github.com/sierrasoftworks/git-tool"),
+                "feature/x"
+            ),
+            worktree_path(
+                Path::new("/dev/github.com/sierrasoftworks/git-tool"),
+                "feature%2Fx"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn run() {
+        let cmd = WorktreeCommand {};
+        let temp = tempdir().unwrap();
+
+        let repo = Repo::new(
+            "github.com/sierrasoftworks/test-worktree",
+            temp.path()
+                .join("github.com")
+                .join("sierrasoftworks")
+                .join("test-worktree"),
+        );
+
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .with_mock_output()
+            .with_mock_resolver(|r| r.set_repo(repo.clone()))
+            .build();
+
+        sequence![
+            GitInit {},
+            GitCheckout { branch: "main" },
+            WriteFile {
+                path: "README.md".into(),
+                content: "This is an example README file.",
+            },
+            GitAdd {
+                paths: vec!["README.md"],
+            },
+            GitCommit {
+                message: "Add README.md",
+                paths: vec!["README.md"],
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .unwrap();
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["worktree", "add", "feature/test"]);
+        cmd.run(&core, &args).await.unwrap();
+
+        let worktree = temp
+            .path()
+            .join("github.com")
+            .join("sierrasoftworks")
+            .join("test-worktree@feature%2Ftest");
+        assert!(
+            worktree.join("README.md").exists(),
+            "the worktree should have been created alongside the repository"
+        );
+        assert_eq!(
+            git::git_current_branch(&worktree).await.unwrap(),
+            "feature/test"
+        );
+
+        let args = cmd.app().get_matches_from(vec!["worktree", "list"]);
+        cmd.run(&core, &args).await.unwrap();
+        assert!(core.output().to_string().contains("feature/test"));
+
+        let args = cmd
+            .app()
+            .get_matches_from(vec!["worktree", "remove", "feature/test"]);
+        cmd.run(&core, &args).await.unwrap();
+        assert!(!worktree.exists(), "the worktree should have been removed");
+    }
+}
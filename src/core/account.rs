@@ -0,0 +1,113 @@
+use super::Service;
+use serde::{Deserialize, Serialize};
+
+/// An account on a service which should be used for repositories in specific
+/// namespaces, allowing you to keep (for example) your work and personal repos
+/// separate on the same service.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Account {
+    name: String,
+    #[serde(default)]
+    namespaces: Vec<String>,
+    #[serde(rename = "sshHost", default, skip_serializing_if = "Option::is_none")]
+    ssh_host: Option<String>,
+    #[serde(rename = "userName", default, skip_serializing_if = "Option::is_none")]
+    user_name: Option<String>,
+    #[serde(rename = "userEmail", default, skip_serializing_if = "Option::is_none")]
+    user_email: Option<String>,
+}
+
+impl Account {
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_ssh_host(&self) -> Option<&str> {
+        self.ssh_host.as_deref()
+    }
+
+    pub fn get_user_name(&self) -> Option<&str> {
+        self.user_name.as_deref()
+    }
+
+    pub fn get_user_email(&self) -> Option<&str> {
+        self.user_email.as_deref()
+    }
+
+    /// Gets the name of the keychain entry which holds this account's access token.
+    pub fn get_token_name(&self, service: &Service) -> String {
+        format!("{}/{}", service.get_domain(), self.name)
+    }
+
+    /// Determines whether this account should be used for repositories in the given
+    /// namespace.
+    pub fn handles_namespace(&self, namespace: &str) -> bool {
+        self.namespaces
+            .iter()
+            .any(|pattern| namespace_matches(pattern, namespace))
+    }
+}
+
+/// Determines whether a namespace matches the given pattern, which may use `*`
+/// wildcards within each segment and also matches any namespaces nested beneath it.
+pub fn namespace_matches(pattern: &str, namespace: &str) -> bool {
+    let namespace: Vec<&str> = namespace.split('/').collect();
+    let pattern: Vec<&str> = pattern.split('/').collect();
+
+    pattern.len() <= namespace.len()
+        && pattern
+            .iter()
+            .zip(namespace.iter())
+            .all(|(p, n)| wildcard_matches(p, n))
+}
+
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == value,
+        Some(idx) => {
+            let (prefix, rest) = (&pattern[..idx], &pattern[idx + 1..]);
+            if !value.starts_with(prefix) {
+                return false;
+            }
+
+            let value = &value[prefix.len()..];
+            (0..=value.len())
+                .filter(|&i| value.is_char_boundary(i))
+                .any(|i| wildcard_matches(rest, &value[i..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(namespaces: &[&str]) -> Account {
+        Account {
+            name: "work".to_string(),
+            namespaces: namespaces.iter().map(|n| n.to_string()).collect(),
+            ssh_host: None,
+            user_name: None,
+            user_email: None,
+        }
+    }
+
+    #[test]
+    fn handles_namespace() {
+        let acc = account(&["work-org", "work-*-team"]);
+
+        assert!(acc.handles_namespace("work-org"));
+        assert!(acc.handles_namespace("work-org/project"));
+        assert!(acc.handles_namespace("work-platform-team"));
+        assert!(!acc.handles_namespace("work-org-other"));
+        assert!(!acc.handles_namespace("sierrasoftworks"));
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_matches("*", "anything"));
+        assert!(wildcard_matches("a*c", "abbbc"));
+        assert!(wildcard_matches("a*", "a"));
+        assert!(!wildcard_matches("a*c", "abd"));
+    }
+}
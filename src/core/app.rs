@@ -1,3 +1,6 @@
+use super::super::errors;
+use super::templates;
+use gtmpl::Value;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,13 +26,28 @@ impl App {
         self.command.as_str()
     }
 
+    /// Renders the command template, giving the program which should be run to launch this app.
+    pub fn get_program(&self, context: Value) -> Result<String, errors::Error> {
+        templates::render(self.get_command(), context)
+    }
+
     pub fn get_args(&self) -> Vec<String> {
         self.args.clone()
     }
 
+    /// Renders each of the argument templates which should be passed to the program.
+    pub fn get_program_args(&self, context: Value) -> Result<Vec<String>, errors::Error> {
+        templates::render_list(self.get_args(), context)
+    }
+
     pub fn get_environment(&self) -> Vec<String> {
         self.environment.clone()
     }
+
+    /// Renders each of the `KEY=value` environment variable templates for the program.
+    pub fn get_program_environment(&self, context: Value) -> Result<Vec<String>, errors::Error> {
+        templates::render_list(self.get_environment(), context)
+    }
 }
 
 pub struct AppBuilder {
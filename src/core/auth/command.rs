@@ -0,0 +1,141 @@
+use super::*;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Stores access tokens in the standard Unix password manager, `pass`, under the
+/// `git-tool/` prefix.
+pub struct PassKeyChain {}
+
+impl From<Arc<Config>> for PassKeyChain {
+    fn from(_: Arc<Config>) -> Self {
+        Self {}
+    }
+}
+
+impl KeyChain for PassKeyChain {
+    fn get_token(&self, service: &str) -> Result<String, Error> {
+        let output = run(
+            Command::new("pass")
+                .arg("show")
+                .arg(format!("git-tool/{}", service)),
+            None,
+        )?;
+
+        // By convention, the first line of a pass entry holds the password.
+        Ok(output.lines().next().unwrap_or_default().to_string())
+    }
+
+    fn set_token(&self, service: &str, token: &str) -> Result<(), Error> {
+        run(
+            Command::new("pass")
+                .arg("insert")
+                .arg("--multiline")
+                .arg("--force")
+                .arg(format!("git-tool/{}", service)),
+            Some(token),
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_token(&self, service: &str) -> Result<(), Error> {
+        run(
+            Command::new("pass")
+                .arg("rm")
+                .arg("--force")
+                .arg(format!("git-tool/{}", service)),
+            None,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Stores access tokens using `secret-tool`, which talks to any Secret Service
+/// provider (like GNOME Keyring or KeePassXC) on Linux.
+pub struct SecretToolKeyChain {}
+
+impl From<Arc<Config>> for SecretToolKeyChain {
+    fn from(_: Arc<Config>) -> Self {
+        Self {}
+    }
+}
+
+impl KeyChain for SecretToolKeyChain {
+    fn get_token(&self, service: &str) -> Result<String, Error> {
+        let output = run(
+            Command::new("secret-tool")
+                .arg("lookup")
+                .arg("application")
+                .arg("git-tool")
+                .arg("service")
+                .arg(service),
+            None,
+        )?;
+
+        Ok(output.trim_end().to_string())
+    }
+
+    fn set_token(&self, service: &str, token: &str) -> Result<(), Error> {
+        run(
+            Command::new("secret-tool")
+                .arg("store")
+                .arg(format!("--label=Git-Tool: {}", service))
+                .arg("application")
+                .arg("git-tool")
+                .arg("service")
+                .arg(service),
+            Some(token),
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_token(&self, service: &str) -> Result<(), Error> {
+        run(
+            Command::new("secret-tool")
+                .arg("clear")
+                .arg("application")
+                .arg("git-tool")
+                .arg("service")
+                .arg(service),
+            None,
+        )?;
+
+        Ok(())
+    }
+}
+
+fn run(cmd: &mut Command, input: Option<&str>) -> Result<String, Error> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            errors::user_with_internal(
+                &format!("Could not run {} to access your stored tokens.", program),
+                "Please make sure that it is installed and available on your $PATH, or choose a different `keychain.backend` in your config.",
+                e,
+            )
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(input) = input {
+            stdin.write_all(input.as_bytes())?;
+        }
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(errors::user_with_internal(
+            &format!("{} was not able to access the requested token.", program),
+            "Please make sure that you have stored an access token for this service using `git-tool auth`, and that your password store is unlocked.",
+            errors::detailed_message(String::from_utf8_lossy(&output.stderr).trim()),
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,103 @@
+use super::*;
+use std::collections::HashMap;
+
+/// Reads access tokens from `GITTOOL_TOKEN_*` environment variables, which is
+/// useful in CI and containers where no secure credential store is available.
+pub struct EnvKeyChain {
+    variables: Option<HashMap<String, String>>,
+}
+
+impl EnvKeyChain {
+    /// Gets the name of the environment variable holding the token for a service,
+    /// for example `GITTOOL_TOKEN_GITHUB_COM` for `github.com`.
+    pub fn variable_name(service: &str) -> String {
+        let name: String = service
+            .chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+                _ => '_',
+            })
+            .collect();
+
+        format!("GITTOOL_TOKEN_{}", name)
+    }
+
+    /// Creates a keychain which reads tokens from the provided variables instead of the
+    /// process' environment, so that tests don't need to modify shared state.
+    #[cfg(test)]
+    pub fn from_vars<I: IntoIterator<Item = (String, String)>>(vars: I) -> Self {
+        Self {
+            variables: Some(vars.into_iter().collect()),
+        }
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        match &self.variables {
+            Some(variables) => variables.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        }
+    }
+}
+
+impl From<Arc<Config>> for EnvKeyChain {
+    fn from(_: Arc<Config>) -> Self {
+        Self { variables: None }
+    }
+}
+
+impl KeyChain for EnvKeyChain {
+    fn get_token(&self, service: &str) -> Result<String, Error> {
+        let variable = Self::variable_name(service);
+
+        match self.var(&variable) {
+            Some(token) if !token.is_empty() => Ok(token),
+            _ => Err(errors::user(
+                &format!(
+                    "No access token could be found for {} in your environment.",
+                    service
+                ),
+                &format!(
+                    "Please set the {} environment variable to your access token.",
+                    variable
+                ),
+            )),
+        }
+    }
+
+    fn set_token(&self, service: &str, _token: &str) -> Result<(), Error> {
+        Err(errors::user(
+            "Access tokens cannot be saved when using the `env` keychain backend.",
+            &format!(
+                "Please set the {} environment variable to your access token instead.",
+                Self::variable_name(service)
+            ),
+        ))
+    }
+
+    fn remove_token(&self, service: &str) -> Result<(), Error> {
+        Err(errors::user(
+            "Access tokens cannot be removed when using the `env` keychain backend.",
+            &format!(
+                "Please unset the {} environment variable instead.",
+                Self::variable_name(service)
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_name() {
+        assert_eq!(
+            EnvKeyChain::variable_name("github.com"),
+            "GITTOOL_TOKEN_GITHUB_COM"
+        );
+        assert_eq!(
+            EnvKeyChain::variable_name("dev.azure.com/work"),
+            "GITTOOL_TOKEN_DEV_AZURE_COM_WORK"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use super::*;
+use secrecy::Secret;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Stores access tokens in an age encrypted file, protected by a passphrase which
+/// is read from `GITTOOL_KEYCHAIN_PASSPHRASE` or requested when it is first needed.
+pub struct FileKeyChain {
+    path: Option<PathBuf>,
+    passphrase: Mutex<Option<String>>,
+}
+
+impl From<Arc<Config>> for FileKeyChain {
+    fn from(config: Arc<Config>) -> Self {
+        // If no path has been configured, we keep the token store alongside the config file.
+        let path = config.get_keychain().get_path().or_else(|| {
+            config
+                .get_config_file()
+                .and_then(|f| f.parent().map(|p| p.join("tokens.age")))
+        });
+
+        Self {
+            path,
+            passphrase: Mutex::new(std::env::var("GITTOOL_KEYCHAIN_PASSPHRASE").ok()),
+        }
+    }
+}
+
+impl FileKeyChain {
+    fn get_path(&self) -> Result<&Path, Error> {
+        self.path.as_deref().ok_or_else(|| errors::user(
+            "We could not determine where your encrypted token store should be kept.",
+            "Please set `keychain.path` in your config file to the location where your tokens should be stored."))
+    }
+
+    fn get_passphrase(&self) -> Result<String, Error> {
+        let mut passphrase = self.passphrase.lock().map_err(|_| {
+            errors::system(
+                "Could not read the keychain passphrase due to a poisoned lock.",
+                "Please restart the application and try again.",
+            )
+        })?;
+
+        if let Some(passphrase) = passphrase.as_ref() {
+            return Ok(passphrase.clone());
+        }
+
+        let entered = rpassword::read_password_from_tty(Some("Keychain Passphrase: ")).map_err(|e| errors::user_with_internal(
+            "Could not read the passphrase for your encrypted token store.",
+            "Please try running this command again, or set the GITTOOL_KEYCHAIN_PASSPHRASE environment variable to your passphrase.",
+            e))?;
+
+        *passphrase = Some(entered.clone());
+        Ok(entered)
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, Error> {
+        let path = self.get_path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let encrypted = std::fs::read(path)?;
+        let decryptor = match age::Decryptor::new(&encrypted[..])? {
+            age::Decryptor::Passphrase(d) => d,
+            _ => {
+                return Err(errors::user(
+                    "Your token store was not encrypted using a passphrase.",
+                    "Please make sure that the file configured in `keychain.path` is a token store created by Git-Tool, or remove it to start with an empty token store.",
+                ))
+            }
+        };
+
+        let mut decrypted = vec![];
+        decryptor
+            .decrypt(&Secret::new(self.get_passphrase()?), None)?
+            .read_to_end(&mut decrypted)?;
+
+        Ok(serde_json::from_slice(&decrypted)?)
+    }
+
+    fn write(&self, tokens: &BTreeMap<String, String>) -> Result<(), Error> {
+        let path = self.get_path()?;
+        let plaintext = serde_json::to_vec(tokens)?;
+
+        let mut encrypted = vec![];
+        let mut writer = age::Encryptor::with_user_passphrase(Secret::new(self.get_passphrase()?))
+            .wrap_output(&mut encrypted)?;
+        writer.write_all(&plaintext)?;
+        writer.finish()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, encrypted)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl KeyChain for FileKeyChain {
+    fn get_token(&self, service: &str) -> Result<String, Error> {
+        self.read()?.remove(service).ok_or_else(|| {
+            errors::user(
+                "Could not find an access token for this service.",
+                &format!(
+                    "Please add an access token using `git-tool auth {}`.",
+                    service
+                ),
+            )
+        })
+    }
+
+    fn set_token(&self, service: &str, token: &str) -> Result<(), Error> {
+        let mut tokens = self.read()?;
+        tokens.insert(service.to_string(), token.to_string());
+        self.write(&tokens)
+    }
+
+    fn remove_token(&self, service: &str) -> Result<(), Error> {
+        let mut tokens = self.read()?;
+        if tokens.remove(service).is_some() {
+            self.write(&tokens)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_keychain() {
+        let temp = tempdir().unwrap();
+        let keychain = FileKeyChain {
+            path: Some(temp.path().join("tokens.age")),
+            passphrase: Mutex::new(Some("test-passphrase".to_string())),
+        };
+
+        assert!(keychain.get_token("github.com").is_err());
+
+        keychain.set_token("github.com", "example-token").unwrap();
+        assert_eq!(keychain.get_token("github.com").unwrap(), "example-token");
+
+        let contents = std::fs::read(temp.path().join("tokens.age")).unwrap();
+        assert!(
+            !String::from_utf8_lossy(&contents).contains("example-token"),
+            "the token should not be stored in plain text"
+        );
+
+        let wrong = FileKeyChain {
+            path: Some(temp.path().join("tokens.age")),
+            passphrase: Mutex::new(Some("wrong-passphrase".to_string())),
+        };
+        assert!(wrong.get_token("github.com").is_err());
+
+        keychain.remove_token("github.com").unwrap();
+        assert!(keychain.get_token("github.com").is_err());
+    }
+}
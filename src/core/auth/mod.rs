@@ -0,0 +1,313 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+mod command;
+mod env;
+mod file;
+mod system;
+
+pub use command::{PassKeyChain, SecretToolKeyChain};
+pub use env::EnvKeyChain;
+pub use file::FileKeyChain;
+pub use system::SystemKeyChain;
+
+pub trait KeyChain: From<Arc<Config>> + Send + Sync {
+    fn get_token(&self, service: &str) -> Result<String, Error>;
+    fn set_token(&self, service: &str, token: &str) -> Result<(), Error>;
+    fn remove_token(&self, service: &str) -> Result<(), Error>;
+}
+
+/// The secure storage backends which Git-Tool can keep your access tokens in.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyChainBackend {
+    #[default]
+    System,
+    File,
+    Env,
+    Pass,
+    SecretTool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct KeyChainConfig {
+    #[serde(default)]
+    backend: KeyChainBackend,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+}
+
+impl KeyChainConfig {
+    pub fn get_backend(&self) -> KeyChainBackend {
+        self.backend
+    }
+
+    pub fn get_path(&self) -> Option<PathBuf> {
+        self.path.clone()
+    }
+
+    /// Gets the namespace which tokens are stored under, keeping those for each of your
+    /// profiles separate.
+    pub fn get_namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// The keychain used by Git-Tool, which looks for tokens in your environment
+/// variables before falling back to the backend selected in your config.
+pub struct ConfiguredKeyChain {
+    env: EnvKeyChain,
+    backend: Backend,
+    namespace: Option<String>,
+}
+
+enum Backend {
+    System(SystemKeyChain),
+    File(FileKeyChain),
+    Env,
+    Pass(PassKeyChain),
+    SecretTool(SecretToolKeyChain),
+}
+
+impl From<Arc<Config>> for ConfiguredKeyChain {
+    fn from(config: Arc<Config>) -> Self {
+        let backend = match config.get_keychain().get_backend() {
+            KeyChainBackend::System => Backend::System(SystemKeyChain::from(config.clone())),
+            KeyChainBackend::File => Backend::File(FileKeyChain::from(config.clone())),
+            KeyChainBackend::Env => Backend::Env,
+            KeyChainBackend::Pass => Backend::Pass(PassKeyChain::from(config.clone())),
+            KeyChainBackend::SecretTool => {
+                Backend::SecretTool(SecretToolKeyChain::from(config.clone()))
+            }
+        };
+
+        Self {
+            namespace: config.get_keychain().get_namespace().map(|n| n.to_string()),
+            env: EnvKeyChain::from(config),
+            backend,
+        }
+    }
+}
+
+impl ConfiguredKeyChain {
+    /// Replaces the keychain used to read tokens from environment variables.
+    #[cfg(test)]
+    pub fn with_env(self, env: EnvKeyChain) -> Self {
+        Self { env, ..self }
+    }
+
+    fn token_name(&self, service: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}:{}", namespace, service),
+            None => service.to_string(),
+        }
+    }
+}
+
+impl KeyChain for ConfiguredKeyChain {
+    fn get_token(&self, service: &str) -> Result<String, Error> {
+        let service = &self.token_name(service);
+        if let Ok(token) = self.env.get_token(service) {
+            return Ok(token);
+        }
+
+        match &self.backend {
+            Backend::System(k) => k.get_token(service),
+            Backend::File(k) => k.get_token(service),
+            Backend::Env => self.env.get_token(service),
+            Backend::Pass(k) => k.get_token(service),
+            Backend::SecretTool(k) => k.get_token(service),
+        }
+    }
+
+    fn set_token(&self, service: &str, token: &str) -> Result<(), Error> {
+        let service = &self.token_name(service);
+        match &self.backend {
+            Backend::System(k) => k.set_token(service, token),
+            Backend::File(k) => k.set_token(service, token),
+            Backend::Env => self.env.set_token(service, token),
+            Backend::Pass(k) => k.set_token(service, token),
+            Backend::SecretTool(k) => k.set_token(service, token),
+        }
+    }
+
+    fn remove_token(&self, service: &str) -> Result<(), Error> {
+        let service = &self.token_name(service);
+        match &self.backend {
+            Backend::System(k) => k.remove_token(service),
+            Backend::File(k) => k.remove_token(service),
+            Backend::Env => self.env.remove_token(service),
+            Backend::Pass(k) => k.remove_token(service),
+            Backend::SecretTool(k) => k.remove_token(service),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keychain_config() {
+        let config = Config::from_str(
+            r#"
+directory: /dev
+keychain:
+  backend: secret-tool
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.get_keychain().get_backend(),
+            KeyChainBackend::SecretTool
+        );
+        assert_eq!(config.get_keychain().get_path(), None);
+        assert!(Config::default().get_keychain().is_default());
+        assert_eq!(
+            Config::default().get_keychain().get_backend(),
+            KeyChainBackend::System
+        );
+    }
+
+    #[test]
+    fn test_env_backend() {
+        let config = Arc::new(
+            Config::from_str(
+                r#"
+directory: /dev
+keychain:
+  backend: env
+"#,
+            )
+            .unwrap(),
+        );
+
+        let keychain = ConfiguredKeyChain::from(config).with_env(EnvKeyChain::from_vars(vec![(
+            "GITTOOL_TOKEN_CONFIGURED_EXAMPLE_COM".to_string(),
+            "env-token".to_string(),
+        )]));
+        assert_eq!(
+            keychain.get_token("configured.example.com").unwrap(),
+            "env-token"
+        );
+        assert!(keychain.get_token("missing.example.com").is_err());
+        assert!(keychain
+            .set_token("configured.example.com", "new-token")
+            .is_err());
+    }
+
+    #[test]
+    fn test_namespace() {
+        let config = Arc::new(
+            Config::from_str(
+                r#"
+directory: /dev
+keychain:
+  backend: env
+  namespace: work
+"#,
+            )
+            .unwrap(),
+        );
+
+        let keychain =
+            ConfiguredKeyChain::from(config.clone()).with_env(EnvKeyChain::from_vars(vec![
+                (
+                    "GITTOOL_TOKEN_WORK_NAMESPACED_EXAMPLE_COM".to_string(),
+                    "work-token".to_string(),
+                ),
+                (
+                    "GITTOOL_TOKEN_NAMESPACED_EXAMPLE_COM".to_string(),
+                    "personal-token".to_string(),
+                ),
+            ]));
+        assert_eq!(
+            keychain.get_token("namespaced.example.com").unwrap(),
+            "work-token"
+        );
+
+        let keychain = ConfiguredKeyChain::from(config).with_env(EnvKeyChain::from_vars(vec![(
+            "GITTOOL_TOKEN_NAMESPACED_EXAMPLE_COM".to_string(),
+            "personal-token".to_string(),
+        )]));
+        assert!(keychain.get_token("namespaced.example.com").is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    use super::*;
+    use std::{collections::BTreeMap, sync::Mutex};
+
+    pub struct MockKeyChain {
+        tokens: Arc<Mutex<BTreeMap<String, String>>>,
+    }
+
+    impl From<Arc<Config>> for MockKeyChain {
+        fn from(_: Arc<Config>) -> Self {
+            Self {
+                tokens: Arc::new(Mutex::new(BTreeMap::new())),
+            }
+        }
+    }
+
+    impl KeyChain for MockKeyChain {
+        fn get_token(&self, service: &str) -> Result<String, Error> {
+            self.tokens
+                .lock()
+                .map_err(|_| {
+                    errors::system(
+                        "Could not read the token from the keychain due to a poisoned lock.",
+                        "Please restart the application and try again.",
+                    )
+                })
+                .and_then(|t| {
+                    t.get(service).map(|o| o.clone()).ok_or(errors::user(
+                        "Could not find an access token for this service.",
+                        &format!(
+                            "Please add an access token using `git-tool auth {}`.",
+                            service
+                        ),
+                    ))
+                })
+        }
+
+        fn set_token(&self, service: &str, token: &str) -> Result<(), Error> {
+            self.tokens
+                .lock()
+                .map_err(|_| {
+                    errors::system(
+                        "Could not read the token from the keychain due to a poisoned lock.",
+                        "Please restart the application and try again.",
+                    )
+                })
+                .map(|mut t| {
+                    t.insert(service.to_string(), token.to_string())
+                        .unwrap_or_default()
+                })
+                .map(|_| ())
+        }
+
+        fn remove_token(&self, service: &str) -> Result<(), Error> {
+            self.tokens
+                .lock()
+                .map_err(|_| {
+                    errors::system(
+                        "Could not read the token from the keychain due to a poisoned lock.",
+                        "Please restart the application and try again.",
+                    )
+                })
+                .map(|mut t| t.remove(service).unwrap_or_default())
+                .map(|_| ())
+        }
+    }
+}
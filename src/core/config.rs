@@ -5,8 +5,14 @@ use std::{path, sync::Arc};
 
 use super::super::errors;
 use super::app;
+use super::auth;
 use super::features;
+use super::layers;
+use super::profile;
+use super::registries;
+use super::scratchpad;
 use super::service;
+use super::yaml::{YamlDocument, YamlPath};
 use crate::online::registry::EntryConfig;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,8 +22,18 @@ pub struct Config {
 
     #[serde(rename = "directory")]
     dev_directory: path::PathBuf,
+    #[serde(default, rename = "directories", skip_serializing_if = "Vec::is_empty")]
+    extra_directories: Vec<path::PathBuf>,
     #[serde(default, rename = "scratchpads")]
     scratch_directory: Option<path::PathBuf>,
+    #[serde(
+        default,
+        rename = "scratchpad_retention",
+        skip_serializing_if = "Option::is_none"
+    )]
+    scratch_retention: Option<scratchpad::RetentionPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clone_timeout: Option<u64>,
 
     #[serde(default)]
     services: Vec<Arc<service::Service>>,
@@ -28,6 +44,25 @@ pub struct Config {
 
     #[serde(default)]
     features: features::Features,
+
+    #[serde(default, skip_serializing_if = "auth::KeyChainConfig::is_default")]
+    keychain: auth::KeyChainConfig,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    registries: Vec<registries::RegistrySource>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    installed: Vec<registries::InstalledEntry>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<profile::Profile>,
+
+    #[serde(skip)]
+    layers: Vec<layers::ConfigLayer>,
+    #[serde(skip)]
+    profile: Option<String>,
+    #[serde(skip)]
+    base: Option<Box<Config>>,
 }
 
 impl Config {
@@ -42,17 +77,36 @@ impl Config {
         if from.dev_directory.components().count() > 0 {
             into.dev_directory = from.dev_directory.clone();
         }
+        if !from.extra_directories.is_empty() {
+            into.extra_directories = from.extra_directories.clone();
+        }
         match from.scratch_directory {
             Some(path) => into.scratch_directory = Some(path.clone()),
             None => {}
         }
+        if from.scratch_retention.is_some() {
+            into.scratch_retention = from.scratch_retention.clone();
+        }
+        if from.clone_timeout.is_some() {
+            into.clone_timeout = from.clone_timeout;
+        }
         if !from.services.is_empty() {
             into.services = from.services.clone();
         }
         if !from.apps.is_empty() {
             into.apps = from.apps.clone();
         }
+        if !from.registries.is_empty() {
+            into.registries = from.registries.clone();
+        }
+        if !from.installed.is_empty() {
+            into.installed = from.installed.clone();
+        }
+        if !from.profiles.is_empty() {
+            into.profiles = from.profiles.clone();
+        }
         into.features = from.features;
+        into.keychain = from.keychain;
 
         for (k, v) in from.aliases.iter() {
             into.aliases.insert(k.clone(), v.clone());
@@ -61,54 +115,84 @@ impl Config {
         into
     }
 
+    /// Adds the app and service from a registry template to your config file, replacing any
+    /// existing entries with the same name (or domain) when `replace_existing` is set.
     pub fn apply_template(
         &self,
+        doc: &mut YamlDocument,
         template: EntryConfig,
         replace_existing: bool,
-    ) -> Result<Self, errors::Error> {
-        let mut into = self.clone();
-
-        match template.app {
-            Some(app) => {
-                if let Some(existing_position) =
-                    into.apps.iter().position(|a| a.get_name() == app.name)
-                {
-                    if !replace_existing {
-                        return Err(errors::user(
-                            &format!("The application {} already exists in your configuration file. Adding a duplicate entry will have no effect.", &app.name),
-                            &format!("If you would like to replace the existing entry for {app}, use `gt config add apps/{app} --force`.", app=&app.name)));
-                    } else {
-                        into.apps[existing_position] = Arc::new(app.into());
-                    }
-                } else {
-                    into.apps.push(Arc::new(app.into()));
-                }
+    ) -> Result<(), errors::Error> {
+        if let Some(app) = template.app {
+            if self.get_app(&app.name).is_some() && !replace_existing {
+                return Err(errors::user(
+                    &format!("The application {} already exists in your configuration file. Adding a duplicate entry will have no effect.", &app.name),
+                    &format!("If you would like to replace the existing entry for {app}, use `gt config add apps/{app} --force`.", app=&app.name)));
             }
-            None => {}
+
+            let name = app.name.clone();
+            self.set_item(
+                doc,
+                "apps",
+                &name,
+                serde_yaml::to_value(Into::<app::App>::into(app))?,
+            )?;
         }
 
-        match template.service {
-            Some(svc) => {
-                if let Some(existing_position) = into
-                    .services
-                    .iter()
-                    .position(|s| s.get_domain() == svc.domain)
-                {
-                    if !replace_existing {
-                        return Err(errors::user(
-                            &format!("The service {} already exists in your configuration file. Adding a duplicate entry will have no effect.", &svc.domain),
-                            &format!("If you would like to replace the existing entry for {svc}, use `gt config add services/{svc} --force`.", svc=&svc.domain)));
-                    } else {
-                        into.services[existing_position] = Arc::new(svc.into());
-                    }
-                } else {
-                    into.services.push(Arc::new(svc.into()));
-                }
+        if let Some(svc) = template.service {
+            if self.get_service(&svc.domain).is_some() && !replace_existing {
+                return Err(errors::user(
+                    &format!("The service {} already exists in your configuration file. Adding a duplicate entry will have no effect.", &svc.domain),
+                    &format!("If you would like to replace the existing entry for {svc}, use `gt config add services/{svc} --force`.", svc=&svc.domain)));
             }
-            None => {}
+
+            let domain = svc.domain.clone();
+            self.set_item(
+                doc,
+                "services",
+                &domain,
+                serde_yaml::to_value(Into::<service::Service>::into(svc))?,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets a named item (like an app or service) in your config file, adding it to the list if
+    /// it is not already there.
+    fn set_item(
+        &self,
+        doc: &mut YamlDocument,
+        list: &str,
+        name: &str,
+        value: serde_yaml::Value,
+    ) -> Result<(), errors::Error> {
+        let list_path = YamlPath::parse(list)?;
+        let item_path = list_path.item(name);
+        let current = doc.value()?;
+
+        if item_path.get(&current).is_some() {
+            return doc.set(&item_path, &value);
+        }
+
+        // Adding a list to your config file replaces Git-Tool's defaults, so we keep them unless
+        // another config file already provides the list.
+        if list_path.get(&current).is_none()
+            && !self
+                .layers
+                .iter()
+                .any(|l| list_path.get(l.get_value()).is_some())
+        {
+            let mut items = match list_path.get(&serde_yaml::to_value(self)?) {
+                Some(serde_yaml::Value::Sequence(items)) => items.clone(),
+                _ => vec![],
+            };
+            items.push(value);
+
+            return doc.set(&list_path, &serde_yaml::Value::Sequence(items));
         }
 
-        Ok(into)
+        doc.push(&list_path, &value)
     }
 
     #[cfg(test)]
@@ -116,7 +200,9 @@ impl Config {
         Self {
             config_file: None,
             dev_directory: dir.to_path_buf(),
+            extra_directories: vec![],
             scratch_directory: None,
+            scratch_retention: None,
             features: features::Features::builder()
                 .with_use_http_transport(true)
                 .build(),
@@ -136,6 +222,7 @@ impl Config {
             })
     }
 
+    #[cfg(test)]
     pub fn from_file(path: &path::Path) -> Result<Self, errors::Error> {
         let f = std::fs::File::open(path)?;
 
@@ -145,6 +232,139 @@ impl Config {
         Ok(cfg)
     }
 
+    /// Loads your config by layering the system-wide config file, your own config file, the
+    /// profile you have selected (or the one whose directory you are in) and any `GITTOOL_*`
+    /// environment variable overrides over Git-Tool's defaults.
+    pub fn load<I>(
+        system_file: Option<&path::Path>,
+        user_file: Option<&path::Path>,
+        env: I,
+        profile: Option<&str>,
+        cwd: Option<&path::Path>,
+    ) -> Result<Self, errors::Error>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut file_layers = vec![];
+        if let Some(path) = system_file {
+            file_layers.push(layers::ConfigLayer::from_file("system", path)?);
+        }
+
+        if let Some(path) = user_file {
+            file_layers.push(layers::ConfigLayer::from_file("user", path)?);
+        }
+
+        let env_layers = layers::ConfigLayer::from_env(env)?;
+
+        let base = Self::from_layers(
+            file_layers
+                .iter()
+                .chain(env_layers.iter())
+                .cloned()
+                .collect(),
+            user_file,
+        )?;
+
+        let selected = match profile {
+            Some(name) => Some(base.profiles.iter().find(|p| p.get_name() == name).ok_or_else(|| {
+                errors::user(
+                    &format!("There is no profile named '{}' in your config.", name),
+                    "Make sure that you have spelled the profile's name correctly and that it is listed in the profiles section of your config file.",
+                )
+            })?),
+            None => cwd.and_then(|cwd| base.profiles.iter().find(|p| p.contains(cwd))),
+        };
+
+        match selected {
+            Some(selected) => {
+                let mut cfg = Self::from_layers(
+                    file_layers
+                        .iter()
+                        .cloned()
+                        .chain(std::iter::once(selected.get_layer()))
+                        .chain(env_layers.iter().cloned())
+                        .collect(),
+                    user_file,
+                )?;
+
+                cfg.profile = Some(selected.get_name().to_string());
+                cfg.base = Some(Box::new(base));
+                Ok(cfg)
+            }
+            None => Ok(base),
+        }
+    }
+
+    fn from_layers(
+        config_layers: Vec<layers::ConfigLayer>,
+        user_file: Option<&path::Path>,
+    ) -> Result<Self, errors::Error> {
+        let mut merged = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        for layer in config_layers.iter() {
+            layers::merge(&mut merged, layer.get_value());
+        }
+
+        if merged.get("directory").is_none() {
+            layers::merge(&mut merged, &serde_yaml::from_str("directory: \"\"")?);
+        }
+
+        let mut cfg = serde_yaml::from_value(merged)
+            .map(|x| Config::default().extend(x))
+            .map_err(|e| {
+                errors::user_with_internal(
+            "We couldn't parse your configuration file.", 
+            "Please make sure that the YAML in your configuration file is correctly formatted.", 
+            e)
+            })?;
+
+        cfg.config_file = user_file.map(|p| p.to_path_buf());
+        cfg.layers = config_layers;
+
+        Ok(cfg)
+    }
+
+    /// Rebuilds this config with the provided text in place of your config file, which lets
+    /// changes be checked against your other config layers before they are written.
+    pub fn with_user_file(&self, text: &str) -> Result<Self, errors::Error> {
+        let path = self
+            .config_file
+            .clone()
+            .unwrap_or_else(|| path::PathBuf::from("git-tool.yml"));
+        let user = layers::ConfigLayer::from_str("user", &path, text)?;
+
+        let mut config_layers: Vec<layers::ConfigLayer> = self
+            .layers
+            .iter()
+            .filter(|l| l.get_kind() != "user")
+            .cloned()
+            .collect();
+        let position = config_layers
+            .iter()
+            .position(|l| l.get_kind() == "environment")
+            .unwrap_or_else(|| config_layers.len());
+        config_layers.insert(position, user);
+
+        Self::from_layers(config_layers, self.config_file.as_deref())
+    }
+
+    /// Gets the path to the system-wide config file, if one exists.
+    pub fn get_system_config_file() -> Option<path::PathBuf> {
+        let path = match std::env::var("GITTOOL_SYSTEM_CONFIG") {
+            Ok(path) => path::PathBuf::from(path),
+            Err(_) if cfg!(windows) => path::PathBuf::from(std::env::var("ProgramData").ok()?)
+                .join("git-tool")
+                .join("config.yml"),
+            Err(_) => path::PathBuf::from("/etc/git-tool/config.yml"),
+        };
+
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(test)]
     pub fn from_reader<R>(rdr: R) -> Result<Self, errors::Error>
     where
         R: std::io::Read,
@@ -177,6 +397,20 @@ impl Config {
         &self.dev_directory
     }
 
+    /// Gets each of the directories which your repositories are stored in, starting with your
+    /// primary dev directory (which new repositories are cloned into) and followed by those
+    /// listed in the `directories` section of your config, in order.
+    pub fn get_dev_directories(&self) -> Vec<&path::Path> {
+        let mut dirs = vec![self.dev_directory.as_path()];
+        for dir in self.extra_directories.iter() {
+            if dir.components().count() > 0 && !dirs.contains(&dir.as_path()) {
+                dirs.push(dir.as_path());
+            }
+        }
+
+        dirs
+    }
+
     pub fn get_scratch_directory(&self) -> path::PathBuf {
         match self.scratch_directory.clone() {
             Some(dir) => dir,
@@ -184,6 +418,16 @@ impl Config {
         }
     }
 
+    /// Gets the policy which controls how long your scratchpads are kept for, if you have one.
+    pub fn get_scratch_retention(&self) -> Option<&scratchpad::RetentionPolicy> {
+        self.scratch_retention.as_ref()
+    }
+
+    /// Gets how long `git clone` is allowed to run for before it is stopped, if you have set a limit.
+    pub fn get_clone_timeout(&self) -> Option<std::time::Duration> {
+        self.clone_timeout.map(std::time::Duration::from_secs)
+    }
+
     pub fn get_apps(&self) -> core::slice::Iter<Arc<app::App>> {
         self.apps.iter()
     }
@@ -228,17 +472,127 @@ impl Config {
         self.aliases.iter()
     }
 
-    pub fn add_alias(&mut self, name: &str, repo: &str) {
-        self.aliases.insert(name.to_string(), repo.to_string());
+    pub fn get_features(&self) -> &features::Features {
+        &self.features
     }
 
-    pub fn remove_alias(&mut self, name: &str) {
-        self.aliases.remove(name);
+    pub fn get_keychain(&self) -> &auth::KeyChainConfig {
+        &self.keychain
     }
 
-    pub fn get_features(&self) -> &features::Features {
-        &self.features
+    /// Gets the registries which entries can be added from, including Git-Tool's own
+    /// registry unless you have configured a registry with the same name.
+    pub fn get_registries(&self) -> Vec<registries::RegistrySource> {
+        let mut sources = self.registries.clone();
+
+        let default = registries::RegistrySource::default();
+        if !sources.iter().any(|r| r.get_name() == default.get_name()) {
+            sources.insert(0, default);
+        }
+
+        sources
+    }
+
+    /// Gets the name of the profile which is being used, if any.
+    pub fn get_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
     }
+
+    pub fn get_profiles(&self) -> core::slice::Iter<'_, profile::Profile> {
+        self.profiles.iter()
+    }
+
+    /// Gets your config without the overrides from the profile you are using, which is what
+    /// changes made with `gt config` are applied to.
+    pub fn get_base(&self) -> &Self {
+        self.base.as_deref().unwrap_or(self)
+    }
+
+    /// Gets the registry entries which have been added to your config with `gt config add`.
+    pub fn get_installed(&self) -> core::slice::Iter<'_, registries::InstalledEntry> {
+        self.installed.iter()
+    }
+
+    pub fn get_installed_entry(&self, id: &str) -> Option<&registries::InstalledEntry> {
+        self.installed.iter().find(|e| e.get_id() == id)
+    }
+
+    /// Records that a registry entry has been added to your config file, along with the apps
+    /// and services it added so that they can be removed again later.
+    pub fn add_installed(
+        &self,
+        doc: &mut YamlDocument,
+        installed: &registries::InstalledEntry,
+    ) -> Result<(), errors::Error> {
+        let path = YamlPath::parse("installed")?;
+        let index = match find_installed(doc, installed.get_id())? {
+            Some(index) => index,
+            None => return doc.push(&path, &serde_yaml::to_value(installed)?),
+        };
+
+        let item = path.index(index);
+        let current = doc.value()?;
+        let fields = vec![
+            ("version", serde_yaml::to_value(installed.get_version())?),
+            ("apps", serde_yaml::to_value(installed.get_apps())?),
+            ("services", serde_yaml::to_value(installed.get_services())?),
+        ];
+
+        for (field, value) in fields {
+            let field = item.child(field);
+            let unchanged = field.get(&current) == Some(&value);
+            let empty = value
+                .as_sequence()
+                .map(|s| s.is_empty())
+                .unwrap_or_default();
+
+            if !unchanged && !empty {
+                doc.set(&field, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the apps and services which a registry entry added to your config file, along
+    /// with the record of it having been added.
+    pub fn remove_installed(
+        &self,
+        doc: &mut YamlDocument,
+        installed: &registries::InstalledEntry,
+    ) -> Result<(), errors::Error> {
+        for app in installed.get_apps() {
+            doc.unset(&YamlPath::parse("apps")?.item(app))?;
+        }
+
+        for svc in installed.get_services() {
+            doc.unset(&YamlPath::parse("services")?.item(svc))?;
+        }
+
+        if let Some(index) = find_installed(doc, installed.get_id())? {
+            doc.unset(&YamlPath::parse("installed")?.index(index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets each of your config values along with the layer (like your config file or an
+    /// environment variable) that it was set by.
+    pub fn get_origins(&self) -> Result<Vec<(String, serde_yaml::Value, String)>, errors::Error> {
+        let value = serde_yaml::to_value(self)?;
+        Ok(layers::origins(&value, &self.layers))
+    }
+}
+
+fn find_installed(doc: &YamlDocument, id: &str) -> Result<Option<usize>, errors::Error> {
+    Ok(YamlPath::parse("installed")?
+        .get(&doc.value()?)
+        .and_then(|v| v.as_sequence())
+        .and_then(|items| {
+            items
+                .iter()
+                .position(|item| item.get("id").and_then(|v| v.as_str()) == Some(id))
+        }))
 }
 
 impl Default for Config {
@@ -248,7 +602,10 @@ impl Default for Config {
         Self {
             config_file: None,
             dev_directory: dev_dir,
+            extra_directories: vec![],
             scratch_directory: None,
+            scratch_retention: None,
+            clone_timeout: None,
             apps: vec![
                 Arc::new(app::App::builder().with_name("shell").with_command("bash").into()),
             ],
@@ -284,14 +641,22 @@ impl Default for Config {
             ],
             aliases: HashMap::new(),
             features: Default::default(),
+            keychain: Default::default(),
+            registries: vec![],
+            installed: vec![],
+            profiles: vec![],
+            layers: vec![],
+            profile: None,
+            base: None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{Config, YamlDocument};
     use crate::{
+        core::InstalledEntry,
         online::registry::{EntryApp, EntryConfig, EntryService},
         test::get_repo_root,
     };
@@ -325,6 +690,8 @@ mod tests {
             Ok(cfg) => {
                 assert_eq!(cfg.get_dev_directory(), PathBuf::from("/test/dev"));
                 assert_eq!(cfg.get_scratch_directory(), PathBuf::from("/test/scratch"));
+                assert!(cfg.get_scratch_retention().is_none());
+                assert!(cfg.get_clone_timeout().is_none());
 
                 match cfg.get_service("github.com") {
                     Some(_) => {}
@@ -340,6 +707,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn load_from_string_with_scratch_retention() {
+        let cfg = Config::from_str(
+            "directory: /test/dev\nscratchpad_retention:\n  weeks: 8\n  action: delete",
+        )
+        .unwrap();
+
+        let policy = cfg.get_scratch_retention().unwrap();
+        assert_eq!(policy.get_weeks(), 8);
+        assert_eq!(
+            policy.get_action(),
+            super::scratchpad::RetentionAction::Delete
+        );
+    }
+
+    #[test]
+    fn load_from_string_with_clone_timeout() {
+        let cfg = Config::from_str("directory: /test/dev\nclone_timeout: 600").unwrap();
+        assert_eq!(
+            cfg.get_clone_timeout(),
+            Some(std::time::Duration::from_secs(600))
+        );
+    }
+
+    #[test]
+    fn load_from_string_with_directories() {
+        let cfg = Config::from_str(
+            "directory: /ssd/dev\ndirectories:\n  - /hdd/dev\n  - /ssd/dev\n  - /usb/dev",
+        )
+        .unwrap();
+
+        assert_eq!(cfg.get_dev_directory(), PathBuf::from("/ssd/dev"));
+        assert_eq!(
+            cfg.get_dev_directories(),
+            vec![
+                PathBuf::from("/ssd/dev").as_path(),
+                PathBuf::from("/hdd/dev").as_path(),
+                PathBuf::from("/usb/dev").as_path()
+            ]
+        );
+        assert_eq!(
+            cfg.get_scratch_directory(),
+            PathBuf::from("/ssd/dev/scratch")
+        );
+
+        let cfg = Config::from_str("directory: /test/dev").unwrap();
+        assert_eq!(
+            cfg.get_dev_directories(),
+            vec![PathBuf::from("/test/dev").as_path()]
+        );
+        assert!(!cfg.to_string().unwrap().contains("directories"));
+        assert!(!cfg.to_string().unwrap().contains("keychain"));
+    }
+
     #[test]
     fn load_from_string_with_new_apps() {
         match Config::from_str(
@@ -390,21 +811,122 @@ apps:
                 website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}".to_string(),
                 git_url: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git".to_string(),
                 http_url: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git".to_string(),
+                oauth_client_id: None,
             }),
         };
 
-        let new_cfg = cfg.apply_template(template.clone(), false).unwrap();
+        let mut doc = YamlDocument::new("# My config\ndirectory: /dev\n");
+        cfg.apply_template(&mut doc, template.clone(), false)
+            .unwrap();
+
+        let new_cfg = cfg.with_user_file(&doc.to_string()).unwrap();
         assert!(
             new_cfg.get_app("test-app").is_some(),
             "the test-app should have been added"
         );
+        assert!(
+            new_cfg.get_app("shell").is_some(),
+            "the default apps should have been kept"
+        );
         assert!(
             new_cfg.get_service("example.com").is_some(),
             "the example service should have been registered"
         );
+        assert!(doc
+            .to_string()
+            .starts_with("# My config\ndirectory: /dev\n"));
+
+        assert!(new_cfg
+            .apply_template(&mut doc, template.clone(), false)
+            .is_err());
+        assert!(new_cfg
+            .apply_template(&mut doc, template.clone(), true)
+            .is_ok());
+
+        new_cfg
+            .remove_installed(
+                &mut doc,
+                &InstalledEntry::new("local:apps/test-app", "1.0.0")
+                    .with_app("test-app")
+                    .with_service("example.com"),
+            )
+            .unwrap();
+        let removed_cfg = new_cfg.with_user_file(&doc.to_string()).unwrap();
+        assert!(
+            removed_cfg.get_app("test-app").is_none(),
+            "the test-app should have been removed"
+        );
+        assert!(
+            removed_cfg.get_service("example.com").is_none(),
+            "the example service should have been removed"
+        );
+        assert!(
+            removed_cfg.get_service("github.com").is_some(),
+            "other services should not have been removed"
+        );
+    }
+
+    #[test]
+    fn installed_entries() {
+        let cfg = Config::default();
+        let mut doc = YamlDocument::new(
+            "directory: /dev\napps:\n  - name: test\n    command: test\n  - name: other\n    command: other\n",
+        );
+        cfg.add_installed(
+            &mut doc,
+            &InstalledEntry::new("git-tool:apps/test", "1.0.0").with_app("test"),
+        )
+        .unwrap();
+        cfg.add_installed(
+            &mut doc,
+            &InstalledEntry::new("git-tool:apps/test", "1.1.0").with_app("test"),
+        )
+        .unwrap();
 
-        assert!(new_cfg.apply_template(template.clone(), false).is_err());
-        assert!(new_cfg.apply_template(template.clone(), true).is_ok());
+        let cfg = Config::from_str(&doc.to_string()).unwrap();
+        assert_eq!(cfg.get_installed().count(), 1);
+
+        let installed = cfg.get_installed_entry("git-tool:apps/test").unwrap();
+        assert_eq!(installed.get_version(), "1.1.0");
+        assert_eq!(installed.get_apps(), &["test".to_string()]);
+
+        cfg.remove_installed(&mut doc, installed).unwrap();
+        let cfg = Config::from_str(&doc.to_string()).unwrap();
+        assert!(cfg.get_installed_entry("git-tool:apps/test").is_none());
+        assert!(cfg.get_app("test").is_none());
+        assert!(cfg.get_app("other").is_some());
+    }
+
+    #[test]
+    fn test_with_user_file() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("system.yml"), "directory: /dev\n").unwrap();
+        std::fs::write(temp.path().join("user.yml"), "aliases: {}\n").unwrap();
+
+        let cfg = Config::load(
+            Some(&temp.path().join("system.yml")),
+            Some(&temp.path().join("user.yml")),
+            vec![("GITTOOL_SCRATCHPADS".to_string(), "~".to_string())],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let updated = cfg
+            .with_user_file(
+                "aliases:\n  gt: github.com/sierrasoftworks/git-tool\nscratchpads: /tmp\n",
+            )
+            .unwrap();
+        assert_eq!(updated.get_dev_directory(), PathBuf::from("/dev"));
+        assert_eq!(updated.get_scratch_directory(), PathBuf::from("~"));
+        assert_eq!(
+            updated.get_alias("gt"),
+            Some("github.com/sierrasoftworks/git-tool".to_string())
+        );
+        assert_eq!(
+            updated.get_config_file(),
+            Some(temp.path().join("user.yml"))
+        );
     }
 
     #[test]
@@ -430,4 +952,184 @@ apps:
             "the file path should have been populated"
         );
     }
+
+    #[test]
+    fn test_load_layers() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("system.yml"),
+            r#"
+directory: /dev
+apps:
+  - name: shell
+    command: bash
+  - name: code
+    command: code
+features:
+  http_transport: true
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("user.yml"),
+            r#"
+apps:
+  - name: vim
+    command: vim
+aliases:
+  gt: github.com/sierrasoftworks/git-tool
+"#,
+        )
+        .unwrap();
+
+        let cfg = Config::load(
+            Some(&temp.path().join("system.yml")),
+            Some(&temp.path().join("user.yml")),
+            vec![(
+                "GITTOOL_FEATURE_NATIVE_CLONE".to_string(),
+                "true".to_string(),
+            )],
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(cfg.get_dev_directory(), PathBuf::from("/dev"));
+        assert_eq!(
+            cfg.get_apps().map(|a| a.get_name()).collect::<Vec<&str>>(),
+            vec!["vim", "shell", "code"]
+        );
+        assert!(cfg.get_features().use_http_transport());
+        assert!(cfg.get_features().use_native_clone());
+        assert!(cfg.get_service("github.com").is_some());
+        assert_eq!(cfg.get_config_file(), Some(temp.path().join("user.yml")));
+
+        let origins = cfg.get_origins().unwrap();
+        let origin_of = |path: &str| {
+            origins
+                .iter()
+                .find(|(p, _, _)| p == path)
+                .map(|(_, _, o)| o.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            origin_of("directory"),
+            format!("system ({})", temp.path().join("system.yml").display())
+        );
+        assert_eq!(
+            origin_of("aliases.gt"),
+            format!("user ({})", temp.path().join("user.yml").display())
+        );
+        assert_eq!(
+            origin_of("features.native_clone"),
+            "environment (GITTOOL_FEATURE_NATIVE_CLONE)"
+        );
+        assert_eq!(origin_of("features.create_remote"), "default");
+        assert_eq!(origin_of("services[github.com].pattern"), "default");
+    }
+
+    #[test]
+    fn test_load_profiles() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("user.yml"),
+            r#"
+directory: /home/user/personal
+apps:
+  - name: shell
+    command: bash
+profiles:
+  - name: work
+    directory: /home/user/work
+    apps:
+      - name: code
+        command: code
+    features:
+      http_transport: true
+  - name: oss
+    directory: /home/user/oss
+    directories:
+      - /mnt/archive/oss
+    keychain:
+      namespace: opensource
+"#,
+        )
+        .unwrap();
+        let user_file = temp.path().join("user.yml");
+
+        let cfg = Config::load(None, Some(&user_file), vec![], None, None).unwrap();
+        assert_eq!(cfg.get_profile(), None);
+        assert_eq!(
+            cfg.get_dev_directory(),
+            PathBuf::from("/home/user/personal")
+        );
+        assert_eq!(cfg.get_keychain().get_namespace(), None);
+        assert_eq!(cfg.get_profiles().count(), 2);
+
+        let cfg = Config::load(
+            None,
+            Some(&user_file),
+            vec![(
+                "GITTOOL_FEATURE_HTTP_TRANSPORT".to_string(),
+                "false".to_string(),
+            )],
+            None,
+            Some(&PathBuf::from("/home/user/work/github.com/acme/api")),
+        )
+        .unwrap();
+        assert_eq!(cfg.get_profile(), Some("work"));
+        assert_eq!(cfg.get_dev_directory(), PathBuf::from("/home/user/work"));
+        assert_eq!(
+            cfg.get_apps().map(|a| a.get_name()).collect::<Vec<&str>>(),
+            vec!["code", "shell"]
+        );
+        assert!(!cfg.get_features().use_http_transport());
+        assert_eq!(cfg.get_keychain().get_namespace(), Some("work"));
+        assert_eq!(
+            cfg.get_base().get_dev_directory(),
+            PathBuf::from("/home/user/personal")
+        );
+        assert_eq!(cfg.get_base().get_profile(), None);
+
+        let origins = cfg.get_origins().unwrap();
+        assert!(origins
+            .iter()
+            .any(|(p, _, o)| p == "directory" && o == "profile (work)"));
+
+        let cfg = Config::load(
+            None,
+            Some(&user_file),
+            vec![],
+            Some("oss"),
+            Some(&PathBuf::from("/home/user/work")),
+        )
+        .unwrap();
+        assert_eq!(cfg.get_profile(), Some("oss"));
+        assert_eq!(cfg.get_dev_directory(), PathBuf::from("/home/user/oss"));
+        assert_eq!(cfg.get_dev_directories().len(), 2);
+
+        let cfg = Config::load(
+            None,
+            Some(&user_file),
+            vec![],
+            None,
+            Some(&PathBuf::from("/mnt/archive/oss/github.com")),
+        )
+        .unwrap();
+        assert_eq!(cfg.get_profile(), Some("oss"));
+        assert_eq!(cfg.get_keychain().get_namespace(), Some("opensource"));
+        assert!(!cfg.to_string().unwrap().contains("opensource:"));
+
+        let err = Config::load(None, Some(&user_file), vec![], Some("missing"), None)
+            .expect_err("an unknown profile should be rejected");
+        assert!(err.message().contains("missing"));
+    }
+
+    #[test]
+    fn test_load_defaults() {
+        let cfg = Config::load(None, None, vec![], None, None).unwrap();
+        assert_eq!(cfg.get_apps().count(), Config::default().get_apps().count());
+        assert!(cfg.get_config_file().is_none());
+    }
 }
@@ -155,8 +155,18 @@ where
         }
     }
 
-    pub fn with_config_file(self, cfg_file: &str) -> Result<Self, Error> {
-        let cfg = Config::from_file(&std::path::PathBuf::from(cfg_file))?;
+    pub fn with_config_file(
+        self,
+        cfg_file: Option<&str>,
+        profile: Option<&str>,
+    ) -> Result<Self, Error> {
+        let cfg = Config::load(
+            Config::get_system_config_file().as_deref(),
+            cfg_file.map(std::path::Path::new),
+            std::env::vars(),
+            profile,
+            std::env::current_dir().ok().as_deref(),
+        )?;
 
         Ok(self.with_config(&cfg))
     }
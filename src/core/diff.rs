@@ -0,0 +1,160 @@
+/// The number of unchanged lines shown around each change in a diff.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Builds a unified diff (like `diff -u`) showing the changes needed to turn `old` into `new`,
+/// returning an empty string if they are the same.
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let lines = diff_lines(
+        &old.lines().collect::<Vec<&str>>(),
+        &new.lines().collect::<Vec<&str>>(),
+    );
+
+    if lines.iter().all(|l| matches!(l, Line::Same(_))) {
+        return String::new();
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", old_name, new_name);
+
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, Line::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Group changes which are close enough together that their context would overlap into hunks.
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &i in changes.iter() {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(lines.len());
+
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let old_start = lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, Line::Added(_)))
+            .count();
+        let new_start = lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, Line::Removed(_)))
+            .count();
+        let old_len = lines[start..end]
+            .iter()
+            .filter(|l| !matches!(l, Line::Added(_)))
+            .count();
+        let new_len = lines[start..end]
+            .iter()
+            .filter(|l| !matches!(l, Line::Removed(_)))
+            .count();
+
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + if old_len > 0 { 1 } else { 0 },
+            old_len,
+            new_start + if new_len > 0 { 1 } else { 0 },
+            new_len
+        ));
+
+        for line in lines[start..end].iter() {
+            match line {
+                Line::Same(l) => output.push_str(&format!(" {}\n", l)),
+                Line::Removed(l) => output.push_str(&format!("-{}\n", l)),
+                Line::Added(l) => output.push_str(&format!("+{}\n", l)),
+            }
+        }
+    }
+
+    output
+}
+
+/// Finds the longest common subsequence of lines, describing every other line as removed or added.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = vec![];
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+
+    lines.extend(old[i..].iter().map(|l| Line::Removed(l)));
+    lines.extend(new[j..].iter().map(|l| Line::Added(l)));
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new"), "");
+    }
+
+    #[test]
+    fn changed() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n11\n12\n13\n";
+
+        assert_eq!(
+            unified_diff(old, new, "a/config.yml", "b/config.yml"),
+            "--- a/config.yml
++++ b/config.yml
+@@ -2,7 +2,7 @@
+ 2
+ 3
+ 4
+-5
++five
+ 6
+ 7
+ 8
+@@ -10,3 +10,4 @@
+ 10
+ 11
+ 12
++13
+"
+        );
+    }
+
+    #[test]
+    fn from_empty() {
+        assert_eq!(
+            unified_diff("", "a\nb\n", "old", "new"),
+            "--- old\n+++ new\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+    }
+}
@@ -6,7 +6,7 @@ fn default_as_true() -> bool {
 
 #[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 pub struct Features {
-    #[serde(default = "default_as_true")]
+    #[serde(default)]
     native_clone: bool,
     #[serde(default = "default_as_true")]
     create_remote: bool,
@@ -40,6 +40,10 @@ impl Features {
         }
     }
 
+    pub fn use_native_clone(&self) -> bool {
+        self.native_clone
+    }
+
     pub fn use_http_transport(&self) -> bool {
         self.http_transport
     }
@@ -102,4 +106,12 @@ mod tests {
         assert_eq!(Features::default().create_remote, true);
         assert_eq!(Features::default().http_transport, false);
     }
+
+    #[test]
+    fn deserialize_defaults() {
+        let features: Features = serde_yaml::from_str("http_transport: true").unwrap();
+        assert_eq!(features.native_clone, false);
+        assert_eq!(features.create_remote, true);
+        assert_eq!(features.create_remote_private, true);
+    }
 }
@@ -1,9 +1,6 @@
 use super::app;
 use super::Error;
-use super::{
-    templates::{render, render_list},
-    Config, Target,
-};
+use super::{Config, Target};
 use async_trait::async_trait;
 use futures::{pin_mut, select, FutureExt};
 
@@ -35,9 +32,9 @@ impl Launcher for TokioLauncher {
     async fn run(&self, a: &app::App, t: &(dyn Target + Send + Sync)) -> Result<i32, Error> {
         let context = t.template_context(&self.config);
 
-        let program = render(a.get_command(), context.clone())?;
-        let args = render_list(a.get_args(), context.clone())?;
-        let env_args = render_list(a.get_environment(), context.clone())?;
+        let program = a.get_program(context.clone())?;
+        let args = a.get_program_args(context.clone())?;
+        let env_args = a.get_program_environment(context.clone())?;
         let env_arg_tuples = env_args
             .iter()
             .map(|i| i.split("=").collect())
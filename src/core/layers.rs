@@ -0,0 +1,320 @@
+use super::super::errors;
+use super::yaml::{item_name, YamlPath};
+use serde_yaml::{Mapping, Value};
+use std::path;
+
+/// A single source of configuration, like the system-wide config file, your own
+/// config file or an environment variable, which is layered over those before it.
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    kind: String,
+    origin: String,
+    value: Value,
+}
+
+impl ConfigLayer {
+    pub fn new(kind: &str, origin: &str, value: Value) -> Self {
+        Self {
+            kind: kind.to_string(),
+            origin: origin.to_string(),
+            value,
+        }
+    }
+
+    pub fn from_file(kind: &str, path: &path::Path) -> Result<Self, errors::Error> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_str(kind, path, &text)
+    }
+
+    /// Creates a layer from the contents of a config file, which may not have been written yet.
+    pub fn from_str(kind: &str, path: &path::Path, text: &str) -> Result<Self, errors::Error> {
+        let value: Value = serde_yaml::from_str(text).map_err(|e| {
+            errors::user_with_internal(
+                &format!("We couldn't parse your {} configuration file.", kind),
+                &format!(
+                    "Please make sure that the YAML in '{}' is correctly formatted.",
+                    path.display()
+                ),
+                e,
+            )
+        })?;
+
+        Ok(Self {
+            kind: kind.to_string(),
+            origin: format!("{} ({})", kind, path.display()),
+            value: match value {
+                Value::Null => Value::Mapping(Mapping::new()),
+                value => value,
+            },
+        })
+    }
+
+    /// Creates the layers for any of the `GITTOOL_*` environment variables which override config values.
+    pub fn from_env<I>(vars: I) -> Result<Vec<Self>, errors::Error>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut layers = vec![];
+
+        for (name, raw_value) in vars {
+            if let Some(path) = env_path(&name) {
+                // Only feature flags are parsed, so that paths like `~` are not read as YAML nulls.
+                let value = match raw_value.parse::<bool>() {
+                    Ok(flag) if path.starts_with("features.") => Value::Bool(flag),
+                    _ => Value::String(raw_value.clone()),
+                };
+
+                let mut layer = Value::Mapping(Mapping::new());
+                YamlPath::parse(&path)?.set(&mut layer, value)?;
+
+                layers.push(Self {
+                    kind: "environment".to_string(),
+                    origin: format!("environment ({})", name),
+                    value: layer,
+                });
+            }
+        }
+
+        layers.sort_by(|a, b| a.origin.cmp(&b.origin));
+
+        Ok(layers)
+    }
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get_value(&self) -> &Value {
+        &self.value
+    }
+}
+
+/// Gets the config path which is overridden by an environment variable, if any.
+fn env_path(name: &str) -> Option<String> {
+    match name {
+        "GITTOOL_DIRECTORY" => Some("directory".to_string()),
+        "GITTOOL_SCRATCHPADS" => Some("scratchpads".to_string()),
+        "GITTOOL_KEYCHAIN_BACKEND" => Some("keychain.backend".to_string()),
+        "GITTOOL_KEYCHAIN_PATH" => Some("keychain.path".to_string()),
+        name if name.starts_with("GITTOOL_FEATURE_") && name.len() > 16 => {
+            Some(format!("features.{}", name[16..].to_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Merges a layer over the values from the layers before it. Mappings are merged key by key,
+/// lists of named items (like apps and services) are merged by their name (or domain) and
+/// everything else is replaced.
+pub fn merge(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay.iter() {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(overlay))
+            if is_named_list(base) && is_named_list(overlay) =>
+        {
+            // Items from the overlay come first so that it can choose your default app and service.
+            let mut merged = vec![];
+            for item in overlay.iter() {
+                let mut item = item.clone();
+                if let Some(existing) = base.iter().find(|b| item_name(b) == item_name(&item)) {
+                    let mut existing = existing.clone();
+                    merge(&mut existing, &item);
+                    item = existing;
+                }
+
+                merged.push(item);
+            }
+
+            for item in base.iter() {
+                if !overlay.iter().any(|o| item_name(o) == item_name(item)) {
+                    merged.push(item.clone());
+                }
+            }
+
+            *base = merged;
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+fn is_named_list(items: &[Value]) -> bool {
+    items.iter().all(|item| item_name(item).is_some())
+}
+
+/// Gets each of the values in a config along with the layer that it came from, falling
+/// back to `default` for values which were not set by any layer.
+pub fn origins(config: &Value, layers: &[ConfigLayer]) -> Vec<(String, Value, String)> {
+    let mut leaves = vec![];
+    collect_leaves(config, None, &mut leaves);
+
+    leaves
+        .into_iter()
+        .map(|(path, value)| {
+            let origin = YamlPath::parse(&path)
+                .ok()
+                .and_then(|p| layers.iter().rev().find(|l| p.get(&l.value).is_some()))
+                .map(|l| l.origin.clone())
+                .unwrap_or_else(|| "default".to_string());
+
+            (path, value, origin)
+        })
+        .collect()
+}
+
+fn collect_leaves(value: &Value, path: Option<String>, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, child) in map.iter() {
+                if let Some(key) = key.as_str() {
+                    let child_path = match &path {
+                        Some(path) => format!("{}.{}", path, key),
+                        None => key.to_string(),
+                    };
+
+                    collect_leaves(child, Some(child_path), leaves);
+                }
+            }
+        }
+        Value::Sequence(items) if !items.is_empty() && is_named_list(items) => {
+            for item in items.iter() {
+                let child_path = format!(
+                    "{}[{}]",
+                    path.clone().unwrap_or_default(),
+                    item_name(item).unwrap_or_default()
+                );
+
+                collect_leaves(item, Some(child_path), leaves);
+            }
+        }
+        value => {
+            if let Some(path) = path {
+                leaves.push((path, value.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(origin: &str, yaml: &str) -> ConfigLayer {
+        ConfigLayer {
+            kind: origin.to_string(),
+            origin: origin.to_string(),
+            value: serde_yaml::from_str(yaml).unwrap(),
+        }
+    }
+
+    #[test]
+    fn merge_layers() {
+        let system = layer(
+            "system",
+            r#"
+directory: /dev
+apps:
+  - name: shell
+    command: bash
+  - name: code
+    command: code
+    args: ["."]
+features:
+  http_transport: true
+aliases:
+  blog: github.com/sierrasoftworks/blog
+"#,
+        );
+
+        let user = layer(
+            "user",
+            r#"
+apps:
+  - name: code
+    command: code-insiders
+  - name: vim
+    command: vim
+features:
+  native_clone: true
+aliases:
+  gt: github.com/sierrasoftworks/git-tool
+"#,
+        );
+
+        let mut merged = system.value.clone();
+        merge(&mut merged, &user.value);
+
+        let expected: Value = serde_yaml::from_str(
+            r#"
+directory: /dev
+apps:
+  - name: code
+    command: code-insiders
+    args: ["."]
+  - name: vim
+    command: vim
+  - name: shell
+    command: bash
+features:
+  http_transport: true
+  native_clone: true
+aliases:
+  blog: github.com/sierrasoftworks/blog
+  gt: github.com/sierrasoftworks/git-tool
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(merged, expected);
+
+        let origins = origins(&merged, &[system, user]);
+        let origin_of = |path: &str| {
+            origins
+                .iter()
+                .find(|(p, _, _)| p == path)
+                .map(|(_, _, o)| o.as_str())
+        };
+
+        assert_eq!(origin_of("directory"), Some("system"));
+        assert_eq!(origin_of("apps[code].command"), Some("user"));
+        assert_eq!(origin_of("apps[code].args"), Some("system"));
+        assert_eq!(origin_of("features.native_clone"), Some("user"));
+        assert_eq!(origin_of("aliases.blog"), Some("system"));
+    }
+
+    #[test]
+    fn env_layers() {
+        let layers = ConfigLayer::from_env(vec![
+            (
+                "GITTOOL_FEATURE_HTTP_TRANSPORT".to_string(),
+                "true".to_string(),
+            ),
+            ("GITTOOL_DIRECTORY".to_string(), "~".to_string()),
+            ("GITTOOL_TOKEN_GITHUB_COM".to_string(), "secret".to_string()),
+            (
+                "GITTOOL_KEYCHAIN_PASSPHRASE".to_string(),
+                "secret".to_string(),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].origin, "environment (GITTOOL_DIRECTORY)");
+        assert_eq!(
+            layers[0].get_value(),
+            &serde_yaml::from_str::<Value>("directory: \"~\"").unwrap()
+        );
+        assert_eq!(
+            layers[1].get_value(),
+            &serde_yaml::from_str::<Value>("features: { http_transport: true }").unwrap()
+        );
+    }
+}
@@ -1,34 +1,46 @@
+mod account;
 mod app;
 mod auth;
 mod config;
 mod core;
+mod diff;
 mod features;
 mod launcher;
+mod layers;
 mod output;
+mod profile;
+mod registries;
 mod repo;
 mod resolver;
+mod schema;
 mod scratchpad;
 mod service;
 mod target;
 mod templates;
+mod yaml;
 
 use super::errors;
 pub use errors::Error;
 
 pub use self::core::{Core, CoreBuilder};
+pub use account::Account;
 pub use app::App;
 pub use auth::KeyChain;
 pub use config::Config;
+pub use diff::unified_diff;
 pub use launcher::Launcher;
 pub use output::Output;
+pub use registries::{InstalledEntry, RegistryKind, RegistrySource};
 pub use repo::Repo;
 pub use resolver::Resolver;
-pub use scratchpad::Scratchpad;
+pub use schema::{config_schema, registry_entry_schema};
+pub use scratchpad::{RetentionAction, RetentionPolicy, Scratchpad};
 pub use service::Service;
 pub use target::Target;
+pub use yaml::{format_value, YamlDocument, YamlPath};
 
 pub type DefaultCore = core::DefaultCore;
 pub type DefaultLauncher = launcher::TokioLauncher;
 pub type DefaultResolver = resolver::FileSystemResolver;
-pub type DefaultKeyChain = auth::SystemKeyChain;
+pub type DefaultKeyChain = auth::ConfiguredKeyChain;
 pub type DefaultOutput = output::StdoutOutput;
@@ -0,0 +1,118 @@
+use super::layers::ConfigLayer;
+use super::yaml::YamlPath;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+
+/// A named set of overrides for your config, like the dev directory, services and apps you use
+/// for work. Its tokens are kept separately from your other profiles' in the keychain.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Profile {
+    name: String,
+    #[serde(flatten)]
+    overrides: Mapping,
+}
+
+impl Profile {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Determines whether a directory is within one of this profile's dev or scratchpad
+    /// directories, in which case the profile is used automatically.
+    pub fn contains(&self, dir: &Path) -> bool {
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+
+        self.contains_with_home(dir, home.as_deref())
+    }
+
+    fn contains_with_home(&self, dir: &Path, home: Option<&Path>) -> bool {
+        let dir = normalize(dir, home);
+        let get = |key: &str| self.overrides.get(&Value::String(key.to_string()));
+
+        get("directory")
+            .into_iter()
+            .chain(get("scratchpads"))
+            .chain(
+                get("directories")
+                    .and_then(|v| v.as_sequence())
+                    .into_iter()
+                    .flatten(),
+            )
+            .filter_map(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .any(|p| dir.starts_with(normalize(Path::new(p), home)))
+    }
+
+    /// Gets the config layer for this profile, which uses the profile's name as its keychain
+    /// namespace unless it sets one of its own.
+    pub fn get_layer(&self) -> ConfigLayer {
+        let mut value = Value::Mapping(self.overrides.clone());
+
+        if let Ok(path) = YamlPath::parse("keychain.namespace") {
+            if path.get(&value).is_none() {
+                path.set(&mut value, Value::String(self.name.clone())).ok();
+            }
+        }
+
+        ConfigLayer::new("profile", &format!("profile ({})", self.name), value)
+    }
+}
+
+/// Expands a leading `~` to the user's home directory and resolves any symlinks or `..`
+/// components, so that paths which point at the same place compare as equal.
+fn normalize(path: &Path, home: Option<&Path>) -> PathBuf {
+    let path = match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    };
+
+    path.canonicalize().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(yaml: &str) -> Profile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_contains() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("work").join("repo")).unwrap();
+        std::fs::create_dir_all(temp.path().join("other")).unwrap();
+
+        let p = profile(&format!(
+            "name: work\ndirectory: {}\n",
+            temp.path().join("work").display()
+        ));
+        assert!(p.contains_with_home(&temp.path().join("work").join("repo"), None));
+        assert!(p.contains_with_home(
+            &temp
+                .path()
+                .join("other")
+                .join("..")
+                .join("work")
+                .join("repo"),
+            None
+        ));
+        assert!(!p.contains_with_home(temp.path(), None));
+    }
+
+    #[test]
+    fn test_contains_home() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("work").join("repo")).unwrap();
+        std::fs::create_dir_all(temp.path().join("other")).unwrap();
+
+        let p = profile("name: work\ndirectory: ~/work\nscratchpads: ~/notes\n");
+        assert!(p.contains_with_home(&temp.path().join("work").join("repo"), Some(temp.path())));
+        assert!(p.contains_with_home(&temp.path().join("notes"), Some(temp.path())));
+        assert!(!p.contains_with_home(&temp.path().join("other"), Some(temp.path())));
+        assert!(!p.contains_with_home(&temp.path().join("work"), None));
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A registry of app and service templates which can be added to your config with
+/// `gt config add`. Entries from each registry are namespaced by the registry's name.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RegistrySource {
+    name: String,
+    #[serde(flatten)]
+    kind: RegistryKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum RegistryKind {
+    /// A folder of entries within a GitHub repository, like the `registry/` folder in Git-Tool's own repo.
+    #[serde(rename = "github")]
+    GitHub {
+        repo: String,
+        #[serde(rename = "ref", default = "default_github_ref")]
+        git_ref: String,
+        #[serde(default = "default_github_path")]
+        path: String,
+    },
+    /// A local directory containing entries, like a checkout of your company's registry.
+    #[serde(rename = "directory")]
+    Directory { path: PathBuf },
+    /// A web server which hosts an `index.yaml` listing its entries, alongside each `$id.yaml` entry.
+    #[serde(rename = "http")]
+    Http { url: String },
+}
+
+fn default_github_ref() -> String {
+    "main".to_string()
+}
+
+fn default_github_path() -> String {
+    "registry".to_string()
+}
+
+impl RegistrySource {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> &RegistryKind {
+        &self.kind
+    }
+}
+
+/// A record of a registry entry which has been added to your config, used to remove it
+/// again with `gt config remove` or to notice when it has changed with `gt config upgrade`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InstalledEntry {
+    id: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    apps: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    services: Vec<String>,
+}
+
+impl InstalledEntry {
+    pub fn new(id: &str, version: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            version: version.to_string(),
+            apps: vec![],
+            services: vec![],
+        }
+    }
+
+    pub fn with_app(mut self, name: &str) -> Self {
+        if !self.apps.iter().any(|a| a == name) {
+            self.apps.push(name.to_string());
+        }
+
+        self
+    }
+
+    pub fn with_service(mut self, domain: &str) -> Self {
+        if !self.services.iter().any(|s| s == domain) {
+            self.services.push(domain.to_string());
+        }
+
+        self
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_version(&self) -> &str {
+        &self.version
+    }
+
+    /// Gets the names of the apps which were added to your config by this entry.
+    pub fn get_apps(&self) -> &[String] {
+        &self.apps
+    }
+
+    /// Gets the domains of the services which were added to your config by this entry.
+    pub fn get_services(&self) -> &[String] {
+        &self.services
+    }
+}
+
+impl Default for RegistrySource {
+    fn default() -> Self {
+        Self {
+            name: "git-tool".to_string(),
+            kind: RegistryKind::GitHub {
+                repo: "SierraSoftworks/git-tool".to_string(),
+                git_ref: default_github_ref(),
+                path: default_github_path(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let sources: Vec<RegistrySource> = serde_yaml::from_str(
+            r#"
+- name: community
+  type: github
+  repo: SierraSoftworks/git-tool
+- name: acme
+  type: github
+  repo: acme/git-tool-registry
+  ref: v2
+  path: entries
+- name: local
+  type: directory
+  path: /opt/git-tool/registry
+- name: internal
+  type: http
+  url: https://registry.acme.com/git-tool
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(sources.len(), 4);
+        assert_eq!(sources[0].kind, RegistrySource::default().kind);
+        assert_eq!(
+            sources[1].get_kind(),
+            &RegistryKind::GitHub {
+                repo: "acme/git-tool-registry".to_string(),
+                git_ref: "v2".to_string(),
+                path: "entries".to_string(),
+            }
+        );
+        assert_eq!(
+            sources[2].get_kind(),
+            &RegistryKind::Directory {
+                path: PathBuf::from("/opt/git-tool/registry")
+            }
+        );
+        assert_eq!(sources[3].get_name(), "internal");
+        assert_eq!(
+            sources[3].get_kind(),
+            &RegistryKind::Http {
+                url: "https://registry.acme.com/git-tool".to_string()
+            }
+        );
+    }
+}
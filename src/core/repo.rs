@@ -61,6 +61,7 @@ impl Repo {
     }
 
     pub fn valid(&self) -> bool {
-        self.path.join(".git").is_dir()
+        // In a worktree, `.git` is a file which points at the parent repository.
+        self.path.join(".git").exists()
     }
 }
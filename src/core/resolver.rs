@@ -64,31 +64,43 @@ impl Resolver for FileSystemResolver {
             Ok(repo) => Ok(repo),
             Err(e) => Err(errors::user_with_internal(
                 &format!("Current directory ('{}') is not a valid repository.", cwd.display()),
-                &format!("Make sure that you are currently within a repository contained within your development directory ('{}').", self.config.get_dev_directory().canonicalize()?.display()),
+                &format!("Make sure that you are currently within a repository contained within one of your development directories ({}).", self.describe_dev_directories()),
                 e))
         }
     }
 
     fn get_repo(&self, path: &std::path::PathBuf) -> Result<Repo, Error> {
-        let dev_dir = self.config.get_dev_directory().canonicalize()?;
-        let dir = if path.is_absolute() {
-            path.canonicalize()?
-        } else {
-            dev_dir.join(path)
-        };
-
-        if !dir.starts_with(&dev_dir) || dir == dev_dir {
-            return Err(errors::user(
-                "Current directory is not a valid repository.",
-                &format!("Make sure that you are currently within a repository contained within your development directory ('{}').", dev_dir.display())));
+        if !path.is_absolute() {
+            return repo_from_relative_path(
+                &self.config,
+                &self.config.get_dev_directories(),
+                path,
+                false,
+            );
         }
 
-        match dir.strip_prefix(&dev_dir) {
-            Ok(relative_path) => repo_from_relative_path(&self.config, &relative_path.to_path_buf(), false),
-            Err(e) => Err(errors::system_with_internal(
-                "We were unable to determine the repository's fully qualified name.", 
-                &format!("Make sure that you are currently within a repository contained within your development directory ('{}').", dev_dir.display()),
-                e))
+        let dir = path.canonicalize()?;
+
+        // When one of your dev directories is nested within another, the most specific one wins.
+        let root = self
+            .config
+            .get_dev_directories()
+            .into_iter()
+            .filter_map(|root| root.canonicalize().ok().map(|canonical| (root, canonical)))
+            .filter(|(_, canonical)| dir.starts_with(canonical) && &dir != canonical)
+            .max_by_key(|(_, canonical)| canonical.components().count());
+
+        match root {
+            Some((root, canonical)) => match dir.strip_prefix(&canonical) {
+                Ok(relative_path) => repo_from_relative_path(&self.config, &[root], &relative_path.to_path_buf(), false),
+                Err(e) => Err(errors::system_with_internal(
+                    "We were unable to determine the repository's fully qualified name.",
+                    &format!("Make sure that you are currently within a repository contained within one of your development directories ({}).", self.describe_dev_directories()),
+                    e))
+            },
+            None => Err(errors::user(
+                "Current directory is not a valid repository.",
+                &format!("Make sure that you are currently within a repository contained within one of your development directories ({}).", self.describe_dev_directories())))
         }
     }
 
@@ -109,7 +121,7 @@ impl Resolver for FileSystemResolver {
 
         match repos.len() {
             0 => {
-                match repo_from_relative_path(&self.config, &true_name, true) {
+                match repo_from_relative_path(&self.config, &self.config.get_dev_directories(), &true_name, true) {
                     Ok(repo) => Ok(repo.clone()),
                     Err(_) => Err(errors::user("No matching repository found.", "Please check that you have provided the correct name for the repository and try again."))
                 }
@@ -125,27 +137,40 @@ impl Resolver for FileSystemResolver {
     }
 
     fn get_repos(&self) -> Result<Vec<Repo>, Error> {
-        let mut repos = vec![];
+        let mut services: Vec<&Service> = vec![];
+
+        for (i, root) in self.config.get_dev_directories().into_iter().enumerate() {
+            // Your other dev directories might be on a drive which isn't connected right now,
+            // so only your primary dev directory is required to exist.
+            if i > 0 && !root.is_dir() {
+                continue;
+            }
 
-        for svc_dir in self.config.get_dev_directory().read_dir()? {
-            match svc_dir {
-                Ok(dir) => {
-                    if dir.file_type()?.is_dir() {
-                        match self.config.get_service(dir.file_name().to_str().unwrap()) {
-                            Some(svc) => {
-                                repos.extend(self.get_repos_for(svc)?);
-                            },
-                            None => {}
+            for svc_dir in root.read_dir()? {
+                match svc_dir {
+                    Ok(dir) => {
+                        if dir.file_type()?.is_dir() {
+                            match self.config.get_service(dir.file_name().to_str().unwrap()) {
+                                Some(svc) if !services.iter().any(|s| s.get_domain() == svc.get_domain()) => {
+                                    services.push(svc);
+                                },
+                                _ => {}
+                            }
                         }
-                    }
-                },
-                Err(e) => return Err(errors::system_with_internal(
-                    "We were unable to access your development directory.",
-                    "Please make sure that your development directory exists and that git-tool has permission to access it.",
-                    e))
+                    },
+                    Err(e) => return Err(errors::system_with_internal(
+                        "We were unable to access your development directory.",
+                        "Please make sure that your development directory exists and that git-tool has permission to access it.",
+                        e))
+                }
             }
         }
 
+        let mut repos = vec![];
+        for svc in services {
+            repos.extend(self.get_repos_for(svc)?);
+        }
+
         Ok(repos)
     }
 
@@ -156,19 +181,53 @@ impl Resolver for FileSystemResolver {
                 "Please ensure that the glob pattern you have used for this service (in your config file) is valid and try again."));
         }
 
-        let path = self.config.get_dev_directory().join(svc.get_domain());
+        let mut repos: Vec<Repo> = vec![];
+        for root in self.config.get_dev_directories() {
+            let path = root.join(svc.get_domain());
+            if !path.is_dir() {
+                continue;
+            }
 
-        let repos = get_child_directories(&path, &svc.get_pattern())
-            .iter()
-            .map(|p| self.get_repo(p))
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
+            let found: Vec<Repo> = get_child_directories(&path, &svc.get_pattern())
+                .iter()
+                .filter(|p| {
+                    !p.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| n.contains('@'))
+                        .unwrap_or_default()
+                })
+                .map(|p| self.get_repo(p))
+                .filter(|r| r.is_ok())
+                .map(|r| r.unwrap())
+                .collect();
+
+            // If a repository has been cloned into more than one of your dev directories, the
+            // copy in the earliest directory is the one which is used.
+            for repo in found {
+                if !repos
+                    .iter()
+                    .any(|r| r.get_full_name() == repo.get_full_name())
+                {
+                    repos.push(repo);
+                }
+            }
+        }
 
         Ok(repos)
     }
 }
 
+impl FileSystemResolver {
+    fn describe_dev_directories(&self) -> String {
+        self.config
+            .get_dev_directories()
+            .iter()
+            .map(|d| format!("'{}'", d.display()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
 fn service_from_relative_path<'a>(
     config: &'a Config,
     relative_path: &std::path::PathBuf,
@@ -196,8 +255,11 @@ fn service_from_relative_path<'a>(
     }
 }
 
+/// Builds the repo for a path relative to one of your dev directories, using the first of the
+/// `roots` which already contains it (or the first root, where it would be cloned, if none do).
 fn repo_from_relative_path<'a>(
     config: &'a Config,
+    roots: &[&std::path::Path],
     relative_path: &std::path::PathBuf,
     fallback_to_default: bool,
 ) -> Result<Repo, Error> {
@@ -215,6 +277,14 @@ fn repo_from_relative_path<'a>(
         .map(|c| c.as_os_str().to_str().unwrap().to_string())
         .collect();
 
+    // Worktrees live alongside their repository as `$REPO@$BRANCH`, so we map them back
+    // to the repository they belong to while retaining the worktree's path.
+    if let Some(name) = name_parts.last_mut() {
+        if let Some(idx) = name.find('@') {
+            name.truncate(idx);
+        }
+    }
+
     let mut true_path = relative_path.to_path_buf();
 
     if fallback_to_default && !relative_path.starts_with(svc.get_domain()) {
@@ -233,9 +303,16 @@ fn repo_from_relative_path<'a>(
             "Make sure that your repository is correctly named for the service you are using.",
         ))
     } else {
+        let root = roots
+            .iter()
+            .find(|root| root.join(&true_path).exists())
+            .or_else(|| roots.first())
+            .map(|root| root.to_path_buf())
+            .unwrap_or_else(|| config.get_dev_directory().to_path_buf());
+
         Ok(Repo::new(
             &name_parts.join("/"),
-            to_native_path(config.get_dev_directory().join(true_path)),
+            to_native_path(root.join(true_path)),
         ))
     }
 }
@@ -542,6 +619,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_repo_worktree() {
+        let resolver = get_resolver();
+
+        let example = resolver
+            .get_repo(&path::PathBuf::from(
+                "github.com/sierrasoftworks/test1@feature-test",
+            ))
+            .unwrap();
+        assert_eq!(example.get_full_name(), "sierrasoftworks/test1");
+        assert_eq!(
+            example.get_path(),
+            resolver
+                .config
+                .get_dev_directory()
+                .join("github.com")
+                .join("sierrasoftworks")
+                .join("test1@feature-test")
+        );
+    }
+
     #[test]
     fn get_repo_new() {
         let resolver = get_resolver();
@@ -593,6 +691,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_repos_multiple_directories() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssd = temp.path().join("ssd");
+        let hdd = temp.path().join("hdd");
+
+        std::fs::create_dir_all(
+            ssd.join("github.com")
+                .join("sierrasoftworks")
+                .join("active"),
+        )
+        .unwrap();
+        std::fs::create_dir_all(ssd.join("github.com").join("sierrasoftworks").join("both"))
+            .unwrap();
+        std::fs::create_dir_all(hdd.join("github.com").join("sierrasoftworks").join("both"))
+            .unwrap();
+        std::fs::create_dir_all(
+            hdd.join("gitlab.com")
+                .join("sierrasoftworks")
+                .join("archived"),
+        )
+        .unwrap();
+
+        let config = Config::from_str(&format!(
+            "directory: {}\ndirectories:\n  - {}\n  - {}\n",
+            ssd.display(),
+            hdd.display(),
+            temp.path().join("disconnected").display()
+        ))
+        .unwrap();
+        let resolver = FileSystemResolver::from(Arc::new(config));
+
+        let mut repos: Vec<String> = resolver
+            .get_repos()
+            .unwrap()
+            .iter()
+            .map(|r| format!("{}/{}", r.get_domain(), r.get_full_name()))
+            .collect();
+        repos.sort();
+        assert_eq!(
+            repos,
+            vec![
+                "github.com/sierrasoftworks/active",
+                "github.com/sierrasoftworks/both",
+                "gitlab.com/sierrasoftworks/archived"
+            ]
+        );
+
+        let archived = resolver
+            .get_best_repo("gitlab.com/sierrasoftworks/archived")
+            .unwrap();
+        assert_eq!(
+            archived.get_path(),
+            hdd.join("gitlab.com")
+                .join("sierrasoftworks")
+                .join("archived")
+        );
+
+        let both = resolver
+            .get_repo(&path::PathBuf::from("github.com/sierrasoftworks/both"))
+            .unwrap();
+        assert_eq!(
+            both.get_path(),
+            ssd.join("github.com").join("sierrasoftworks").join("both")
+        );
+
+        let from_path = resolver
+            .get_repo(
+                &hdd.join("gitlab.com")
+                    .join("sierrasoftworks")
+                    .join("archived"),
+            )
+            .unwrap();
+        assert_eq!(from_path.get_full_name(), "sierrasoftworks/archived");
+
+        let new = resolver.get_best_repo("sierrasoftworks/new").unwrap();
+        assert_eq!(
+            new.get_path(),
+            ssd.join("github.com").join("sierrasoftworks").join("new")
+        );
+
+        resolver
+            .get_repo(&temp.path().to_path_buf())
+            .expect_err("paths outside your dev directories are not repositories");
+    }
+
     #[test]
     fn get_child_directories() {
         let children = super::get_child_directories(&get_dev_dir().join("github.com"), "*/*");
@@ -0,0 +1,1033 @@
+use crate::online::registry::PLATFORMS;
+use serde_json::{json, Value};
+
+const DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Builds the JSON Schema for Git-Tool's config file, which editors can use to validate
+/// and autocomplete it.
+pub fn config_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "$id": "https://raw.githubusercontent.com/SierraSoftworks/git-tool/main/schemas/config.schema.json",
+        "title": "Git-Tool Config",
+        "description": "The configuration file used by Git-Tool.",
+        "type": "object",
+        "required": ["directory"],
+        "additionalProperties": false,
+        "properties": {
+            "directory": {
+                "description": "The directory which your repositories are stored in, organized by service and namespace.",
+                "type": "string"
+            },
+            "directories": {
+                "description": "Other directories which your repositories are stored in, searched in order after your primary `directory` (which new repositories are cloned into).",
+                "type": "array",
+                "items": { "type": "string" }
+            },
+            "scratchpads": {
+                "description": "The directory which your weekly scratchpads are stored in (defaults to the scratch folder in your dev directory).",
+                "type": "string"
+            },
+            "scratchpad_retention": { "$ref": "#/definitions/scratchpadRetention" },
+            "clone_timeout": {
+                "description": "The number of seconds that `git clone` is allowed to run for before it is stopped (by default it is never stopped).",
+                "type": "integer",
+                "minimum": 1
+            },
+            "services": {
+                "description": "The services which host your repositories, the first of which is your default service.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/service" }
+            },
+            "apps": {
+                "description": "The applications which can be launched in your repositories and scratchpads, the first of which is your default app.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/app" }
+            },
+            "aliases": {
+                "description": "Short names for your repositories, mapped to their fully qualified names (like github.com/sierrasoftworks/git-tool).",
+                "type": "object",
+                "additionalProperties": { "type": "string" }
+            },
+            "features": { "$ref": "#/definitions/features" },
+            "keychain": { "$ref": "#/definitions/keychain" },
+            "registries": {
+                "description": "The registries which app and service templates can be added from, in addition to Git-Tool's own registry.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/registry" }
+            },
+            "installed": {
+                "description": "The registry entries which have been added to your config with `gt config add`.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/installedEntry" }
+            },
+            "profiles": {
+                "description": "Named sets of overrides for your config, like those you use for work, selected with --profile, GITTOOL_PROFILE or by working within their directory.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/profile" }
+            }
+        },
+        "definitions": {
+            "service": {
+                "description": "A service which hosts Git repositories.",
+                "type": "object",
+                "required": ["domain", "website", "httpUrl", "gitUrl", "pattern"],
+                "additionalProperties": false,
+                "properties": {
+                    "domain": {
+                        "description": "The domain name of the service, which is also the name of its folder in your dev directory.",
+                        "type": "string"
+                    },
+                    "website": {
+                        "description": "The template used to build the URL of a repository's web page.",
+                        "type": "string"
+                    },
+                    "httpUrl": {
+                        "description": "The template used to build the URL used to clone a repository over HTTPS.",
+                        "type": "string"
+                    },
+                    "gitUrl": {
+                        "description": "The template used to build the URL used to clone a repository over SSH.",
+                        "type": "string"
+                    },
+                    "pattern": {
+                        "description": "The glob pattern which matches repositories within the service's folder.",
+                        "type": "string",
+                        "pattern": "^\\*(/\\*)*$"
+                    },
+                    "oauthClientId": {
+                        "description": "The OAuth client ID used by `gt auth --web` to sign in to the service.",
+                        "type": "string"
+                    },
+                    "accounts": {
+                        "description": "The accounts you use on this service, matched to repositories by their namespace.",
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/account" }
+                    },
+                    "gitConfig": {
+                        "description": "Git config values which should be set on repositories from this service.",
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/gitConfig" }
+                    }
+                }
+            },
+            "account": {
+                "description": "An account on a service, used for the repositories in its namespaces.",
+                "type": "object",
+                "required": ["name"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {
+                        "description": "The name of the account, which its access token is stored under.",
+                        "type": "string"
+                    },
+                    "namespaces": {
+                        "description": "The namespaces (like your organization) which this account is used for.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "sshHost": {
+                        "description": "The host alias from your SSH config which should be used to clone repositories with this account.",
+                        "type": "string"
+                    },
+                    "userName": {
+                        "description": "The user.name which should be set on repositories used with this account.",
+                        "type": "string"
+                    },
+                    "userEmail": {
+                        "description": "The user.email which should be set on repositories used with this account.",
+                        "type": "string"
+                    }
+                }
+            },
+            "gitConfig": {
+                "description": "A Git config value which should be set on repositories.",
+                "type": "object",
+                "required": ["key", "value"],
+                "additionalProperties": false,
+                "properties": {
+                    "key": {
+                        "description": "The Git config key, like core.autocrlf.",
+                        "type": "string"
+                    },
+                    "value": {
+                        "description": "The value which should be set.",
+                        "type": "string"
+                    },
+                    "namespace": {
+                        "description": "A pattern which limits this value to repositories in matching namespaces.",
+                        "type": "string"
+                    }
+                }
+            },
+            "app": {
+                "description": "An application which can be launched in your repositories and scratchpads.",
+                "type": "object",
+                "required": ["name", "command"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {
+                        "description": "The name used to launch the app, like `gt o code`.",
+                        "type": "string"
+                    },
+                    "command": {
+                        "description": "The template for the program which should be run.",
+                        "type": "string"
+                    },
+                    "args": {
+                        "description": "The templates for the arguments passed to the program.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "environment": {
+                        "description": "The templates for KEY=value environment variables passed to the program.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    }
+                }
+            },
+            "features": {
+                "description": "Optional features which change how Git-Tool behaves.",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "native_clone": {
+                        "description": "Clone repositories using Git-Tool's built in Git client (libgit2) instead of the git CLI.",
+                        "type": "boolean"
+                    },
+                    "create_remote": {
+                        "description": "Create a repository on the service when you run `gt new`.",
+                        "type": "boolean"
+                    },
+                    "http_transport": {
+                        "description": "Clone repositories over HTTPS instead of SSH.",
+                        "type": "boolean"
+                    },
+                    "create_remote_private": {
+                        "description": "Make the repositories created by `gt new` private.",
+                        "type": "boolean"
+                    },
+                    "open_new_repo_in_default_app": {
+                        "description": "Open new repositories in your default app after they are created.",
+                        "type": "boolean"
+                    }
+                }
+            },
+            "keychain": {
+                "description": "Where Git-Tool stores your access tokens.",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "backend": {
+                        "description": "The secure storage used for your access tokens.",
+                        "type": "string",
+                        "enum": ["system", "file", "env", "pass", "secret-tool"],
+                        "default": "system"
+                    },
+                    "path": {
+                        "description": "The path of the encrypted file used by the file backend.",
+                        "type": "string"
+                    },
+                    "namespace": {
+                        "description": "The namespace your access tokens are stored under, which defaults to the name of the profile you are using.",
+                        "type": "string"
+                    }
+                }
+            },
+            "scratchpadRetention": {
+                "description": "How long your scratchpads are kept for before `gt scratch prune` archives or removes them.",
+                "type": "object",
+                "required": ["weeks"],
+                "additionalProperties": false,
+                "properties": {
+                    "weeks": {
+                        "description": "The number of weeks since a scratchpad was last modified before it is pruned.",
+                        "type": "integer",
+                        "minimum": 0
+                    },
+                    "action": {
+                        "description": "Whether old scratchpads are compressed into a .tar.gz archive or removed.",
+                        "type": "string",
+                        "enum": ["archive", "delete"],
+                        "default": "archive"
+                    }
+                }
+            },
+            "registry": {
+                "description": "A registry of app and service templates.",
+                "type": "object",
+                "required": ["name", "type"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {
+                        "description": "The name of the registry, which its entries are prefixed with.",
+                        "type": "string"
+                    },
+                    "type": {
+                        "description": "The kind of registry.",
+                        "type": "string",
+                        "enum": ["github", "directory", "http"]
+                    },
+                    "repo": {
+                        "description": "The GitHub repository which holds the registry.",
+                        "type": "string"
+                    },
+                    "ref": {
+                        "description": "The branch or tag of the GitHub repository to use.",
+                        "type": "string",
+                        "default": "main"
+                    },
+                    "path": {
+                        "description": "The folder in the GitHub repository, or the local directory, which holds the registry.",
+                        "type": "string"
+                    },
+                    "url": {
+                        "description": "The URL of the web server which hosts the registry.",
+                        "type": "string"
+                    }
+                },
+                "allOf": [
+                    {
+                        "if": { "properties": { "type": { "const": "github" } } },
+                        "then": { "required": ["repo"] }
+                    },
+                    {
+                        "if": { "properties": { "type": { "const": "directory" } } },
+                        "then": { "required": ["path"] }
+                    },
+                    {
+                        "if": { "properties": { "type": { "const": "http" } } },
+                        "then": { "required": ["url"] }
+                    }
+                ]
+            },
+            "installedEntry": {
+                "description": "A registry entry which has been added to your config.",
+                "type": "object",
+                "required": ["id"],
+                "additionalProperties": false,
+                "properties": {
+                    "id": {
+                        "description": "The ID of the entry, prefixed with the name of its registry.",
+                        "type": "string"
+                    },
+                    "version": {
+                        "description": "The version of the entry which was added.",
+                        "type": "string"
+                    },
+                    "apps": {
+                        "description": "The names of the apps which the entry added, which are removed by `gt config remove`.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "services": {
+                        "description": "The domains of the services which the entry added, which are removed by `gt config remove`.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    }
+                }
+            },
+            "profile": {
+                "description": "A named set of overrides which are layered over the rest of your config when the profile is used.",
+                "type": "object",
+                "required": ["name"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {
+                        "description": "The name of the profile, used with --profile and GITTOOL_PROFILE.",
+                        "type": "string"
+                    },
+                    "directory": {
+                        "description": "The directory which this profile's repositories are stored in. The profile is used automatically within it.",
+                        "type": "string"
+                    },
+                    "directories": {
+                        "description": "Other directories which this profile's repositories are stored in.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "scratchpads": {
+                        "description": "The directory which this profile's scratchpads are stored in. The profile is used automatically within it.",
+                        "type": "string"
+                    },
+                    "services": {
+                        "description": "Services which are added to, or override, those in the rest of your config.",
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/service" }
+                    },
+                    "apps": {
+                        "description": "Apps which are added to, or override, those in the rest of your config.",
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/app" }
+                    },
+                    "aliases": {
+                        "description": "Aliases which are added to those in the rest of your config.",
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "features": { "$ref": "#/definitions/features" },
+                    "keychain": { "$ref": "#/definitions/keychain" }
+                }
+            }
+        }
+    })
+}
+
+/// Builds the JSON Schema for the entries in a registry, like those in Git-Tool's `registry/` folder.
+pub fn registry_entry_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "$id": "https://raw.githubusercontent.com/SierraSoftworks/git-tool/main/schemas/registry-entry.schema.json",
+        "title": "Git-Tool Registry Entry",
+        "description": "An app or service template which can be added to your config with `gt config add`.",
+        "type": "object",
+        "required": ["name", "description", "configs"],
+        "additionalProperties": false,
+        "properties": {
+            "name": {
+                "description": "The name of the entry.",
+                "type": "string"
+            },
+            "description": {
+                "description": "A short description of what the entry adds.",
+                "type": "string"
+            },
+            "version": {
+                "description": "The version of the entry, which should be updated whenever it changes so that `gt config upgrade` can apply it.",
+                "type": "string"
+            },
+            "configs": {
+                "description": "The config which should be added on each platform.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/config" }
+            }
+        },
+        "definitions": {
+            "config": {
+                "description": "The app or service which should be added on a platform.",
+                "type": "object",
+                "required": ["platform"],
+                "additionalProperties": false,
+                "properties": {
+                    "platform": {
+                        "description": "The platform which this config is used on.",
+                        "type": "string",
+                        "enum": PLATFORMS
+                    },
+                    "app": { "$ref": "#/definitions/app" },
+                    "service": { "$ref": "#/definitions/service" }
+                }
+            },
+            "app": {
+                "description": "An application which can be launched in your repositories and scratchpads.",
+                "type": "object",
+                "required": ["name", "command"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {
+                        "description": "The name used to launch the app.",
+                        "type": "string"
+                    },
+                    "command": {
+                        "description": "The template for the program which should be run.",
+                        "type": "string"
+                    },
+                    "args": {
+                        "description": "The templates for the arguments passed to the program.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "environment": {
+                        "description": "The templates for KEY=value environment variables passed to the program.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    }
+                }
+            },
+            "service": {
+                "description": "A service which hosts Git repositories.",
+                "type": "object",
+                "required": ["domain", "website", "httpUrl", "gitUrl", "pattern"],
+                "additionalProperties": false,
+                "properties": {
+                    "domain": {
+                        "description": "The domain name of the service.",
+                        "type": "string"
+                    },
+                    "website": {
+                        "description": "The template used to build the URL of a repository's web page.",
+                        "type": "string"
+                    },
+                    "httpUrl": {
+                        "description": "The template used to build the URL used to clone a repository over HTTPS.",
+                        "type": "string"
+                    },
+                    "gitUrl": {
+                        "description": "The template used to build the URL used to clone a repository over SSH.",
+                        "type": "string"
+                    },
+                    "pattern": {
+                        "description": "The glob pattern which matches repositories within the service's folder.",
+                        "type": "string",
+                        "pattern": "^\\*(/\\*)*$"
+                    },
+                    "oauthClientId": {
+                        "description": "The OAuth client ID used by `gt auth --web` to sign in to the service.",
+                        "type": "string"
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Config;
+    use crate::online::registry::Entry;
+    use crate::test::get_repo_root;
+    use serde::ser::{
+        Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+        SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+    };
+    use serde_json::Map;
+    use std::collections::HashSet;
+
+    /// Checks that every field in a value is described by the schema, recording the
+    /// schema properties which were used along the way.
+    fn assert_described(schema: &Value, location: &str, value: &Value, seen: &mut HashSet<String>) {
+        let node = schema
+            .pointer(&location[1..])
+            .unwrap_or_else(|| panic!("{} should exist in the schema", location));
+
+        if let Some(reference) = node.get("$ref").and_then(|r| r.as_str()) {
+            return assert_described(schema, reference, value, seen);
+        }
+
+        match value {
+            Value::Object(fields) => {
+                for (key, child) in fields.iter() {
+                    let child_location = if node.get("properties").is_some() {
+                        format!("{}/properties/{}", location, key)
+                    } else {
+                        format!("{}/additionalProperties", location)
+                    };
+
+                    assert!(
+                        schema.pointer(&child_location[1..]).is_some(),
+                        "the field '{}' is not described by {}",
+                        key,
+                        location
+                    );
+
+                    seen.insert(child_location.clone());
+                    assert_described(schema, &child_location, child, seen);
+                }
+            }
+            Value::Array(items) => {
+                for item in items.iter() {
+                    assert_described(schema, &format!("{}/items", location), item, seen);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Serializes a model like `serde_json::to_value`, but keeps the fields which are skipped
+    /// when they are empty (as nulls) so that every field of each model is checked against the schema.
+    fn record<T: Serialize>(value: &T) -> Value {
+        value.serialize(Recorder).unwrap()
+    }
+
+    struct Recorder;
+
+    struct RecordSeq(Vec<Value>);
+
+    struct RecordMap {
+        fields: Map<String, Value>,
+        key: Option<String>,
+    }
+
+    impl RecordMap {
+        fn new() -> Self {
+            Self {
+                fields: Map::new(),
+                key: None,
+            }
+        }
+    }
+
+    impl Serializer for Recorder {
+        type Ok = Value;
+        type Error = serde_json::Error;
+        type SerializeSeq = RecordSeq;
+        type SerializeTuple = RecordSeq;
+        type SerializeTupleStruct = RecordSeq;
+        type SerializeTupleVariant = RecordSeq;
+        type SerializeMap = RecordMap;
+        type SerializeStruct = RecordMap;
+        type SerializeStructVariant = RecordMap;
+
+        fn serialize_bool(self, v: bool) -> Result<Value, Self::Error> {
+            Ok(Value::Bool(v))
+        }
+
+        fn serialize_i8(self, v: i8) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_i16(self, v: i16) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_i32(self, v: i32) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_i64(self, v: i64) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_u8(self, v: u8) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_u16(self, v: u16) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_u32(self, v: u32) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_u64(self, v: u64) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_f32(self, v: f32) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_f64(self, v: f64) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_char(self, v: char) -> Result<Value, Self::Error> {
+            Ok(Value::String(v.to_string()))
+        }
+
+        fn serialize_str(self, v: &str) -> Result<Value, Self::Error> {
+            Ok(Value::String(v.to_string()))
+        }
+
+        fn serialize_bytes(self, v: &[u8]) -> Result<Value, Self::Error> {
+            Ok(v.into())
+        }
+
+        fn serialize_none(self) -> Result<Value, Self::Error> {
+            Ok(Value::Null)
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Self::Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<Value, Self::Error> {
+            Ok(Value::Null)
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+            Ok(Value::Null)
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            variant: &'static str,
+        ) -> Result<Value, Self::Error> {
+            Ok(Value::String(variant.to_string()))
+        }
+
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Value, Self::Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _index: u32,
+            variant: &'static str,
+            value: &T,
+        ) -> Result<Value, Self::Error> {
+            let mut fields = Map::new();
+            fields.insert(variant.to_string(), value.serialize(self)?);
+            Ok(Value::Object(fields))
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<RecordSeq, Self::Error> {
+            Ok(RecordSeq(vec![]))
+        }
+
+        fn serialize_tuple(self, _len: usize) -> Result<RecordSeq, Self::Error> {
+            Ok(RecordSeq(vec![]))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<RecordSeq, Self::Error> {
+            Ok(RecordSeq(vec![]))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<RecordSeq, Self::Error> {
+            Ok(RecordSeq(vec![]))
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<RecordMap, Self::Error> {
+            Ok(RecordMap::new())
+        }
+
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<RecordMap, Self::Error> {
+            Ok(RecordMap::new())
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<RecordMap, Self::Error> {
+            Ok(RecordMap::new())
+        }
+    }
+
+    impl SerializeSeq for RecordSeq {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_element<T: ?Sized + Serialize>(
+            &mut self,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.0.push(value.serialize(Recorder)?);
+            Ok(())
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            Ok(Value::Array(self.0))
+        }
+    }
+
+    impl SerializeTuple for RecordSeq {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_element<T: ?Sized + Serialize>(
+            &mut self,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            SerializeSeq::serialize_element(self, value)
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            SerializeSeq::end(self)
+        }
+    }
+
+    impl SerializeTupleStruct for RecordSeq {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            SerializeSeq::serialize_element(self, value)
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            SerializeSeq::end(self)
+        }
+    }
+
+    impl SerializeTupleVariant for RecordSeq {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            SerializeSeq::serialize_element(self, value)
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            SerializeSeq::end(self)
+        }
+    }
+
+    impl SerializeMap for RecordMap {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+            self.key = Some(match key.serialize(Recorder)? {
+                Value::String(key) => key,
+                key => key.to_string(),
+            });
+            Ok(())
+        }
+
+        fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            let key = self.key.take().unwrap_or_default();
+            self.fields.insert(key, value.serialize(Recorder)?);
+            Ok(())
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            Ok(Value::Object(self.fields))
+        }
+    }
+
+    impl SerializeStruct for RecordMap {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.fields
+                .insert(key.to_string(), value.serialize(Recorder)?);
+            Ok(())
+        }
+
+        fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+            self.fields.insert(key.to_string(), Value::Null);
+            Ok(())
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            Ok(Value::Object(self.fields))
+        }
+    }
+
+    impl SerializeStructVariant for RecordMap {
+        type Ok = Value;
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            SerializeStruct::serialize_field(self, key, value)
+        }
+
+        fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+            SerializeStruct::skip_field(self, key)
+        }
+
+        fn end(self) -> Result<Value, Self::Error> {
+            SerializeStruct::end(self)
+        }
+    }
+
+    fn all_properties(schema: &Value, location: &str, properties: &mut HashSet<String>) {
+        if let Value::Object(fields) = schema {
+            for (key, child) in fields.iter() {
+                if key == "allOf" {
+                    continue;
+                }
+
+                let child_location = format!("{}/{}", location, key);
+                if key == "properties" {
+                    if let Value::Object(props) = child {
+                        for (name, prop) in props.iter() {
+                            let prop_location = format!("{}/{}", child_location, name);
+                            properties.insert(prop_location.clone());
+                            all_properties(prop, &prop_location, properties);
+                        }
+                    }
+                } else {
+                    all_properties(child, &child_location, properties);
+                }
+            }
+        }
+    }
+
+    fn assert_in_sync(schema: &Value, values: &[Value]) {
+        let mut seen = HashSet::new();
+        for value in values.iter() {
+            assert_described(schema, "#", value, &mut seen);
+        }
+
+        let mut properties = HashSet::new();
+        all_properties(schema, "#", &mut properties);
+
+        let mut missing: Vec<&String> = properties.difference(&seen).collect();
+        missing.sort();
+        assert!(
+            missing.is_empty(),
+            "the schema describes fields which the models don't have: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn config_schema_in_sync() {
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+directories: [/archive]
+scratchpads: /scratch
+scratchpad_retention:
+  weeks: 8
+  action: delete
+clone_timeout: 600
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    oauthClientId: client-id
+    accounts:
+      - name: work
+        namespaces: [acme]
+        sshHost: github-work
+        userName: Work User
+        userEmail: work@example.com
+    gitConfig:
+      - key: core.autocrlf
+        value: "false"
+        namespace: acme
+apps:
+  - name: make
+    command: make
+    args: [build]
+    environment: [CI=1]
+aliases:
+  gt: github.com/sierrasoftworks/git-tool
+keychain:
+  backend: file
+  path: /tokens.age
+registries:
+  - name: acme
+    type: github
+    repo: acme/registry
+    ref: main
+    path: registry
+  - name: local
+    type: directory
+    path: /registry
+  - name: internal
+    type: http
+    url: https://registry.example.com
+installed:
+  - id: git-tool:apps/bash
+    version: 1.0.0
+    apps: [bash]
+    services: [github.com]
+profiles:
+  - name: work
+    directory: /work
+    directories: [/archive/work]
+    scratchpads: /work/scratch
+    services:
+      - domain: github.com
+        website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+        httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+        gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+        pattern: "*/*"
+    apps:
+      - name: code
+        command: code
+    aliases:
+      api: github.com/acme/api
+    features:
+      http_transport: true
+    keychain:
+      backend: env
+      namespace: acme
+"#,
+        )
+        .unwrap();
+
+        // Fields which are skipped when they are empty should still be described by the schema.
+        let minimal = Config::from_str("directory: /dev").unwrap();
+
+        assert_in_sync(&config_schema(), &[record(&cfg), record(&minimal)]);
+    }
+
+    #[test]
+    fn registry_entry_schema_in_sync() {
+        let entry: Entry = serde_yaml::from_str(
+            r#"
+name: Example
+description: An example entry.
+version: 1.0.0
+configs:
+  - platform: any
+    app:
+      name: example
+      command: example
+      args: ["{{ .Target.Path }}"]
+      environment: [CI=1]
+    service:
+      domain: example.com
+      website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+      httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+      gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+      pattern: "*/*"
+      oauthClientId: client-id
+"#,
+        )
+        .unwrap();
+
+        let mut values = vec![record(&entry)];
+
+        // The entries in Git-Tool's own registry should only use the fields that the schema describes.
+        for kind in std::fs::read_dir(get_repo_root().join("registry")).unwrap() {
+            for file in std::fs::read_dir(kind.unwrap().path()).unwrap() {
+                let content = std::fs::read_to_string(file.unwrap().path()).unwrap();
+                values.push(serde_yaml::from_str(&content).unwrap());
+            }
+        }
+
+        assert_in_sync(&registry_entry_schema(), &values);
+    }
+
+    #[test]
+    fn schema_files_up_to_date() {
+        for (file, schema) in [
+            ("config.schema.json", config_schema()),
+            ("registry-entry.schema.json", registry_entry_schema()),
+        ] {
+            let path = get_repo_root().join("schemas").join(file);
+            let expected = format!("{}\n", serde_json::to_string_pretty(&schema).unwrap());
+
+            assert_eq!(
+                std::fs::read_to_string(&path)
+                    .unwrap_or_default()
+                    .replace("\r\n", "\n"),
+                expected,
+                "{} is out of date, update it with the output of `gt config schema`",
+                path.display()
+            );
+        }
+    }
+}
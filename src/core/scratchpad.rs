@@ -1,5 +1,6 @@
 use super::{Config, Target};
 use gtmpl::Value;
+use serde::{Deserialize, Serialize};
 use std::path;
 
 #[derive(Debug, Clone)]
@@ -34,3 +35,69 @@ impl Scratchpad {
         }
     }
 }
+
+/// What happens to scratchpads which haven't been changed within your retention period.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RetentionAction {
+    /// Compresses the scratchpad into a `$NAME.tar.gz` file in your scratchpads directory.
+    #[default]
+    Archive,
+    /// Removes the scratchpad entirely.
+    Delete,
+}
+
+/// Controls how long scratchpads are kept for before they are cleaned up by `gt scratch prune`,
+/// which is also run whenever a new scratchpad is created.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    weeks: u32,
+    #[serde(default)]
+    action: RetentionAction,
+}
+
+impl RetentionPolicy {
+    pub fn new(weeks: u32, action: RetentionAction) -> Self {
+        Self { weeks, action }
+    }
+
+    pub fn get_weeks(&self) -> u32 {
+        self.weeks
+    }
+
+    pub fn get_action(&self) -> RetentionAction {
+        self.action
+    }
+
+    /// Determines whether a scratchpad which was last modified at the given time has outlived
+    /// this policy.
+    pub fn is_expired(&self, modified: std::time::SystemTime, now: std::time::SystemTime) -> bool {
+        let retention = std::time::Duration::from_secs(u64::from(self.weeks) * 7 * 24 * 60 * 60);
+        now.duration_since(modified)
+            .map(|age| age > retention)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn retention_policy() {
+        let policy: RetentionPolicy = serde_yaml::from_str("weeks: 4").unwrap();
+        assert_eq!(policy.get_weeks(), 4);
+        assert_eq!(policy.get_action(), RetentionAction::Archive);
+
+        let policy: RetentionPolicy = serde_yaml::from_str("weeks: 2\naction: delete").unwrap();
+        assert_eq!(policy.get_action(), RetentionAction::Delete);
+
+        let now = SystemTime::now();
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        assert!(!policy.is_expired(now, now));
+        assert!(!policy.is_expired(now - week, now));
+        assert!(policy.is_expired(now - week * 3, now));
+        assert!(!policy.is_expired(now + week, now));
+    }
+}
@@ -1,6 +1,9 @@
-use super::{templates, Error, Repo};
+use super::{account::namespace_matches, templates, Account, Error, Repo, Target};
 use serde::{Deserialize, Serialize};
 
+const NAMESPACE_PLACEHOLDER: &str = "__gittool_namespace__";
+const NAME_PLACEHOLDER: &str = "__gittool_name__";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Service {
     domain: String,
@@ -10,6 +13,26 @@ pub struct Service {
     #[serde(rename = "gitUrl")]
     git_url: String,
     pattern: String,
+    #[serde(
+        rename = "oauthClientId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    oauth_client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    accounts: Vec<Account>,
+    #[serde(rename = "gitConfig", default, skip_serializing_if = "Vec::is_empty")]
+    git_config: Vec<GitConfigEntry>,
+}
+
+/// A Git configuration value which should be set on repositories for a service,
+/// optionally limited to those in namespaces matching a pattern.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitConfigEntry {
+    key: String,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
 }
 
 impl Service {
@@ -25,17 +48,218 @@ impl Service {
         self.pattern.clone()
     }
 
+    pub fn get_oauth_client_id(&self) -> Option<String> {
+        self.oauth_client_id.clone()
+    }
+
+    pub fn get_accounts(&self) -> std::slice::Iter<'_, Account> {
+        self.accounts.iter()
+    }
+
+    pub fn get_account(&self, name: &str) -> Option<&Account> {
+        self.accounts.iter().find(|a| a.get_name() == name)
+    }
+
+    /// Gets the account which should be used for repositories in the given namespace,
+    /// if one has been configured.
+    pub fn get_account_for_namespace(&self, namespace: &str) -> Option<&Account> {
+        self.accounts
+            .iter()
+            .find(|a| a.handles_namespace(namespace))
+    }
+
+    /// Gets the Git configuration which should be applied to the given repository, in the
+    /// order it should be applied. The owning account's identity is applied first so that
+    /// it can be overridden by more specific `gitConfig` entries.
+    pub fn get_git_config(&self, r: &Repo) -> Vec<(String, String)> {
+        let mut config = vec![];
+
+        if let Some(account) = self.get_account_for_namespace(&r.get_namespace()) {
+            if let Some(name) = account.get_user_name() {
+                config.push(("user.name".to_string(), name.to_string()));
+            }
+
+            if let Some(email) = account.get_user_email() {
+                config.push(("user.email".to_string(), email.to_string()));
+            }
+        }
+
+        for entry in self.git_config.iter() {
+            let applies = match &entry.namespace {
+                Some(pattern) => namespace_matches(pattern, &r.get_namespace()),
+                None => true,
+            };
+
+            if applies {
+                config.push((entry.key.clone(), entry.value.clone()));
+            }
+        }
+
+        config
+    }
+
+    /// Gets the name of the keychain entry holding the access token which should be
+    /// used for the given repository.
+    pub fn get_token_name(&self, r: &Repo) -> String {
+        match self.get_account_for_namespace(&r.get_namespace()) {
+            Some(account) => account.get_token_name(self),
+            None => self.get_domain(),
+        }
+    }
+
     pub fn get_website(&self, r: &Repo) -> Result<String, Error> {
         templates::render(self.website.clone().as_str(), r.into())
     }
 
     pub fn get_git_url(&self, r: &Repo) -> Result<String, Error> {
-        templates::render(self.git_url.clone().as_str(), r.into())
+        // Accounts may use an SSH host alias (from ~/.ssh/config) to select the right
+        // key, in which case we render the alias in place of the service's domain.
+        match self
+            .get_account_for_namespace(&r.get_namespace())
+            .and_then(|a| a.get_ssh_host())
+        {
+            Some(host) => {
+                let aliased = Repo::new(&format!("{}/{}", host, r.get_full_name()), r.get_path());
+                templates::render(self.git_url.clone().as_str(), (&aliased).into())
+            }
+            None => templates::render(self.git_url.clone().as_str(), r.into()),
+        }
     }
 
     pub fn get_http_url(&self, r: &Repo) -> Result<String, Error> {
         templates::render(self.http_url.clone().as_str(), r.into())
     }
+
+    /// Determines the full name (`namespace/name`) of the repository which would
+    /// produce the given URL when rendered through this service's Git, HTTP or
+    /// website templates, if any.
+    pub fn get_full_name_for_url(&self, url: &str) -> Option<String> {
+        let placeholder = Repo::new(
+            &format!(
+                "{}/{}/{}",
+                self.domain, NAMESPACE_PLACEHOLDER, NAME_PLACEHOLDER
+            ),
+            std::path::PathBuf::new(),
+        );
+
+        let namespace_depth = self.pattern.split_terminator('/').count() - 1;
+
+        let mut candidates = vec![url.to_string()];
+        match url.strip_suffix(".git") {
+            Some(stripped) => candidates.push(stripped.to_string()),
+            None => candidates.push(format!("{}.git", url)),
+        }
+
+        for template in [&self.git_url, &self.http_url, &self.website] {
+            let rendered = match templates::render(template, (&placeholder).into()) {
+                Ok(rendered) => rendered,
+                Err(_) => continue,
+            };
+
+            let parts = UrlPart::parse(&rendered);
+
+            for candidate in candidates.iter() {
+                let mut captures = UrlCaptures::default();
+                if UrlPart::match_all(&parts, candidate, &mut captures) {
+                    if let (Some(namespace), Some(name)) = (captures.namespace, captures.name) {
+                        if namespace.split('/').count() == namespace_depth {
+                            return Some(format!("{}/{}", namespace, name));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum UrlPart<'a> {
+    Literal(&'a str),
+    Namespace,
+    Name,
+}
+
+#[derive(Default)]
+struct UrlCaptures {
+    namespace: Option<String>,
+    name: Option<String>,
+}
+
+impl<'a> UrlPart<'a> {
+    fn parse(rendered: &'a str) -> Vec<UrlPart<'a>> {
+        let mut parts = vec![];
+        let mut remaining = rendered;
+
+        loop {
+            let next_namespace = remaining.find(NAMESPACE_PLACEHOLDER);
+            let next_name = remaining.find(NAME_PLACEHOLDER);
+
+            let (index, part, len) = match (next_namespace, next_name) {
+                (Some(ns), Some(n)) if ns < n => {
+                    (ns, UrlPart::Namespace, NAMESPACE_PLACEHOLDER.len())
+                }
+                (Some(ns), None) => (ns, UrlPart::Namespace, NAMESPACE_PLACEHOLDER.len()),
+                (_, Some(n)) => (n, UrlPart::Name, NAME_PLACEHOLDER.len()),
+                (None, None) => {
+                    if !remaining.is_empty() {
+                        parts.push(UrlPart::Literal(remaining));
+                    }
+
+                    return parts;
+                }
+            };
+
+            if index > 0 {
+                parts.push(UrlPart::Literal(&remaining[..index]));
+            }
+
+            parts.push(part);
+            remaining = &remaining[index + len..];
+        }
+    }
+
+    fn match_all(parts: &[UrlPart], input: &str, captures: &mut UrlCaptures) -> bool {
+        let (part, rest) = match parts.split_first() {
+            Some(p) => p,
+            None => return input.is_empty(),
+        };
+
+        let existing = match part {
+            UrlPart::Literal(literal) => Some(literal.to_string()),
+            UrlPart::Namespace => captures.namespace.clone(),
+            UrlPart::Name => captures.name.clone(),
+        };
+
+        if let Some(expected) = existing {
+            return input.starts_with(&expected)
+                && UrlPart::match_all(rest, &input[expected.len()..], captures);
+        }
+
+        for (end, _) in input.char_indices().skip(1).chain(vec![(input.len(), ' ')]) {
+            let value = &input[..end];
+            if *part == UrlPart::Name && value.contains('/') {
+                break;
+            }
+
+            match part {
+                UrlPart::Namespace => captures.namespace = Some(value.to_string()),
+                _ => captures.name = Some(value.to_string()),
+            }
+
+            if UrlPart::match_all(rest, &input[end..], captures) {
+                return true;
+            }
+        }
+
+        match part {
+            UrlPart::Namespace => captures.namespace = None,
+            _ => captures.name = None,
+        }
+
+        false
+    }
 }
 
 pub struct ServiceBuilder {
@@ -44,6 +268,7 @@ pub struct ServiceBuilder {
     http_url: String,
     git_url: String,
     pattern: String,
+    oauth_client_id: Option<String>,
 }
 
 impl Default for ServiceBuilder {
@@ -54,6 +279,7 @@ impl Default for ServiceBuilder {
             http_url: Default::default(),
             pattern: Default::default(),
             website: Default::default(),
+            oauth_client_id: None,
         }
     }
 }
@@ -88,6 +314,12 @@ impl ServiceBuilder {
 
         self
     }
+
+    pub fn with_oauth_client_id(&mut self, client_id: &str) -> &mut ServiceBuilder {
+        self.oauth_client_id = Some(client_id.to_string());
+
+        self
+    }
 }
 
 impl std::convert::From<&mut ServiceBuilder> for Service {
@@ -118,6 +350,9 @@ impl std::convert::From<&mut ServiceBuilder> for Service {
             git_url: builder.git_url.clone(),
             http_url: builder.http_url.clone(),
             pattern: builder.pattern.clone(),
+            oauth_client_id: builder.oauth_client_id.clone(),
+            accounts: vec![],
+            git_config: vec![],
         }
     }
 }
@@ -158,4 +393,155 @@ mod tests {
             "https://github.com/sierrasoftworks/git-tool"
         );
     }
+
+    #[test]
+    fn accounts() {
+        let svc: Service = serde_yaml::from_str(
+            r#"
+domain: github.com
+website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+pattern: "*/*"
+accounts:
+  - name: work
+    namespaces: ["work-org"]
+    sshHost: github-work
+    userEmail: me@work.example.com
+"#,
+        )
+        .unwrap();
+
+        let work = Repo::new("github.com/work-org/service", PathBuf::from("/test"));
+        let personal = Repo::new(
+            "github.com/sierrasoftworks/git-tool",
+            PathBuf::from("/test"),
+        );
+
+        assert_eq!(svc.get_token_name(&work), "github.com/work");
+        assert_eq!(svc.get_token_name(&personal), "github.com");
+
+        assert_eq!(
+            svc.get_git_url(&work).unwrap(),
+            "git@github-work:work-org/service.git"
+        );
+        assert_eq!(
+            svc.get_git_url(&personal).unwrap(),
+            "git@github.com:sierrasoftworks/git-tool.git"
+        );
+        assert_eq!(
+            svc.get_http_url(&work).unwrap(),
+            "https://github.com/work-org/service.git"
+        );
+    }
+
+    #[test]
+    fn git_config() {
+        let svc: Service = serde_yaml::from_str(
+            r#"
+domain: github.com
+website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+pattern: "*/*"
+accounts:
+  - name: work
+    namespaces: ["work-org"]
+    userEmail: me@work.example.com
+gitConfig:
+  - key: commit.gpgsign
+    value: "true"
+  - key: core.sshCommand
+    value: ssh -i ~/.ssh/work
+    namespace: work-*
+"#,
+        )
+        .unwrap();
+
+        let work = Repo::new("github.com/work-org/service", PathBuf::from("/test"));
+        let personal = Repo::new(
+            "github.com/sierrasoftworks/git-tool",
+            PathBuf::from("/test"),
+        );
+
+        assert_eq!(
+            svc.get_git_config(&work),
+            vec![
+                ("user.email".to_string(), "me@work.example.com".to_string()),
+                ("commit.gpgsign".to_string(), "true".to_string()),
+                (
+                    "core.sshCommand".to_string(),
+                    "ssh -i ~/.ssh/work".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            svc.get_git_config(&personal),
+            vec![("commit.gpgsign".to_string(), "true".to_string())]
+        );
+    }
+
+    #[test]
+    fn get_full_name_for_url() {
+        let svc: Service = Service::builder()
+            .with_domain("github.com")
+            .with_pattern("*/*")
+            .with_website("https://{{ .Service.Domain }}/{{ .Repo.FullName }}")
+            .with_git_url("git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git")
+            .with_http_url("https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git")
+            .into();
+
+        assert_eq!(
+            svc.get_full_name_for_url("git@github.com:sierrasoftworks/git-tool.git"),
+            Some("sierrasoftworks/git-tool".to_string())
+        );
+        assert_eq!(
+            svc.get_full_name_for_url("https://github.com/sierrasoftworks/git-tool.git"),
+            Some("sierrasoftworks/git-tool".to_string())
+        );
+        assert_eq!(
+            svc.get_full_name_for_url("https://github.com/sierrasoftworks/git-tool"),
+            Some("sierrasoftworks/git-tool".to_string())
+        );
+        assert_eq!(
+            svc.get_full_name_for_url("https://gitlab.com/sierrasoftworks/git-tool.git"),
+            None
+        );
+        assert_eq!(
+            svc.get_full_name_for_url("https://github.com/sierrasoftworks/git-tool/extra.git"),
+            None
+        );
+    }
+
+    #[test]
+    fn get_full_name_for_url_nested_namespace() {
+        let svc: Service = Service::builder()
+            .with_domain("dev.azure.com")
+            .with_pattern("*/*/*")
+            .with_website(
+                "https://{{ .Service.Domain }}/{{ .Repo.Namespace }}/_git/{{ .Repo.Name }}",
+            )
+            .with_git_url("git@ssh.{{ .Service.Domain }}:v3/{{ .Repo.FullName }}.git")
+            .with_http_url(
+                "https://{{ .Service.Domain }}/{{ .Repo.Namespace }}/_git/{{ .Repo.Name }}.git",
+            )
+            .into();
+
+        assert_eq!(
+            svc.get_full_name_for_url(
+                "git@ssh.dev.azure.com:v3/sierrasoftworks/opensource/git-tool"
+            ),
+            Some("sierrasoftworks/opensource/git-tool".to_string())
+        );
+        assert_eq!(
+            svc.get_full_name_for_url(
+                "https://dev.azure.com/sierrasoftworks/opensource/_git/git-tool"
+            ),
+            Some("sierrasoftworks/opensource/git-tool".to_string())
+        );
+        assert_eq!(
+            svc.get_full_name_for_url("https://dev.azure.com/sierrasoftworks/_git/git-tool"),
+            None
+        );
+    }
 }
@@ -0,0 +1,1204 @@
+use super::super::errors;
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+
+/// A path to a value within a YAML document, like `features.http_transport` or
+/// `apps[code].args`. List items may be selected by their index or by the value
+/// of their `name` (or `domain`) field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Named(String),
+}
+
+impl YamlPath {
+    pub fn parse(path: &str) -> Result<Self, errors::Error> {
+        let invalid = || {
+            errors::user(
+                &format!("The config path '{}' is not valid.", path),
+                "Config paths are made up of keys separated by dots, with list items selected using their name or index in square brackets, like `features.http_transport` or `apps[code].args`.",
+            )
+        };
+
+        let mut segments = vec![];
+        let mut chars = path.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' if !segments.is_empty() => {
+                    if chars.peek().map(|&c| c == '.' || c == '[').unwrap_or(true) {
+                        return Err(invalid());
+                    }
+                }
+                '[' if !segments.is_empty() => {
+                    let mut selector = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => selector.push(c),
+                            None => return Err(invalid()),
+                        }
+                    }
+
+                    if selector.is_empty() {
+                        return Err(invalid());
+                    }
+
+                    segments.push(match selector.parse::<usize>() {
+                        Ok(index) => Segment::Index(index),
+                        Err(_) => Segment::Named(selector),
+                    });
+                }
+                '.' | '[' | ']' => return Err(invalid()),
+                c => {
+                    let mut key = c.to_string();
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' || c == ']' {
+                            break;
+                        }
+
+                        key.push(c);
+                        chars.next();
+                    }
+
+                    segments.push(Segment::Key(key));
+                }
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Gets the value at this path within the provided document, if it exists.
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |value, segment| match (segment, value) {
+                (Segment::Key(key), Value::Mapping(map)) => map.get(&Value::String(key.clone())),
+                (Segment::Index(idx), Value::Sequence(seq)) => seq.get(*idx),
+                (Segment::Named(name), Value::Sequence(seq)) => {
+                    seq.iter().find(|item| item_has_name(item, name))
+                }
+                _ => None,
+            })
+    }
+
+    /// Sets the value at this path within the provided document, creating any missing mapping keys.
+    pub fn set(&self, target: &mut Value, value: Value) -> Result<(), errors::Error> {
+        set_value(target, &self.segments, value)
+    }
+
+    /// Gets the path of a key within the mapping at this path, which may contain characters
+    /// (like dots) that would otherwise be treated as separators.
+    pub fn child(&self, key: &str) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(Segment::Key(key.to_string()));
+
+        Self { segments }
+    }
+
+    /// Gets the path of the item with the given name (or domain) within the list at this path.
+    pub fn item(&self, name: &str) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(Segment::Named(name.to_string()));
+
+        Self { segments }
+    }
+
+    /// Gets the path of the item at the given index within the list at this path.
+    pub fn index(&self, index: usize) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(Segment::Index(index));
+
+        Self { segments }
+    }
+}
+
+impl fmt::Display for YamlPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(idx) => write!(f, "[{}]", idx)?,
+                Segment::Named(name) => write!(f, "[{}]", name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn item_has_name(item: &Value, name: &str) -> bool {
+    item_name(item).map(|n| n == name).unwrap_or_default()
+}
+
+/// Gets the name (or domain) which identifies a list item, like an app or service.
+pub fn item_name(item: &Value) -> Option<&str> {
+    ["name", "domain"]
+        .iter()
+        .filter_map(|field| item.get(field).and_then(|v| v.as_str()))
+        .next()
+}
+
+/// Renders a value as YAML without the leading document marker.
+pub fn format_value(value: &Value) -> Result<String, errors::Error> {
+    let rendered = serde_yaml::to_string(value)?;
+
+    Ok(rendered
+        .trim_start_matches("---")
+        .trim_start_matches('\n')
+        .trim_end()
+        .to_string())
+}
+
+/// A YAML document which can be edited in place, preserving the formatting and
+/// comments of everything other than the values being changed.
+pub struct YamlDocument {
+    lines: Vec<String>,
+}
+
+impl YamlDocument {
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text.lines().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    /// Sets the value at the given path, creating any missing mapping keys along the way.
+    pub fn set(&mut self, path: &YamlPath, value: &Value) -> Result<(), errors::Error> {
+        let document = self.value()?;
+        let outline = self.outline();
+
+        let root = match outline {
+            Node::Scalar if document.is_null() => Node::Map(vec![]),
+            node => node,
+        };
+
+        let edit = plan_set(
+            &root,
+            &document,
+            &path.segments,
+            value,
+            &Context::root(self.lines.len()),
+        )?;
+        self.apply(edit);
+
+        Ok(())
+    }
+
+    /// Removes the value at the given path, returning `false` if it did not exist.
+    pub fn unset(&mut self, path: &YamlPath) -> Result<bool, errors::Error> {
+        let document = self.value()?;
+        if path.get(&document).is_none() {
+            return Ok(false);
+        }
+
+        let outline = self.outline();
+        let edit = plan_unset(
+            &outline,
+            &document,
+            &path.segments,
+            &Context::root(self.lines.len()),
+        )?;
+        self.apply(edit);
+
+        Ok(true)
+    }
+
+    /// Appends an item to the list at the given path, creating the list if it does not exist.
+    pub fn push(&mut self, path: &YamlPath, item: &Value) -> Result<(), errors::Error> {
+        let document = self.value()?;
+        let mut items = match path.get(&document) {
+            Some(Value::Sequence(items)) => items.clone(),
+            Some(Value::Null) | None => vec![],
+            Some(_) => {
+                return Err(errors::user(
+                    &format!("The config path '{}' is not a list.", path),
+                    "Make sure that this section of your config file is a YAML list.",
+                ))
+            }
+        };
+
+        let outline = self.outline();
+        if let Some(Node::Seq(existing)) = locate(&outline, &document, &path.segments) {
+            if let Some(last) = existing.last() {
+                let lines = render_item("", item, existing[0].col)?;
+                self.apply(Edit {
+                    start: last.end,
+                    end: last.end,
+                    lines,
+                    append: None,
+                });
+
+                return Ok(());
+            }
+        }
+
+        // The list is empty or written using a flow style, so we rewrite it instead.
+        items.push(item.clone());
+        self.set(path, &Value::Sequence(items))
+    }
+
+    /// Gets the value of the document as it currently stands.
+    pub fn value(&self) -> Result<Value, errors::Error> {
+        let text = self.to_string();
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        Ok(serde_yaml::from_str(&text)?)
+    }
+
+    fn outline(&self) -> Node {
+        let lines: Vec<Line> = self.lines.iter().map(|l| Line::new(l)).collect();
+        parse(Block::new(&lines, 0, lines.len()))
+    }
+
+    fn apply(&mut self, edit: Edit) {
+        if let Some((line, suffix)) = edit.append {
+            self.lines[line].push_str(&suffix);
+        }
+
+        self.lines.splice(edit.start..edit.end, edit.lines);
+    }
+}
+
+impl fmt::Display for YamlDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines.iter() {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A replacement of a range of lines in the document, optionally followed by
+/// appending text to a line which precedes that range.
+struct Edit {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+    append: Option<(usize, String)>,
+}
+
+/// Describes the entry (or list item) which contains the node being edited, so that
+/// it can be rewritten if the node cannot be edited in place.
+#[derive(Clone)]
+struct Context {
+    line: usize,
+    end: usize,
+    col: usize,
+    prefix: String,
+    key: Option<String>,
+    is_root: bool,
+}
+
+impl Context {
+    fn root(end: usize) -> Self {
+        Self {
+            line: 0,
+            end,
+            col: 0,
+            prefix: String::new(),
+            key: None,
+            is_root: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Map(Vec<Entry>),
+    Seq(Vec<Item>),
+    Scalar,
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    key_text: String,
+    line: usize,
+    col: usize,
+    end: usize,
+    value: Node,
+}
+
+#[derive(Debug)]
+struct Item {
+    line: usize,
+    col: usize,
+    end: usize,
+    value: Node,
+}
+
+fn plan_set(
+    node: &Node,
+    current: &Value,
+    segments: &[Segment],
+    value: &Value,
+    ctx: &Context,
+) -> Result<Edit, errors::Error> {
+    let (segment, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => return rewrite(ctx, value),
+    };
+
+    match (node, segment) {
+        (Node::Map(entries), Segment::Key(key)) => {
+            let child = current.get(key.as_str()).cloned().unwrap_or(Value::Null);
+
+            match entries.iter().find(|e| &e.key == key) {
+                Some(entry) => plan_set(
+                    &entry.value,
+                    &child,
+                    rest,
+                    value,
+                    &Context {
+                        line: entry.line,
+                        end: entry.end,
+                        col: entry.col,
+                        prefix: entry_prefix(ctx, entry),
+                        key: Some(entry.key_text.clone()),
+                        is_root: false,
+                    },
+                ),
+                None => {
+                    let mut new_value = Value::Null;
+                    set_value(&mut new_value, rest, value.clone())?;
+
+                    let (line, col) = match entries.last() {
+                        Some(last) => (last.end, entries[0].col),
+                        None if ctx.is_root => (ctx.end, 0),
+                        None => return rewrite_with(ctx, current, segments, value),
+                    };
+
+                    Ok(Edit {
+                        start: line,
+                        end: line,
+                        lines: render_entry(&" ".repeat(col), &render_key(key)?, &new_value, col)?,
+                        append: None,
+                    })
+                }
+            }
+        }
+        (Node::Seq(items), Segment::Index(_)) | (Node::Seq(items), Segment::Named(_)) => {
+            let index = find_item(current, segment).ok_or_else(|| not_found(segment))?;
+            let item = items.get(index).ok_or_else(|| not_found(segment))?;
+            let child = current
+                .as_sequence()
+                .and_then(|s| s.get(index))
+                .cloned()
+                .unwrap_or(Value::Null);
+
+            plan_set(
+                &item.value,
+                &child,
+                rest,
+                value,
+                &Context {
+                    line: item.line,
+                    end: item.end,
+                    col: item.col,
+                    prefix: String::new(),
+                    key: None,
+                    is_root: false,
+                },
+            )
+        }
+        _ if ctx.is_root => Err(errors::user(
+            "Your config file does not have the structure we expected.",
+            "Please make sure that your config file is a YAML mapping and that the path you provided matches its structure.",
+        )),
+        // The value is written inline (or using a flow style), so we rewrite the whole entry.
+        _ => rewrite_with(ctx, current, segments, value),
+    }
+}
+
+fn plan_unset(
+    node: &Node,
+    current: &Value,
+    segments: &[Segment],
+    ctx: &Context,
+) -> Result<Edit, errors::Error> {
+    let (segment, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => {
+            return Err(errors::system(
+                "We could not find the value you wanted to remove in your config file.",
+                "Please report this issue to us on GitHub so that we can investigate further.",
+            ))
+        }
+    };
+
+    match (node, segment) {
+        (Node::Map(entries), Segment::Key(key)) => {
+            let entry = match entries.iter().find(|e| &e.key == key) {
+                Some(entry) => entry,
+                None => return unset_with(ctx, current, segments),
+            };
+
+            // The first entry of a list item shares its line with the `- ` marker, so we rewrite the whole item.
+            if rest.is_empty() && ctx.key.is_none() && !ctx.is_root && entry.line == ctx.line {
+                return unset_with(ctx, current, segments);
+            }
+
+            if rest.is_empty() {
+                return Ok(Edit {
+                    start: entry.line,
+                    end: entry.end,
+                    lines: vec![],
+                    // Removing the last entry would leave a null value behind, so we replace it with an empty mapping.
+                    append: match (entries.len(), &ctx.key) {
+                        (1, Some(_)) => Some((ctx.line, " {}".to_string())),
+                        _ => None,
+                    },
+                });
+            }
+
+            plan_unset(
+                &entry.value,
+                current.get(key.as_str()).unwrap_or(&Value::Null),
+                rest,
+                &Context {
+                    line: entry.line,
+                    end: entry.end,
+                    col: entry.col,
+                    prefix: entry_prefix(ctx, entry),
+                    key: Some(entry.key_text.clone()),
+                    is_root: false,
+                },
+            )
+        }
+        (Node::Seq(items), Segment::Index(_)) | (Node::Seq(items), Segment::Named(_)) => {
+            let index = find_item(current, segment).ok_or_else(|| not_found(segment))?;
+            let item = items.get(index).ok_or_else(|| not_found(segment))?;
+
+            if rest.is_empty() {
+                return Ok(Edit {
+                    start: item.line,
+                    end: item.end,
+                    lines: vec![],
+                    append: match (items.len(), &ctx.key) {
+                        (1, Some(_)) => Some((ctx.line, " []".to_string())),
+                        _ => None,
+                    },
+                });
+            }
+
+            plan_unset(
+                &item.value,
+                current
+                    .as_sequence()
+                    .and_then(|s| s.get(index))
+                    .unwrap_or(&Value::Null),
+                rest,
+                &Context {
+                    line: item.line,
+                    end: item.end,
+                    col: item.col,
+                    prefix: String::new(),
+                    key: None,
+                    is_root: false,
+                },
+            )
+        }
+        _ => unset_with(ctx, current, segments),
+    }
+}
+
+/// Finds the block node for the value at the given path, if it is written in a block style.
+fn locate<'a>(node: &'a Node, current: &Value, segments: &[Segment]) -> Option<&'a Node> {
+    let (segment, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => return Some(node),
+    };
+
+    match (node, segment) {
+        (Node::Map(entries), Segment::Key(key)) => {
+            let entry = entries.iter().find(|e| &e.key == key)?;
+            locate(&entry.value, current.get(key.as_str())?, rest)
+        }
+        (Node::Seq(items), Segment::Index(_)) | (Node::Seq(items), Segment::Named(_)) => {
+            let index = find_item(current, segment)?;
+            locate(
+                &items.get(index)?.value,
+                current.as_sequence()?.get(index)?,
+                rest,
+            )
+        }
+        _ => None,
+    }
+}
+
+fn rewrite_with(
+    ctx: &Context,
+    current: &Value,
+    segments: &[Segment],
+    value: &Value,
+) -> Result<Edit, errors::Error> {
+    let mut updated = current.clone();
+    set_value(&mut updated, segments, value.clone())?;
+    rewrite(ctx, &updated)
+}
+
+fn unset_with(ctx: &Context, current: &Value, segments: &[Segment]) -> Result<Edit, errors::Error> {
+    if ctx.is_root {
+        return Err(errors::user(
+            "Your config file does not have the structure we expected.",
+            "Please make sure that your config file is a YAML mapping and that the path you provided matches its structure.",
+        ));
+    }
+
+    let mut updated = current.clone();
+    remove_value(&mut updated, segments);
+    rewrite(ctx, &updated)
+}
+
+/// Replaces the entry (or list item) described by the context with a new value.
+fn rewrite(ctx: &Context, value: &Value) -> Result<Edit, errors::Error> {
+    let lines = match &ctx.key {
+        Some(key) => render_entry(&ctx.prefix, key, value, ctx.col)?,
+        None => render_item(&ctx.prefix, value, ctx.col)?,
+    };
+
+    Ok(Edit {
+        start: ctx.line,
+        end: ctx.end,
+        lines,
+        append: None,
+    })
+}
+
+fn entry_prefix(ctx: &Context, entry: &Entry) -> String {
+    // The first entry of a list item shares its line with the `- ` marker, which we need to keep.
+    if ctx.key.is_none() && !ctx.is_root && entry.line == ctx.line {
+        format!(
+            "{}- {}",
+            " ".repeat(ctx.col),
+            " ".repeat(entry.col - ctx.col - 2)
+        )
+    } else {
+        " ".repeat(entry.col)
+    }
+}
+
+fn not_found(segment: &Segment) -> errors::Error {
+    let selector = match segment {
+        Segment::Index(idx) => idx.to_string(),
+        Segment::Named(name) => name.clone(),
+        Segment::Key(key) => key.clone(),
+    };
+
+    errors::user(
+        &format!("Could not find the list item '{}' in your config file.", selector),
+        "List items can be selected using their index or the value of their `name` (or `domain`) field, like `apps[code]`.",
+    )
+}
+
+fn find_item(value: &Value, segment: &Segment) -> Option<usize> {
+    let seq = value.as_sequence()?;
+
+    match segment {
+        Segment::Index(idx) if *idx < seq.len() => Some(*idx),
+        Segment::Named(name) => seq.iter().position(|item| item_has_name(item, name)),
+        _ => None,
+    }
+}
+
+fn set_value(target: &mut Value, segments: &[Segment], value: Value) -> Result<(), errors::Error> {
+    let (segment, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => {
+            *target = value;
+            return Ok(());
+        }
+    };
+
+    match segment {
+        Segment::Key(key) => {
+            if !target.is_mapping() {
+                *target = Value::Mapping(Mapping::new());
+            }
+
+            if let Value::Mapping(map) = target {
+                let key = Value::String(key.clone());
+                if !map.contains_key(&key) {
+                    map.insert(key.clone(), Value::Null);
+                }
+
+                if let Some(child) = map.get_mut(&key) {
+                    set_value(child, rest, value)?;
+                }
+            }
+
+            Ok(())
+        }
+        _ => {
+            let index = find_item(target, segment).ok_or_else(|| not_found(segment))?;
+            match target {
+                Value::Sequence(seq) => set_value(&mut seq[index], rest, value),
+                _ => Err(not_found(segment)),
+            }
+        }
+    }
+}
+
+fn remove_value(target: &mut Value, segments: &[Segment]) {
+    let (segment, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => return,
+    };
+
+    let index = find_item(target, segment);
+    match (segment, target) {
+        (Segment::Key(key), Value::Mapping(map)) => {
+            let key = Value::String(key.clone());
+            if rest.is_empty() {
+                map.remove(&key);
+            } else if let Some(child) = map.get_mut(&key) {
+                remove_value(child, rest);
+            }
+        }
+        (_, Value::Sequence(seq)) => {
+            if let Some(index) = index {
+                if rest.is_empty() {
+                    seq.remove(index);
+                } else {
+                    remove_value(&mut seq[index], rest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render_key(key: &str) -> Result<String, errors::Error> {
+    format_value(&Value::String(key.to_string()))
+}
+
+fn render_entry(
+    prefix: &str,
+    key: &str,
+    value: &Value,
+    col: usize,
+) -> Result<Vec<String>, errors::Error> {
+    match value {
+        Value::Mapping(m) if !m.is_empty() => {}
+        Value::Sequence(s) if !s.is_empty() => {}
+        _ => return Ok(vec![format!("{}{}: {}", prefix, key, format_value(value)?)]),
+    }
+
+    let mut lines = vec![format!("{}{}:", prefix, key)];
+    for line in format_value(value)?.lines() {
+        lines.push(format!("{}{}", " ".repeat(col + 2), line));
+    }
+
+    Ok(lines)
+}
+
+fn render_item(prefix: &str, value: &Value, col: usize) -> Result<Vec<String>, errors::Error> {
+    let prefix = if prefix.is_empty() {
+        " ".repeat(col)
+    } else {
+        prefix.to_string()
+    };
+
+    let rendered = format_value(value)?;
+    let mut lines = vec![];
+    for (i, line) in rendered.lines().enumerate() {
+        if i == 0 {
+            lines.push(format!("{}- {}", prefix, line));
+        } else {
+            lines.push(format!("{}{}", " ".repeat(col + 2), line));
+        }
+    }
+
+    Ok(lines)
+}
+
+fn is_ignorable(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "..."
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Splits a mapping entry into its (unquoted) key, the key as written and the text
+/// which follows the colon, with any trailing comment removed.
+fn split_key(content: &str) -> Option<(String, String, String)> {
+    if is_item(content) || content.starts_with('#') {
+        return None;
+    }
+
+    let (key, key_len) = match content.chars().next()? {
+        quote @ '"' | quote @ '\'' => {
+            let end = content[1..].find(quote)? + 1;
+            (content[1..end].to_string(), end + 1)
+        }
+        _ => {
+            let end = content
+                .find(": ")
+                .or_else(|| content.strip_suffix(':').map(|c| c.len()))?;
+            (content[..end].trim_end().to_string(), end)
+        }
+    };
+
+    let rest = content[key_len..].trim_start();
+    let rest = rest.strip_prefix(':')?;
+
+    Some((
+        key,
+        content[..key_len].to_string(),
+        strip_comment(rest).trim().to_string(),
+    ))
+}
+
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if previous.is_whitespace() => return &text[..i],
+            _ => {}
+        }
+
+        previous = c;
+    }
+
+    text
+}
+
+/// A line of the document, along with the column at which its content starts.
+#[derive(Clone, Copy, Default)]
+struct Line<'a> {
+    text: &'a str,
+    col: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            col: indent(text),
+        }
+    }
+
+    fn content(&self) -> &'a str {
+        &self.text[self.col..]
+    }
+
+    fn is_ignorable(&self) -> bool {
+        is_ignorable(self.content())
+    }
+
+    /// Views a list item's line as though its `-` marker was indentation, which keeps the
+    /// columns of the item's first line aligned with the lines which follow it.
+    fn without_marker(self) -> Self {
+        let col = self.col + 1;
+        Self {
+            text: self.text,
+            col: col + indent(&self.text[col..]),
+        }
+    }
+}
+
+/// A range of the document's lines, whose first line may be viewed differently to the
+/// way it is written so that list items can be parsed without copying the document.
+#[derive(Clone, Copy)]
+struct Block<'a> {
+    lines: &'a [Line<'a>],
+    start: usize,
+    end: usize,
+    head: Line<'a>,
+}
+
+impl<'a> Block<'a> {
+    fn new(lines: &'a [Line<'a>], start: usize, end: usize) -> Self {
+        Self {
+            lines,
+            start,
+            end,
+            head: lines.get(start).copied().unwrap_or_default(),
+        }
+    }
+
+    fn line(&self, i: usize) -> Line<'a> {
+        if i == self.start {
+            self.head
+        } else {
+            self.lines[i]
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Self {
+        Self {
+            lines: self.lines,
+            start,
+            end,
+            head: if start < end {
+                self.line(start)
+            } else {
+                Line::default()
+            },
+        }
+    }
+
+    /// Finds the lines in this block which hold content, rather than blank lines or comments.
+    fn content_lines(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        (self.start..self.end).filter(move |&i| !self.line(i).is_ignorable())
+    }
+}
+
+/// Builds an outline of the block structure of the given lines, recording where each
+/// mapping entry and list item starts and ends.
+fn parse(block: Block) -> Node {
+    let first = match block.content_lines().next() {
+        Some(first) => first,
+        None => return Node::Scalar,
+    };
+
+    let line = block.line(first);
+    let block = block.slice(first, block.end);
+
+    if is_item(line.content()) {
+        Node::Seq(parse_items(block, line.col))
+    } else if split_key(line.content()).is_some() {
+        Node::Map(parse_entries(block, line.col))
+    } else {
+        Node::Scalar
+    }
+}
+
+fn last_content_line(block: Block, start: usize, end: usize) -> usize {
+    block
+        .slice(start, end)
+        .content_lines()
+        .next_back()
+        .map(|i| i + 1)
+        .unwrap_or(start + 1)
+}
+
+/// Finds the lines in a block which start a new entry or item at the given column.
+fn starts<F: Fn(&str) -> bool>(block: Block, col: usize, is_start: F) -> Vec<usize> {
+    block
+        .content_lines()
+        .filter(|&i| block.line(i).col == col && is_start(block.line(i).content()))
+        .collect()
+}
+
+fn parse_entries(block: Block, col: usize) -> Vec<Entry> {
+    let starts = starts(block, col, |content| split_key(content).is_some());
+
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &line)| {
+            let span_end = starts.get(i + 1).copied().unwrap_or(block.end);
+            let entry_end = last_content_line(block, line, span_end);
+            let (key, key_text, rest) = split_key(block.line(line).content())?;
+
+            let value = if rest.is_empty() {
+                parse(block.slice(line + 1, entry_end))
+            } else {
+                Node::Scalar
+            };
+
+            Some(Entry {
+                key,
+                key_text,
+                line,
+                col,
+                end: entry_end,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn parse_items(block: Block, col: usize) -> Vec<Item> {
+    let starts = starts(block, col, is_item);
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &line)| {
+            let span_end = starts.get(i + 1).copied().unwrap_or(block.end);
+            let item_end = last_content_line(block, line, span_end);
+
+            Item {
+                line,
+                col,
+                end: item_end,
+                value: parse(Block {
+                    head: block.line(line).without_marker(),
+                    ..block.slice(line, item_end)
+                }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"# My Git-Tool config
+directory: /dev
+
+services:
+  - domain: github.com # the default service
+    pattern: "*/*"
+apps:
+- name: shell
+  command: bash
+- name: code
+  command: code
+  args:
+    - .
+
+features:
+  # Use HTTPS instead of SSH
+  http_transport: false
+aliases: {}
+"#;
+
+    fn set(path: &str, value: &str) -> String {
+        let mut doc = YamlDocument::new(DOC);
+        doc.set(
+            &YamlPath::parse(path).unwrap(),
+            &serde_yaml::from_str(value).unwrap(),
+        )
+        .unwrap();
+        doc.to_string()
+    }
+
+    fn unset(path: &str) -> String {
+        let mut doc = YamlDocument::new(DOC);
+        assert!(doc.unset(&YamlPath::parse(path).unwrap()).unwrap());
+        doc.to_string()
+    }
+
+    #[test]
+    fn parse_path() {
+        assert_eq!(
+            YamlPath::parse("apps[code].args").unwrap().segments,
+            vec![
+                Segment::Key("apps".to_string()),
+                Segment::Named("code".to_string()),
+                Segment::Key("args".to_string())
+            ]
+        );
+        assert_eq!(
+            YamlPath::parse("services[0].pattern").unwrap().segments,
+            vec![
+                Segment::Key("services".to_string()),
+                Segment::Index(0),
+                Segment::Key("pattern".to_string())
+            ]
+        );
+        assert_eq!(
+            YamlPath::parse("services[github.com]").unwrap().to_string(),
+            "services[github.com]"
+        );
+        assert!(YamlPath::parse("").is_err());
+        assert!(YamlPath::parse("apps[").is_err());
+        assert!(YamlPath::parse("features..http").is_err());
+    }
+
+    #[test]
+    fn get() {
+        let value: Value = serde_yaml::from_str(DOC).unwrap();
+
+        assert_eq!(
+            YamlPath::parse("apps[code].command")
+                .unwrap()
+                .get(&value)
+                .and_then(|v| v.as_str()),
+            Some("code")
+        );
+        assert_eq!(
+            YamlPath::parse("features.http_transport")
+                .unwrap()
+                .get(&value)
+                .and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        assert!(YamlPath::parse("apps[missing]")
+            .unwrap()
+            .get(&value)
+            .is_none());
+    }
+
+    #[test]
+    fn set_scalar_preserves_comments() {
+        let result = set("features.http_transport", "true");
+        assert_eq!(
+            result,
+            DOC.replace("http_transport: false", "http_transport: true")
+        );
+    }
+
+    #[test]
+    fn set_first_entry_of_item() {
+        let result = set("services[github.com].domain", "gitlab.com");
+        assert!(
+            result.contains("\n  - domain: gitlab.com\n    pattern: \"*/*\"\n"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn set_nested_item() {
+        let text =
+            "matrix:\n- - name: a # first\n    value: 1\n  - name: b\n    value: 2\n- - name: c\n";
+        let mut doc = YamlDocument::new(text);
+        doc.set(
+            &YamlPath::parse("matrix[0][b].value").unwrap(),
+            &Value::Number(3.into()),
+        )
+        .unwrap();
+
+        assert_eq!(doc.to_string(), text.replace("value: 2", "value: 3"));
+    }
+
+    #[test]
+    fn set_list() {
+        let result = set("apps[code].args", "[., --new-window]");
+        assert!(
+            result.contains(
+                "  command: code\n  args:\n    - \".\"\n    - \"--new-window\"\n\nfeatures:"
+            ),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn set_new_key() {
+        let result = set("features.native_clone", "true");
+        assert!(
+            result.contains("  http_transport: false\n  native_clone: true\naliases: {}"),
+            "{}",
+            result
+        );
+
+        let result = set("apps[shell].args", "[-l]");
+        assert!(
+            result.contains("- name: shell\n  command: bash\n  args:\n    - \"-l\"\n- name: code"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn set_new_section() {
+        let result = set("keychain.backend", "file");
+        assert!(
+            result.ends_with("aliases: {}\nkeychain:\n  backend: file\n"),
+            "{}",
+            result
+        );
+        assert!(result.starts_with("# My Git-Tool config\n"));
+    }
+
+    #[test]
+    fn set_flow_mapping() {
+        let result = set("aliases.blog", "github.com/sierrasoftworks/blog");
+        assert!(
+            result.ends_with("aliases:\n  blog: github.com/sierrasoftworks/blog\n"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn push_items() {
+        let push = |path: &str, value: &str| {
+            let mut doc = YamlDocument::new(DOC);
+            doc.push(
+                &YamlPath::parse(path).unwrap(),
+                &serde_yaml::from_str(value).unwrap(),
+            )
+            .unwrap();
+            doc.to_string()
+        };
+
+        let result = push("apps", "{ name: vim, command: vim }");
+        assert_eq!(
+            result,
+            DOC.replace("    - .\n\n", "    - .\n- name: vim\n  command: vim\n\n")
+        );
+
+        let result = push("services", "{ domain: gitlab.com }");
+        assert!(
+            result.contains("    pattern: \"*/*\"\n  - domain: gitlab.com\napps:"),
+            "{}",
+            result
+        );
+
+        let result = push("installed", "{ id: git-tool:apps/vim, version: 1.0.0 }");
+        assert!(
+            result.ends_with(
+                "aliases: {}\ninstalled:\n  - id: \"git-tool:apps/vim\"\n    version: 1.0.0\n"
+            ),
+            "{}",
+            result
+        );
+
+        let mut doc = YamlDocument::new(DOC);
+        assert!(doc
+            .push(&YamlPath::parse("features").unwrap(), &Value::Null)
+            .is_err());
+    }
+
+    #[test]
+    fn unset_entries() {
+        let result = unset("features.http_transport");
+        assert!(
+            result.contains("features: {}\n  # Use HTTPS instead of SSH\naliases"),
+            "{}",
+            result
+        );
+
+        let result = unset("apps[shell]");
+        assert!(result.contains("apps:\n- name: code\n"), "{}", result);
+
+        let result = unset("apps[code].args");
+        assert!(
+            result.contains("  command: code\n\nfeatures:"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn unset_missing() {
+        let mut doc = YamlDocument::new(DOC);
+        assert!(!doc
+            .unset(&YamlPath::parse("features.native_clone").unwrap())
+            .unwrap());
+        assert_eq!(doc.to_string(), DOC);
+    }
+}
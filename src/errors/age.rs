@@ -0,0 +1,26 @@
+use super::*;
+use ::age::{DecryptError, EncryptError};
+
+impl From<DecryptError> for Error {
+    fn from(e: DecryptError) -> Self {
+        match e {
+            DecryptError::DecryptionFailed | DecryptError::KeyDecryptionFailed | DecryptError::InvalidMac => user(
+                "We could not decrypt your token store with the passphrase you provided.",
+                "Please make sure that you entered the correct passphrase (or set it in the GITTOOL_KEYCHAIN_PASSPHRASE environment variable) and try again."),
+            e => user_with_internal(
+                "We could not read your encrypted token store.",
+                "Please make sure that the file configured in `keychain.path` is a token store created by Git-Tool, or remove it to start with an empty token store.",
+                detailed_message(&format!("{}", e)))
+        }
+    }
+}
+
+impl From<EncryptError> for Error {
+    fn from(e: EncryptError) -> Self {
+        system_with_internal(
+            "We could not encrypt your token store.",
+            "Please make sure that you have permission to write to the file configured in `keychain.path` and try again.",
+            detailed_message(&format!("{}", e)),
+        )
+    }
+}
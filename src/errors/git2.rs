@@ -0,0 +1,21 @@
+use super::*;
+use ::git2::{Error as Git2Error, ErrorCode};
+
+impl From<Git2Error> for Error {
+    fn from(e: Git2Error) -> Self {
+        match e.code() {
+            ErrorCode::NotFound => user_with_internal(
+                "We could not find the Git object you requested.",
+                "Make sure that the repository, branch or reference you are using exists and try again.",
+                e),
+            ErrorCode::Auth | ErrorCode::Certificate => user_with_internal(
+                "We could not authenticate with the remote Git server.",
+                "Make sure that your SSH agent or Git credential helper is configured correctly for this server, or disable the `native_clone` feature in your config file to use the Git CLI instead.",
+                e),
+            _ => system_with_internal(
+                "An error occurred while accessing your Git repository.",
+                "Please read the error message below and decide if there is something you can do to fix the problem, or report it to us on GitHub.",
+                e),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::{error, fmt};
 
+mod age;
 mod base64;
+mod git2;
 pub mod hyper;
 mod keyring;
 #[cfg(unix)]
@@ -65,6 +67,18 @@ impl Error {
         }
     }
 
+    pub fn description(&self) -> &str {
+        match self {
+            Error::UserError(description, ..) | Error::SystemError(description, ..) => description,
+        }
+    }
+
+    pub fn advice(&self) -> &str {
+        match self {
+            Error::UserError(_, advice, _) | Error::SystemError(_, advice, _) => advice,
+        }
+    }
+
     pub fn is_system(&self) -> bool {
         match self {
             Error::SystemError(..) => true,
@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub fn to_native_path<T: Into<PathBuf>>(path: T) -> std::path::PathBuf {
     let mut output = std::path::PathBuf::new();
@@ -19,12 +20,106 @@ pub fn to_native_path<T: Into<PathBuf>>(path: T) -> std::path::PathBuf {
     output
 }
 
+/// Moves a directory, copying it and removing the original when it is being moved to a
+/// different drive (which a rename can't do).
+pub fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_dir(from, to)?;
+    std::fs::remove_dir_all(from)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in from.read_dir()? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to).map(|_| ())
+}
+
+/// Gets the total size of the files within a directory, along with the last time that
+/// anything within it was modified.
+pub fn get_usage(path: &Path) -> std::io::Result<(u64, SystemTime)> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    let mut size = 0;
+    let mut modified = metadata.modified()?;
+
+    if metadata.is_dir() {
+        for entry in path.read_dir()? {
+            let (entry_size, entry_modified) = get_usage(&entry?.path())?;
+            size += entry_size;
+            modified = modified.max(entry_modified);
+        }
+    } else {
+        size = metadata.len();
+    }
+
+    Ok((size, modified))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test::get_dev_dir;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_copy_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let from = temp.path().join("from");
+        std::fs::create_dir_all(from.join(".git").join("refs")).unwrap();
+        std::fs::write(from.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        std::fs::write(from.join("README.md"), "# Test").unwrap();
+
+        let to = temp.path().join("other").join("to");
+        copy_dir(&from, &to).unwrap();
+
+        assert!(to.join(".git").join("refs").is_dir());
+        assert_eq!(
+            std::fs::read_to_string(to.join(".git").join("HEAD")).unwrap(),
+            "ref: refs/heads/main"
+        );
+        assert_eq!(
+            std::fs::read_to_string(to.join("README.md")).unwrap(),
+            "# Test"
+        );
+
+        move_dir(&to, &temp.path().join("moved")).unwrap();
+        assert!(!to.exists());
+        assert!(temp.path().join("moved").join("README.md").exists());
+
+        let (size, modified) = get_usage(&temp.path().join("moved")).unwrap();
+        assert_eq!(
+            size,
+            "ref: refs/heads/main".len() as u64 + "# Test".len() as u64
+        );
+        assert!(modified <= SystemTime::now());
+    }
+
     #[test]
     fn test_to_native_path() {
         assert_eq!(
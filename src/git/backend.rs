@@ -0,0 +1,69 @@
+use super::*;
+use crate::{core::Config, errors};
+use async_trait::async_trait;
+use std::{io::Write, path, sync::Arc, time::Duration};
+
+#[async_trait]
+pub trait GitBackend: Send + Sync {
+    async fn branches(&self, repo: &path::Path) -> Result<Vec<Branch>, errors::Error>;
+    async fn current_branch(&self, repo: &path::Path) -> Result<String, errors::Error>;
+    async fn rev_parse(&self, repo: &path::Path, ref_name: &str) -> Result<String, errors::Error>;
+    async fn status(&self, repo: &path::Path) -> Result<Vec<String>, errors::Error>;
+    async fn clone_repo(
+        &self,
+        repo: &path::Path,
+        url: &str,
+        output: Box<dyn Write + Send>,
+    ) -> Result<(), errors::Error>;
+}
+
+/// Gets the Git backend which should be used for the provided configuration.
+///
+/// Read-only operations are always performed in-process, falling back to the
+/// Git CLI if they fail, while clones are only performed in-process when the
+/// `native_clone` feature is enabled.
+pub fn backend(config: &Config) -> Arc<dyn GitBackend> {
+    Arc::new(NativeBackend::new(
+        config.get_features().use_native_clone(),
+        config.get_clone_timeout(),
+    ))
+}
+
+#[derive(Default)]
+pub struct CliBackend {
+    clone_timeout: Option<Duration>,
+}
+
+impl CliBackend {
+    pub fn new(clone_timeout: Option<Duration>) -> Self {
+        Self { clone_timeout }
+    }
+}
+
+#[async_trait]
+impl GitBackend for CliBackend {
+    async fn branches(&self, repo: &path::Path) -> Result<Vec<Branch>, errors::Error> {
+        git_branches(repo).await
+    }
+
+    async fn current_branch(&self, repo: &path::Path) -> Result<String, errors::Error> {
+        git_current_branch(repo).await
+    }
+
+    async fn rev_parse(&self, repo: &path::Path, ref_name: &str) -> Result<String, errors::Error> {
+        git_rev_parse(repo, ref_name).await
+    }
+
+    async fn status(&self, repo: &path::Path) -> Result<Vec<String>, errors::Error> {
+        git_status(repo).await
+    }
+
+    async fn clone_repo(
+        &self,
+        repo: &path::Path,
+        url: &str,
+        output: Box<dyn Write + Send>,
+    ) -> Result<(), errors::Error> {
+        git_clone(repo, url, self.clone_timeout, output).await
+    }
+}
@@ -1,6 +1,6 @@
 use super::git_cmd;
 use crate::errors;
-use std::{collections::HashSet, path};
+use std::{collections::BTreeMap, path};
 use tokio::process::Command;
 
 pub async fn git_current_branch(repo: &path::Path) -> Result<String, errors::Error> {
@@ -18,32 +18,165 @@ pub async fn git_current_branch(repo: &path::Path) -> Result<String, errors::Err
     .to_string())
 }
 
-pub async fn git_branches(repo: &path::Path) -> Result<Vec<String>, errors::Error> {
-    info!("Running `git for-each-ref --format=%(refname:lstrip=2) refs/heads/` to get the list of branches");
+/// A branch which exists locally, on one of the repository's remotes, or both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub name: String,
+    pub is_local: bool,
+    pub remote: Option<String>,
+}
+
+impl Branch {
+    /// Gets a short marker describing where this branch exists.
+    pub fn marker(&self) -> String {
+        match (self.is_local, &self.remote) {
+            (true, Some(remote)) => format!("(local, {})", remote),
+            (true, None) => "(local)".to_string(),
+            (false, Some(remote)) => format!("({})", remote),
+            (false, None) => String::new(),
+        }
+    }
+}
+
+pub async fn git_branches(repo: &path::Path) -> Result<Vec<Branch>, errors::Error> {
+    info!("Running `git for-each-ref --format=%(refname) refs/heads/ refs/remotes/` to get the list of branches");
+    let output = git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("for-each-ref")
+            .arg("--format=%(refname)")
+            .arg("refs/heads/")
+            .arg("refs/remotes/"),
+    )
+    .await?;
+
+    Ok(parse_branch_refs(
+        output.split_terminator('\n').map(|s| s.trim()),
+    ))
+}
+
+pub async fn git_default_branch(repo: &path::Path) -> Result<String, errors::Error> {
+    info!("Running `git symbolic-ref --short -q refs/remotes/origin/HEAD` to get the default branch name");
+    let origin_head = git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("symbolic-ref")
+            .arg("--short")
+            .arg("-q")
+            .arg("refs/remotes/origin/HEAD"),
+    )
+    .await;
+
+    if let Ok(head) = origin_head {
+        if let Some(name) = head.trim().strip_prefix("origin/") {
+            return Ok(name.to_string());
+        }
+    }
+
+    let branches = git_branches(repo).await?;
+    for candidate in &["main", "master"] {
+        if branches.iter().any(|b| &b.name == candidate) {
+            return Ok(candidate.to_string());
+        }
+    }
+
+    Err(errors::user(
+        "We could not determine the default branch for this repository.",
+        "Make sure that your `origin` remote has a HEAD (`git remote set-head origin --auto`) or that you have a `main` or `master` branch.",
+    ))
+}
+
+pub async fn git_merged_branches(
+    repo: &path::Path,
+    target: &str,
+) -> Result<Vec<String>, errors::Error> {
+    info!("Running `git for-each-ref --merged $TARGET refs/heads/` to get the list of merged branches");
+    let output = git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("for-each-ref")
+            .arg("--format=%(refname:short)")
+            .arg("--merged")
+            .arg(target)
+            .arg("refs/heads/"),
+    )
+    .await?;
+
+    Ok(output
+        .split_terminator('\n')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
+}
+
+pub async fn git_gone_branches(repo: &path::Path) -> Result<Vec<String>, errors::Error> {
+    info!("Running `git for-each-ref --format=%(refname:short) %(upstream:track) refs/heads/` to find branches whose upstream is gone");
     let output = git_cmd(
         Command::new("git")
             .current_dir(repo)
             .arg("for-each-ref")
-            .arg("--format=%(refname:lstrip=2)")
+            .arg("--format=%(refname:short) %(upstream:track)")
             .arg("refs/heads/"),
     )
     .await?;
 
-    let refs = output.split_terminator('\n').map(|s| s.trim());
+    Ok(output
+        .split_terminator('\n')
+        .filter_map(|l| l.trim().strip_suffix("[gone]"))
+        .map(|s| s.trim().to_string())
+        .collect())
+}
+
+pub async fn git_branch_delete(repo: &path::Path, name: &str) -> Result<(), errors::Error> {
+    info!("Running `git branch -D $BRANCH_NAME` to delete a branch");
+    git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("branch")
+            .arg("-D")
+            .arg(name),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Combines a list of fully qualified local and remote-tracking refs into a list
+/// of branches, sorted by name. When a branch exists on several remotes, `origin`
+/// is preferred.
+pub(super) fn parse_branch_refs<'a, I: Iterator<Item = &'a str>>(refs: I) -> Vec<Branch> {
+    let mut branches: BTreeMap<String, Branch> = BTreeMap::new();
 
-    let mut unique_refs = HashSet::new();
     for r in refs {
-        if r.starts_with("remotes/") {
-            match r.splitn(3, '/').nth(2) {
-                Some(rs) => unique_refs.insert(rs),
-                None => unique_refs.insert(r),
-            };
+        let (name, remote) = if let Some(name) = r.strip_prefix("refs/heads/") {
+            (name, None)
+        } else if let Some(remote_ref) = r.strip_prefix("refs/remotes/") {
+            match remote_ref.splitn(2, '/').collect::<Vec<&str>>()[..] {
+                [_, "HEAD"] => continue,
+                [remote, name] => (name, Some(remote)),
+                _ => continue,
+            }
         } else {
-            unique_refs.insert(r);
+            continue;
+        };
+
+        let branch = branches.entry(name.to_string()).or_insert_with(|| Branch {
+            name: name.to_string(),
+            is_local: false,
+            remote: None,
+        });
+
+        match remote {
+            Some(remote) => {
+                if branch.remote.is_none() || remote == "origin" {
+                    branch.remote = Some(remote.to_string());
+                }
+            }
+            None => branch.is_local = true,
         }
     }
 
-    Ok(unique_refs.iter().map(|s| s.to_string()).collect())
+    branches.into_values().collect()
 }
 
 #[cfg(test)]
@@ -125,20 +258,12 @@ mod tests {
         git_update_ref(&repo.get_path(), "refs/heads/test", &current_sha)
             .await
             .unwrap();
-        git_update_ref(
-            &repo.get_path(),
-            "refs/heads/remotes/origin/main",
-            &current_sha,
-        )
-        .await
-        .unwrap();
-        git_update_ref(
-            &repo.get_path(),
-            "refs/heads/remotes/origin/test2",
-            &current_sha,
-        )
-        .await
-        .unwrap();
+        git_update_ref(&repo.get_path(), "refs/remotes/origin/main", &current_sha)
+            .await
+            .unwrap();
+        git_update_ref(&repo.get_path(), "refs/remotes/origin/test2", &current_sha)
+            .await
+            .unwrap();
 
         let branch = git_branches(&repo.get_path())
             .await
@@ -146,17 +271,57 @@ mod tests {
 
         println!("{:?}", branch);
 
-        assert!(
-            branch.iter().any(|x| x == "main"),
-            "'main' should be present in the list"
+        assert_eq!(
+            branch,
+            vec![
+                Branch {
+                    name: "main".into(),
+                    is_local: true,
+                    remote: Some("origin".into()),
+                },
+                Branch {
+                    name: "test".into(),
+                    is_local: true,
+                    remote: None,
+                },
+                Branch {
+                    name: "test2".into(),
+                    is_local: false,
+                    remote: Some("origin".into()),
+                },
+            ]
         );
-        assert!(
-            branch.iter().any(|x| x == "test"),
-            "'test' should be present in the list"
+    }
+
+    #[test]
+    fn test_parse_branch_refs() {
+        let branches = parse_branch_refs(
+            vec![
+                "refs/heads/main",
+                "refs/remotes/upstream/main",
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/main",
+                "refs/remotes/upstream/feature/test",
+            ]
+            .into_iter(),
         );
-        assert!(
-            branch.iter().any(|x| x == "test2"),
-            "'test' should be present in the list"
+
+        assert_eq!(
+            branches,
+            vec![
+                Branch {
+                    name: "feature/test".into(),
+                    is_local: false,
+                    remote: Some("upstream".into()),
+                },
+                Branch {
+                    name: "main".into(),
+                    is_local: true,
+                    remote: Some("origin".into()),
+                },
+            ]
         );
+        assert_eq!(branches[0].marker(), "(upstream)");
+        assert_eq!(branches[1].marker(), "(local, origin)");
     }
 }
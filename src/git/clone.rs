@@ -1,18 +1,87 @@
-use super::git_cmd;
+use super::git_cmd_streaming;
 use crate::errors;
+use std::io::Write;
 use std::path;
+use std::time::Duration;
 use tokio::process::Command;
 
-pub async fn git_clone(repo: &path::Path, url: &str) -> Result<(), errors::Error> {
+pub async fn git_clone(
+    repo: &path::Path,
+    url: &str,
+    timeout: Option<Duration>,
+    output: Box<dyn Write + Send>,
+) -> Result<(), errors::Error> {
+    git_clone_with_config(repo, url, &[], timeout, output).await
+}
+
+/// Clones a repository, setting the provided config entries on the new repository
+/// before its contents are fetched. The clone is stopped if it does not complete
+/// within the provided timeout, and its progress is written to the provided output.
+pub async fn git_clone_with_config(
+    repo: &path::Path,
+    url: &str,
+    config: &[(&str, &str)],
+    timeout: Option<Duration>,
+    output: Box<dyn Write + Send>,
+) -> Result<(), errors::Error> {
     info!("Running `git clone --recurse-submodules $URL` to prepare repository");
-    git_cmd(
-        Command::new("git")
-            .arg("clone")
+
+    let mut cmd = Command::new("git");
+    cmd.arg("clone");
+
+    for (key, value) in config {
+        cmd.arg("--config").arg(format!("{}={}", key, value));
+    }
+
+    let mut progress = CloneProgress::new(output);
+    git_cmd_streaming(
+        cmd.arg("--progress")
             .arg("--recurse-submodules")
             .arg(url)
             .arg(repo),
+        timeout,
+        |line| progress.update(line),
     )
     .await?;
 
     Ok(())
 }
+
+/// Shows the progress of a clone on a single line, which is cleared once the clone
+/// completes or fails.
+pub(super) struct CloneProgress {
+    output: Box<dyn Write + Send>,
+    enabled: bool,
+    last: String,
+}
+
+impl CloneProgress {
+    pub fn new(output: Box<dyn Write + Send>) -> Self {
+        Self {
+            output,
+            // Progress is only shown when we are writing to a terminal, so that it doesn't
+            // end up polluting logs or piped output.
+            enabled: atty::is(atty::Stream::Stdout),
+            last: String::new(),
+        }
+    }
+
+    pub fn update(&mut self, line: &str) {
+        if !self.enabled || line == self.last {
+            return;
+        }
+
+        write!(self.output, "\r\x1b[K{}", line).ok();
+        self.output.flush().ok();
+        self.last = line.to_string();
+    }
+}
+
+impl Drop for CloneProgress {
+    fn drop(&mut self) {
+        if self.enabled && !self.last.is_empty() {
+            write!(self.output, "\r\x1b[K").ok();
+            self.output.flush().ok();
+        }
+    }
+}
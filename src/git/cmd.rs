@@ -1,25 +1,241 @@
 use crate::errors;
+use futures::{future, pin_mut, select, FutureExt, TryFutureExt};
+use std::future::Future;
 use std::process::Stdio;
+use std::time::Duration;
+use tokio::prelude::*;
 use tokio::process::Command;
 
 pub async fn git_cmd(cmd: &mut Command) -> Result<String, errors::Error> {
-    let child = cmd.stdout(Stdio::piped()).spawn()?;
-    let output = child.wait_with_output().await?;
+    run(cmd, None, false, |_| {}).await
+}
+
+/// Runs a long-running Git command (like a clone), passing each progress line
+/// it writes to stderr to the provided callback as it is received.
+///
+/// The command is terminated if it does not complete within the provided
+/// timeout, or if the user presses Ctrl+C while it is running.
+pub async fn git_cmd_streaming<P>(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    progress: P,
+) -> Result<String, errors::Error>
+where
+    P: FnMut(&str) + Send,
+{
+    run(cmd, timeout, true, progress).await
+}
+
+async fn run<P>(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    cancellable: bool,
+    mut progress: P,
+) -> Result<String, errors::Error>
+where
+    P: FnMut(&str) + Send,
+{
+    debug!("Running {:?}", cmd);
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdout = child.stdout.take().ok_or_else(|| {
+        errors::system(
+            "Unable to read the output of the Git process.",
+            "Please report this issue to us on GitHub so that we can investigate further.",
+        )
+    })?;
+    let mut stderr = child.stderr.take().ok_or_else(|| {
+        errors::system(
+            "Unable to read the error output of the Git process.",
+            "Please report this issue to us on GitHub so that we can investigate further.",
+        )
+    })?;
+
+    let read_stdout = async {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).await?;
+        Ok::<_, std::io::Error>(buf)
+    };
+
+    let read_stderr = async {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        let mut line_start = 0;
+
+        loop {
+            let n = stderr.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+
+            buf.extend_from_slice(&chunk[..n]);
+
+            // Git uses carriage returns to update progress lines in place, so we
+            // treat them as line endings when reporting progress.
+            while let Some(end) = buf[line_start..]
+                .iter()
+                .position(|&c| c == b'\n' || c == b'\r')
+            {
+                let line = String::from_utf8_lossy(&buf[line_start..line_start + end]);
+                if !line.trim().is_empty() {
+                    progress(line.trim());
+                }
 
-    let output_text = String::from_utf8(output.stdout)?;
+                line_start += end + 1;
+            }
+        }
+
+        Ok::<_, std::io::Error>(buf)
+    };
+
+    let execution = future::try_join(read_stdout, read_stderr)
+        .and_then(|(out, err)| (&mut child).map_ok(|status| (status, out, err)))
+        .map_err(errors::Error::from);
 
-    if !output.status.success() {
-        match output.status.code() {
+    // If we stop early, dropping the child process will terminate it.
+    let (status, stdout, stderr) = supervise(execution, timeout, cancellable).await?;
+
+    let output_text = String::from_utf8(stdout)?;
+    let error_text = String::from_utf8_lossy(&stderr);
+
+    if !status.success() {
+        match status.code() {
             Some(code) => Err(errors::system_with_internal(
                 &format!("Git exited with a non-zero exit code ({}).", code),
                 "Please check the output printed by Git to determine why the command failed and take appropriate action.",
-                errors::detailed_message(&output_text))),
+                errors::detailed_message(format!("{}\n{}", output_text, error_text).trim()))),
             None => Err(errors::system_with_internal(
                 "Git exited prematurely because it received an unexpected signal.",
                 "Please check the output printed by Git to determine why the command failed and take appropriate action.",
-            errors::detailed_message(&output_text)))
+            errors::detailed_message(format!("{}\n{}", output_text, error_text).trim())))
         }
     } else {
         Ok(output_text)
     }
 }
+
+/// Waits for a long-running Git operation to complete, stopping with an error if it does
+/// not do so within the provided timeout or if the user presses Ctrl+C while it is running.
+pub(super) async fn supervise<F, T>(
+    operation: F,
+    timeout: Option<Duration>,
+    cancellable: bool,
+) -> Result<T, errors::Error>
+where
+    F: Future<Output = Result<T, errors::Error>>,
+{
+    let operation = operation.fuse();
+
+    let deadline = match timeout {
+        Some(duration) => tokio::time::delay_for(duration).left_future(),
+        None => future::pending().right_future(),
+    }
+    .fuse();
+
+    // Listening for Ctrl+C replaces the default handler for the rest of the process, so we
+    // only do so for the commands which the user may want to cancel.
+    let ctrlc = if cancellable {
+        tokio::signal::ctrl_c().left_future()
+    } else {
+        future::pending().right_future()
+    }
+    .fuse();
+
+    pin_mut!(operation, deadline, ctrlc);
+
+    select! {
+        result = operation => result,
+        _ = deadline => Err(errors::user(
+            &format!("Git did not complete within the allowed time ({}s) and was stopped.", timeout.unwrap_or_default().as_secs()),
+            "Please check your network connection and try again. If you are working with a large repository, you may need to run the command manually.")),
+        _ = ctrlc => Err(errors::user(
+            "The Git command was cancelled because you pressed Ctrl+C.",
+            "Run the command again if you would like it to complete."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_stderr_captured() {
+        let temp = tempdir().unwrap();
+
+        let err = git_cmd(
+            Command::new("git")
+                .current_dir(temp.path())
+                .arg("rev-parse")
+                .arg("HEAD"),
+        )
+        .await
+        .expect_err("the command should fail outside of a repository");
+
+        assert!(
+            err.to_string().contains("not a git repository"),
+            "the error should include the output Git wrote to stderr: {}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_progress() {
+        let temp = tempdir().unwrap();
+        let mut lines = vec![];
+
+        git_cmd_streaming(
+            Command::new("git")
+                .current_dir(temp.path())
+                .arg("rev-parse")
+                .arg("--no-such-flag-for-testing"),
+            None,
+            |line| lines.push(line.to_string()),
+        )
+        .await
+        .ok();
+
+        assert!(
+            !lines.is_empty(),
+            "the progress callback should receive the lines written to stderr"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout() {
+        let temp = tempdir().unwrap();
+
+        let err = git_cmd_streaming(
+            Command::new("git")
+                .current_dir(temp.path())
+                .arg("-c")
+                .arg("alias.wait=!sleep 5")
+                .arg("wait"),
+            Some(Duration::from_millis(100)),
+            |_| {},
+        )
+        .await
+        .expect_err("the command should time out");
+
+        assert!(err.to_string().contains("did not complete"));
+    }
+
+    #[tokio::test]
+    async fn test_supervise_timeout() {
+        let err = supervise(
+            future::pending::<Result<(), errors::Error>>(),
+            Some(Duration::from_millis(100)),
+            false,
+        )
+        .await
+        .expect_err("the operation should time out");
+
+        assert!(err.to_string().contains("did not complete"));
+    }
+}
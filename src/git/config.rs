@@ -0,0 +1,23 @@
+use super::git_cmd;
+use crate::errors;
+use std::path;
+use tokio::process::Command;
+
+pub async fn git_config_set(
+    repo: &path::Path,
+    key: &str,
+    value: &str,
+) -> Result<(), errors::Error> {
+    info!("Running `git config --local $KEY $VALUE` to configure the repository");
+    git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("config")
+            .arg("--local")
+            .arg(key)
+            .arg(value),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use super::git_cmd;
+use crate::errors;
+use std::path;
+use tokio::process::Command;
+
+pub async fn git_fetch(repo: &path::Path) -> Result<(), errors::Error> {
+    info!("Running `git fetch --all --prune` to update remote-tracking branches");
+    git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("fetch")
+            .arg("--all")
+            .arg("--prune"),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,21 +1,40 @@
 mod add;
+mod backend;
 mod branch;
 mod checkout;
 mod clone;
 mod cmd;
 mod commit;
+mod config;
+mod fetch;
 mod init;
+mod native;
 mod refs;
 mod remote;
+mod status;
 mod switch;
+mod version;
+mod worktree;
 
 pub use add::git_add;
-pub use branch::{git_branches, git_current_branch};
+pub use backend::{backend, CliBackend, GitBackend};
+pub use branch::{
+    git_branch_delete, git_branches, git_current_branch, git_default_branch, git_gone_branches,
+    git_merged_branches, Branch,
+};
 pub use checkout::git_checkout;
-pub use clone::git_clone;
-pub use cmd::git_cmd;
+pub use clone::{git_clone, git_clone_with_config};
+pub use cmd::{git_cmd, git_cmd_streaming};
 pub use commit::git_commit;
+pub use config::git_config_set;
+pub use fetch::git_fetch;
 pub use init::git_init;
-pub use refs::{git_rev_parse, git_update_ref};
-pub use remote::{git_remote_add, git_remote_list, git_remote_set_url};
-pub use switch::git_switch;
+pub use native::NativeBackend;
+pub use refs::git_rev_parse;
+#[cfg(test)]
+pub use refs::git_update_ref;
+pub use remote::{git_remote_add, git_remote_get_url, git_remote_list, git_remote_set_url};
+pub use status::git_status;
+pub use switch::{git_switch, git_switch_tracking};
+pub use version::git_version;
+pub use worktree::{git_worktree_add, git_worktree_list, git_worktree_remove};
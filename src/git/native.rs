@@ -0,0 +1,408 @@
+use super::{clone::CloneProgress, cmd::supervise, Branch, CliBackend, GitBackend};
+use crate::errors;
+use async_trait::async_trait;
+use git2::{
+    build::RepoBuilder, Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository,
+    StatusOptions, SubmoduleUpdateOptions,
+};
+use std::{
+    cell::RefCell,
+    io::Write,
+    path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A Git backend which uses libgit2 to access repositories in-process, avoiding
+/// the cost of starting a new `git` process for each operation. Any operation
+/// which fails is retried using the Git CLI.
+#[derive(Default)]
+pub struct NativeBackend {
+    fallback: CliBackend,
+    native_clone: bool,
+    clone_timeout: Option<Duration>,
+}
+
+impl NativeBackend {
+    pub fn new(native_clone: bool, clone_timeout: Option<Duration>) -> Self {
+        Self {
+            fallback: CliBackend::new(clone_timeout),
+            native_clone,
+            clone_timeout,
+        }
+    }
+
+    fn native_branches(&self, repo: &path::Path) -> Result<Vec<Branch>, errors::Error> {
+        let repository = Repository::open(repo)?;
+
+        let mut names = vec![];
+        for glob in &["refs/heads/*", "refs/remotes/*"] {
+            for reference in repository.references_glob(glob)? {
+                if let Some(name) = reference?.name() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+
+        Ok(super::branch::parse_branch_refs(
+            names.iter().map(|s| s.as_str()),
+        ))
+    }
+
+    fn native_current_branch(&self, repo: &path::Path) -> Result<String, errors::Error> {
+        let repository = Repository::open(repo)?;
+        let head = repository.find_reference("HEAD")?;
+
+        match head.symbolic_target() {
+            Some(target) if target.starts_with("refs/heads/") => {
+                Ok(target.trim_start_matches("refs/heads/").to_string())
+            }
+            _ => Err(errors::user(
+                "The repository does not currently have a branch checked out.",
+                "Make sure that you have checked out a branch (rather than a specific commit) and try again.",
+            )),
+        }
+    }
+
+    fn native_rev_parse(&self, repo: &path::Path, ref_name: &str) -> Result<String, errors::Error> {
+        let repository = Repository::open(repo)?;
+        let object = repository.revparse_single(ref_name)?;
+
+        Ok(object.id().to_string())
+    }
+
+    fn native_status(&self, repo: &path::Path) -> Result<Vec<String>, errors::Error> {
+        let repository = Repository::open(repo)?;
+        let statuses = repository.statuses(Some(
+            StatusOptions::new()
+                .include_untracked(true)
+                .include_ignored(false),
+        ))?;
+
+        Ok(statuses
+            .iter()
+            .filter_map(|s| s.path().map(|p| p.to_string()))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl GitBackend for NativeBackend {
+    async fn branches(&self, repo: &path::Path) -> Result<Vec<Branch>, errors::Error> {
+        info!("Reading the list of branches using the native Git backend");
+        match self.native_branches(repo) {
+            Ok(branches) => Ok(branches),
+            Err(e) => {
+                debug!("Falling back to the Git CLI: {}", e);
+                self.fallback.branches(repo).await
+            }
+        }
+    }
+
+    async fn current_branch(&self, repo: &path::Path) -> Result<String, errors::Error> {
+        info!("Reading the current branch name using the native Git backend");
+        match self.native_current_branch(repo) {
+            Ok(branch) => Ok(branch),
+            Err(e) => {
+                debug!("Falling back to the Git CLI: {}", e);
+                self.fallback.current_branch(repo).await
+            }
+        }
+    }
+
+    async fn rev_parse(&self, repo: &path::Path, ref_name: &str) -> Result<String, errors::Error> {
+        info!("Resolving the SHA of a reference using the native Git backend");
+        match self.native_rev_parse(repo, ref_name) {
+            Ok(sha) => Ok(sha),
+            Err(e) => {
+                debug!("Falling back to the Git CLI: {}", e);
+                self.fallback.rev_parse(repo, ref_name).await
+            }
+        }
+    }
+
+    async fn status(&self, repo: &path::Path) -> Result<Vec<String>, errors::Error> {
+        info!("Reading the repository status using the native Git backend");
+        match self.native_status(repo) {
+            Ok(changes) => Ok(changes),
+            Err(e) => {
+                debug!("Falling back to the Git CLI: {}", e);
+                self.fallback.status(repo).await
+            }
+        }
+    }
+
+    async fn clone_repo(
+        &self,
+        repo: &path::Path,
+        url: &str,
+        output: Box<dyn Write + Send>,
+    ) -> Result<(), errors::Error> {
+        if !self.native_clone {
+            return self.fallback.clone_repo(repo, url, output).await;
+        }
+
+        info!("Cloning the repository using the native Git backend");
+        let repo = repo.to_path_buf();
+        let url = url.to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let clone = {
+            let cancelled = cancelled.clone();
+            tokio::task::spawn_blocking(move || {
+                native_clone(&repo, &url, &cancelled, CloneProgress::new(output))
+            })
+        };
+
+        let result = supervise(
+            async {
+                clone.await.map_err(|e| {
+                    errors::system_with_internal(
+                        "The background task responsible for cloning your repository failed unexpectedly.",
+                        "Please try again, or disable the `native_clone` feature in your config file to use the Git CLI instead.",
+                        e,
+                    )
+                })?
+            },
+            self.clone_timeout,
+            true,
+        )
+        .await;
+
+        // If we stopped waiting for the clone, it will keep running in the background
+        // until libgit2 next reports its progress and we ask it to stop.
+        cancelled.store(true, Ordering::SeqCst);
+
+        result
+    }
+}
+
+fn native_clone(
+    repo: &path::Path,
+    url: &str,
+    cancelled: &AtomicBool,
+    progress: CloneProgress,
+) -> Result<(), errors::Error> {
+    let progress = RefCell::new(progress);
+    let repository = RepoBuilder::new()
+        .fetch_options(fetch_options(cancelled, &progress))
+        .clone(url, repo)?;
+
+    update_submodules(&repository, cancelled, &progress)
+}
+
+fn update_submodules(
+    repository: &Repository,
+    cancelled: &AtomicBool,
+    progress: &RefCell<CloneProgress>,
+) -> Result<(), errors::Error> {
+    for mut submodule in repository.submodules()? {
+        let mut options = SubmoduleUpdateOptions::new();
+        options.fetch(fetch_options(cancelled, progress));
+        submodule.update(true, Some(&mut options))?;
+
+        update_submodules(&submodule.open()?, cancelled, progress)?;
+    }
+
+    Ok(())
+}
+
+fn fetch_options<'a>(
+    cancelled: &'a AtomicBool,
+    progress: &'a RefCell<CloneProgress>,
+) -> FetchOptions<'a> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        // libgit2 will keep asking for credentials for as long as we keep providing
+        // them, so we give up once each of our strategies has been tried.
+        attempts += 1;
+        if attempts > 2 {
+            return Err(git2::Error::from_str(
+                "no valid credentials were available for this remote",
+            ));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(&git2::Config::open_default()?, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+
+    // Returning false from the progress callbacks tells libgit2 to stop the clone, which
+    // is how we handle timeouts and Ctrl+C.
+    callbacks.sideband_progress(move |data| {
+        for line in String::from_utf8_lossy(data).split(['\r', '\n']) {
+            if !line.trim().is_empty() {
+                progress.borrow_mut().update(line.trim());
+            }
+        }
+
+        !cancelled.load(Ordering::SeqCst)
+    });
+
+    callbacks.transfer_progress(move |stats| {
+        let (stage, done, total) = if stats.received_objects() < stats.total_objects() {
+            (
+                "Receiving objects",
+                stats.received_objects(),
+                stats.total_objects(),
+            )
+        } else {
+            (
+                "Resolving deltas",
+                stats.indexed_deltas(),
+                stats.total_deltas(),
+            )
+        };
+
+        if let Some(percent) = (done * 100).checked_div(total) {
+            progress
+                .borrow_mut()
+                .update(&format!("{}: {}% ({}/{})", stage, percent, done, total));
+        }
+
+        !cancelled.load(Ordering::SeqCst)
+    });
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+    use crate::git::*;
+    use crate::tasks::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_matches_cli() {
+        let temp = tempdir().unwrap();
+        let repo = Repo::new("github.com/sierrasoftworks/test1", temp.path().into());
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .build();
+
+        sequence![
+            GitInit {},
+            GitCheckout { branch: "main" },
+            WriteFile {
+                path: PathBuf::from("README.md"),
+                content: "This is a test file".into(),
+            },
+            GitAdd {
+                paths: vec!["README.md"]
+            },
+            GitCommit {
+                message: "Test",
+                paths: vec!["README.md"]
+            },
+            GitCheckout { branch: "feature" },
+            WriteFile {
+                path: PathBuf::from("CHANGES.md"),
+                content: "This is an uncommitted file",
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .expect("the repo should have been prepared properly");
+
+        let native = NativeBackend::default();
+        let cli = CliBackend::default();
+
+        assert_eq!(
+            native.native_current_branch(&repo.get_path()).unwrap(),
+            cli.current_branch(&repo.get_path()).await.unwrap()
+        );
+
+        let native_branches = native.native_branches(&repo.get_path()).unwrap();
+        assert_eq!(
+            native_branches
+                .iter()
+                .map(|b| b.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["feature", "main"]
+        );
+        assert_eq!(
+            native_branches,
+            cli.branches(&repo.get_path()).await.unwrap()
+        );
+
+        assert_eq!(
+            native.native_rev_parse(&repo.get_path(), "HEAD").unwrap(),
+            cli.rev_parse(&repo.get_path(), "HEAD").await.unwrap()
+        );
+
+        assert_eq!(
+            native.native_status(&repo.get_path()).unwrap(),
+            vec!["CHANGES.md"]
+        );
+        assert_eq!(
+            cli.status(&repo.get_path()).await.unwrap(),
+            vec!["CHANGES.md"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback() {
+        let temp = tempdir().unwrap();
+
+        NativeBackend::default()
+            .current_branch(temp.path())
+            .await
+            .expect_err("the directory is not a repository, so both backends should fail");
+    }
+
+    #[tokio::test]
+    async fn test_clone_local() {
+        let temp = tempdir().unwrap();
+        let source = Repo::new(
+            "github.com/sierrasoftworks/source",
+            temp.path().join("source"),
+        );
+        let core = CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .build();
+
+        sequence![
+            GitInit {},
+            GitCheckout { branch: "main" },
+            WriteFile {
+                path: PathBuf::from("README.md"),
+                content: "This is a test file".into(),
+            },
+            GitAdd {
+                paths: vec!["README.md"]
+            },
+            GitCommit {
+                message: "Test",
+                paths: vec!["README.md"]
+            }
+        ]
+        .apply_repo(&core, &source)
+        .await
+        .expect("the repo should have been prepared properly");
+
+        let target = temp.path().join("target");
+        NativeBackend::new(true, None)
+            .clone_repo(
+                &target,
+                source.get_path().to_str().unwrap(),
+                Box::new(std::io::sink()),
+            )
+            .await
+            .expect("the repository should be cloned");
+
+        assert!(target.join("README.md").exists());
+    }
+}
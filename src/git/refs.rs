@@ -17,6 +17,7 @@ pub async fn git_rev_parse(repo: &path::Path, ref_name: &str) -> Result<String,
     .to_string())
 }
 
+#[cfg(test)]
 pub async fn git_update_ref(
     repo: &path::Path,
     ref_name: &str,
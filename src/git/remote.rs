@@ -14,6 +14,20 @@ pub async fn git_remote_list(repo: &path::Path) -> Result<Vec<String>, errors::E
     Ok(output)
 }
 
+pub async fn git_remote_get_url(repo: &path::Path, name: &str) -> Result<String, errors::Error> {
+    info!("Running `git remote get-url $NAME` to get the URL of a remote");
+    Ok(git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("remote")
+            .arg("get-url")
+            .arg(name),
+    )
+    .await?
+    .trim()
+    .to_string())
+}
+
 pub async fn git_remote_add(repo: &path::Path, name: &str, url: &str) -> Result<(), errors::Error> {
     info!("Running `git remote add $NAME $URL` to add new remote");
     git_cmd(
@@ -0,0 +1,21 @@
+use super::git_cmd;
+use crate::errors;
+use std::path;
+use tokio::process::Command;
+
+pub async fn git_status(repo: &path::Path) -> Result<Vec<String>, errors::Error> {
+    info!("Running `git status --porcelain` to get the list of changed files");
+    let output = git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("status")
+            .arg("--porcelain"),
+    )
+    .await?;
+
+    Ok(output
+        .split_terminator('\n')
+        .filter(|l| l.len() > 3)
+        .map(|l| l[3..].trim().to_string())
+        .collect())
+}
@@ -26,3 +26,23 @@ pub async fn git_switch(repo: &path::Path, name: &str, create: bool) -> Result<(
 
     Ok(())
 }
+
+pub async fn git_switch_tracking(
+    repo: &path::Path,
+    name: &str,
+    remote: &str,
+) -> Result<(), errors::Error> {
+    info!("Running `git switch --create $BRANCH --track $REMOTE/$BRANCH` to track a remote branch");
+    git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("switch")
+            .arg("--create")
+            .arg(name)
+            .arg("--track")
+            .arg(format!("{}/{}", remote, name)),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use super::git_cmd;
+use crate::errors;
+use tokio::process::Command;
+
+/// Gets the version of Git which is installed, like `2.30.1`.
+pub async fn git_version() -> Result<String, errors::Error> {
+    let output = git_cmd(Command::new("git").arg("--version")).await?;
+
+    output
+        .split_whitespace()
+        .find(|part| part.chars().next().map(|c| c.is_ascii_digit()).unwrap_or_default())
+        .map(|v| v.to_string())
+        .ok_or_else(|| {
+            errors::system_with_internal(
+                "We could not determine which version of Git you have installed.",
+                "Please make sure that `git --version` reports the version of Git you have installed.",
+                errors::detailed_message(&output),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_version() {
+        let version = git_version().await.unwrap();
+        assert!(
+            version.split('.').next().unwrap().parse::<u32>().is_ok(),
+            "the version should start with a major version number: {}",
+            version
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use super::git_cmd;
+use crate::errors;
+use std::path;
+use tokio::process::Command;
+
+/// A working tree which is attached to a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worktree {
+    pub path: path::PathBuf,
+    pub branch: Option<String>,
+}
+
+pub async fn git_worktree_list(repo: &path::Path) -> Result<Vec<Worktree>, errors::Error> {
+    info!("Running `git worktree list --porcelain` to get the list of worktrees");
+    let output = git_cmd(
+        Command::new("git")
+            .current_dir(repo)
+            .arg("worktree")
+            .arg("list")
+            .arg("--porcelain"),
+    )
+    .await?;
+
+    let mut worktrees = vec![];
+    for entry in output.split("\n\n") {
+        let mut path = None;
+        let mut branch = None;
+
+        for line in entry.lines() {
+            if let Some(p) = line.strip_prefix("worktree ") {
+                path = Some(path::PathBuf::from(p));
+            } else if let Some(b) = line.strip_prefix("branch ") {
+                branch = Some(b.trim_start_matches("refs/heads/").to_string());
+            }
+        }
+
+        if let Some(path) = path {
+            worktrees.push(Worktree { path, branch });
+        }
+    }
+
+    Ok(worktrees)
+}
+
+pub async fn git_worktree_add(
+    repo: &path::Path,
+    path: &path::Path,
+    branch: &str,
+    start_point: Option<&str>,
+) -> Result<(), errors::Error> {
+    match start_point {
+        Some(start_point) => {
+            info!(
+                "Running `git worktree add -b $BRANCH $PATH $START_POINT` to create a new worktree"
+            );
+            git_cmd(
+                Command::new("git")
+                    .current_dir(repo)
+                    .arg("worktree")
+                    .arg("add")
+                    .arg("-b")
+                    .arg(branch)
+                    .arg(path)
+                    .arg(start_point),
+            )
+            .await?;
+        }
+        None => {
+            info!("Running `git worktree add $PATH $BRANCH` to create a new worktree");
+            git_cmd(
+                Command::new("git")
+                    .current_dir(repo)
+                    .arg("worktree")
+                    .arg("add")
+                    .arg(path)
+                    .arg(branch),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn git_worktree_remove(
+    repo: &path::Path,
+    path: &path::Path,
+    force: bool,
+) -> Result<(), errors::Error> {
+    info!("Running `git worktree remove $PATH` to remove a worktree");
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo).arg("worktree").arg("remove");
+
+    if force {
+        cmd.arg("--force");
+    }
+
+    git_cmd(cmd.arg(path)).await?;
+
+    Ok(())
+}
@@ -68,6 +68,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 .value_name("FILE")
                 .about("The path to your git-tool configuration file.")
                 .takes_value(true))
+        .arg(Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .env("GITTOOL_PROFILE")
+                .value_name("NAME")
+                .about("The config profile to use, instead of the one for your current directory.")
+                .takes_value(true))
         .arg(Arg::new("update-resume-internal")
             .long("update-resume-internal")
             .about("A legacy flag used to coordinate updates in the same way that the `update --state` flag is used now. Maintained for backwards compatibility reasons.")
@@ -103,13 +110,11 @@ async fn run<'a>(
     commands: Vec<Arc<dyn CommandRunnable<DefaultCore>>>,
     matches: ArgMatches,
 ) -> Result<i32, errors::Error> {
-    let mut core_builder = core::CoreBuilder::default();
-
-    if let Some(cfg_file) = matches.value_of("config") {
-        core_builder = core_builder.with_config_file(cfg_file)?;
-    }
-
-    let core = Arc::new(core_builder.build());
+    let core = Arc::new(
+        core::CoreBuilder::default()
+            .with_config_file(matches.value_of("config"), matches.value_of("profile"))?
+            .build(),
+    );
 
     // Legacy update interoperability for compatibility with the Golang implementation
     if let Some(state) = matches.value_of("update-resume-internal") {
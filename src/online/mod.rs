@@ -4,5 +4,5 @@ pub mod service;
 
 use super::errors;
 use super::errors::Error;
-pub use registry::GitHubRegistry;
+pub use registry::ConfiguredRegistry;
 pub use service::{services, OnlineService};
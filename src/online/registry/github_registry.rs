@@ -3,13 +3,34 @@ use crate::errors;
 use http::Uri;
 use serde::Deserialize;
 
-pub struct GitHubRegistry;
+pub struct GitHubRegistry {
+    repo: String,
+    git_ref: String,
+    path: String,
+}
+
+impl GitHubRegistry {
+    pub fn new(repo: &str, git_ref: &str, path: &str) -> Self {
+        Self {
+            repo: repo.to_string(),
+            git_ref: git_ref.to_string(),
+            path: path.trim_matches('/').to_string(),
+        }
+    }
+}
+
+impl Default for GitHubRegistry {
+    fn default() -> Self {
+        Self::new("SierraSoftworks/git-tool", "main", "registry")
+    }
+}
 
 #[async_trait::async_trait]
 impl<C: Core> Registry<C> for GitHubRegistry {
     async fn get_entries(&self, core: &C) -> Result<Vec<String>, Error> {
         let uri: Uri = format!(
-            "https://api.github.com/repos/SierraSoftworks/git-tool/git/trees/main?recursive=true"
+            "https://api.github.com/repos/{}/git/trees/{}?recursive=true",
+            self.repo, self.git_ref
         )
         .parse()?;
 
@@ -33,12 +54,12 @@ impl<C: Core> Registry<C> for GitHubRegistry {
 
                 let mut entries: Vec<String> = Vec::new();
 
-                let prefix = "registry/";
+                let prefix = format!("{}/", self.path);
                 let suffix = ".yaml";
 
                 for node in tree.tree {
                     if node.node_type == "blob"
-                        && node.path.starts_with(prefix)
+                        && node.path.starts_with(&prefix)
                         && node.path.ends_with(suffix)
                     {
                         let len = node.path.len();
@@ -65,8 +86,8 @@ impl<C: Core> Registry<C> for GitHubRegistry {
 
     async fn get_entry(&self, core: &C, id: &str) -> Result<Entry, Error> {
         let uri = format!(
-            "https://raw.githubusercontent.com/SierraSoftworks/git-tool/main/registry/{}.yaml",
-            id
+            "https://raw.githubusercontent.com/{}/{}/{}/{}.yaml",
+            self.repo, self.git_ref, self.path, id
         )
         .parse()?;
         let resp = core.http_client().get(uri).await?;
@@ -90,7 +111,7 @@ impl<C: Core> Registry<C> for GitHubRegistry {
             status => {
                 let inner_error = errors::hyper::HyperResponseError::with_body(resp).await;
                 Err(errors::system_with_internal(
-                    &format!("Received an HTTP {} response from GitHub when attempting to fetch /registry/{}.yaml.", status, id),
+                    &format!("Received an HTTP {} response from GitHub when attempting to fetch /{}/{}.yaml.", status, self.path, id),
                     "Please read the error message below and decide if there is something you can do to fix the problem, or report it to us on GitHub.",
                     inner_error))
             }
@@ -118,7 +139,7 @@ mod tests {
     #[tokio::test]
     async fn get_entries() {
         let core = CoreBuilder::default().build();
-        let registry = GitHubRegistry;
+        let registry = GitHubRegistry::default();
 
         let entries = registry.get_entries(&core).await.unwrap();
         assert_ne!(entries.len(), 0);
@@ -128,7 +149,7 @@ mod tests {
     #[tokio::test]
     async fn get_entry() {
         let core = CoreBuilder::default().build();
-        let registry = GitHubRegistry;
+        let registry = GitHubRegistry::default();
 
         let entry = registry.get_entry(&core, "apps/bash").await.unwrap();
         assert_eq!(entry.name, "Bash");
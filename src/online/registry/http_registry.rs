@@ -0,0 +1,129 @@
+use super::*;
+use crate::errors;
+
+/// A registry hosted on a web server, which lists its entries in an `index.yaml` file
+/// and serves each of them as `$id.yaml`.
+pub struct HttpRegistry {
+    url: String,
+}
+
+impl HttpRegistry {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get<C: Core>(&self, core: &C, path: &str) -> Result<Option<Vec<u8>>, Error> {
+        let uri = format!("{}/{}", self.url, path).parse()?;
+        let resp = core.http_client().get(uri).await?;
+
+        match resp.status() {
+            http::StatusCode::OK => {
+                let body = hyper::body::to_bytes(resp.into_body()).await?;
+                Ok(Some(body.to_vec()))
+            }
+            http::StatusCode::NOT_FOUND => Ok(None),
+            status => {
+                let inner_error = errors::hyper::HyperResponseError::with_body(resp).await;
+                Err(errors::user_with_internal(
+                    &format!("Received an HTTP {} response from the registry at {} when attempting to fetch /{}.", status, self.url, path),
+                    "Please check that the registry's URL in your config file is correct and that the server is available.",
+                    inner_error))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Core> Registry<C> for HttpRegistry {
+    async fn get_entries(&self, core: &C) -> Result<Vec<String>, Error> {
+        match self.get(core, "index.yaml").await? {
+            Some(body) => Ok(serde_yaml::from_slice(&body)?),
+            None => Err(errors::user(
+                &format!("The registry at {} does not have an index.yaml file.", self.url),
+                "Please check that the registry's URL in your config file is correct and that it publishes an index.yaml file listing its entries.",
+            )),
+        }
+    }
+
+    async fn get_entry(&self, core: &C, id: &str) -> Result<Entry, Error> {
+        match self.get(core, &format!("{}.yaml", id)).await? {
+            Some(body) => Ok(serde_yaml::from_slice(&body)?),
+            None => Err(errors::user(
+                &format!("Could not find {} in the registry at {}.", id, self.url),
+                "Please make sure that you've selected a configuration entry which exists in the registry. You can check this with `git-tool config list`.",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_entries() {
+        let core = CoreBuilder::default()
+            .with_http_connector(mocks::IndexFlow::default())
+            .build();
+        let registry = HttpRegistry::new("https://registry.example.com/git-tool/");
+
+        let entries = registry.get_entries(&core).await.unwrap();
+        assert_eq!(entries, vec!["apps/vim", "services/acme"]);
+    }
+
+    #[tokio::test]
+    async fn get_entry() {
+        let core = CoreBuilder::default()
+            .with_http_connector(mocks::EntryFlow::default())
+            .build();
+        let registry = HttpRegistry::new("https://registry.example.com/git-tool");
+
+        let entry = registry.get_entry(&core, "apps/vim").await.unwrap();
+        assert_eq!(entry.name, "Vim");
+    }
+
+    #[tokio::test]
+    async fn get_entry_missing() {
+        let core = CoreBuilder::default()
+            .with_http_connector(mocks::MissingFlow::default())
+            .build();
+        let registry = HttpRegistry::new("https://registry.example.com/git-tool");
+
+        assert!(registry.get_entry(&core, "apps/missing").await.is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    pub type IndexFlow = MockHttpRegistryIndexFlow;
+    pub type EntryFlow = MockHttpRegistryEntryFlow;
+    pub type MissingFlow = MockHttpRegistryMissingFlow;
+
+    mock_connector_in_order!(MockHttpRegistryIndexFlow {
+r#"HTTP/1.1 200 OK
+Content-Type: application/yaml
+Content-Length: 26
+
+- apps/vim
+- services/acme
+"#});
+
+    mock_connector_in_order!(MockHttpRegistryEntryFlow {
+r#"HTTP/1.1 200 OK
+Content-Type: application/yaml
+Content-Length: 71
+
+name: Vim
+description: Opens the repo in Vim
+configs:
+  - platform: any
+"#});
+
+    mock_connector_in_order!(MockHttpRegistryMissingFlow {
+r#"HTTP/1.1 404 Not Found
+Content-Length: 0
+
+"#});
+}
@@ -0,0 +1,449 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+
+/// A problem with one of the entries in a registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub entry: String,
+    pub platform: Option<String>,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(entry: &str, platform: Option<&str>, message: &str) -> Self {
+        Self {
+            entry: entry.to_string(),
+            platform: platform.map(|p| p.to_string()),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.platform {
+            Some(platform) => write!(f, "{}#{} {}", self.entry, platform, self.message),
+            None => write!(f, "{} {}", self.entry, self.message),
+        }
+    }
+}
+
+/// Loads each of the entries in a registry, checking that they are complete, that their templates
+/// can be rendered and that no two entries add the same app or service.
+pub async fn lint_registry<C: Core>(
+    core: &C,
+    registry: &dyn Registry<C>,
+) -> Result<(usize, Vec<LintIssue>), Error> {
+    let mut ids = registry.get_entries(core).await?;
+    ids.sort();
+
+    let mut issues = vec![];
+    let mut names: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    let mut domains: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+    for id in ids.iter() {
+        let entry = match registry.get_entry(core, id).await {
+            Ok(entry) => entry,
+            Err(err) => {
+                issues.push(LintIssue::new(
+                    id,
+                    None,
+                    &format!("could not be loaded: {}", err.message()),
+                ));
+                continue;
+            }
+        };
+
+        issues.extend(lint_entry(core.config(), id, &entry));
+
+        if let Some(other) = claim(&mut names, &entry.name, "any", id) {
+            issues.push(LintIssue::new(
+                id,
+                None,
+                &format!("has the same name as {} ({})", other, entry.name),
+            ));
+        }
+
+        // Apps which share a name are alternatives to one another (like VSCode and VSCode Insiders),
+        // but two services for the same domain on the same platform would replace each other.
+        let entry_domains: HashSet<(&str, &str)> = entry
+            .configs
+            .iter()
+            .filter_map(|c| {
+                c.service
+                    .as_ref()
+                    .map(|s| (s.domain.as_str(), c.platform.as_str()))
+            })
+            .collect();
+        for (domain, platform) in entry_domains {
+            if let Some(other) = claim(&mut domains, domain, platform, id) {
+                issues.push(LintIssue::new(
+                    id,
+                    Some(platform),
+                    &format!(
+                        "adds the service '{}' which is also added by {}",
+                        domain, other
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok((ids.len(), issues))
+}
+
+/// Records that an entry uses a name on a platform, returning the entry which already used it
+/// on the same platform (if any). Names used on the `any` platform clash with every platform.
+fn claim(
+    claimed: &mut HashMap<String, Vec<(String, String)>>,
+    name: &str,
+    platform: &str,
+    id: &str,
+) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+
+    let users = claimed.entry(name.to_string()).or_default();
+    match users
+        .iter()
+        .find(|(p, other)| other != id && (p == platform || p == "any" || platform == "any"))
+    {
+        Some((_, other)) => Some(other.clone()),
+        None => {
+            users.push((platform.to_string(), id.to_string()));
+            None
+        }
+    }
+}
+
+fn lint_entry(config: &Config, id: &str, entry: &Entry) -> Vec<LintIssue> {
+    let mut issues = vec![];
+    let mut issue = |platform: Option<&str>, message: &str| {
+        issues.push(LintIssue::new(id, platform, message));
+    };
+
+    let is_app = id.starts_with("apps/");
+    let is_service = id.starts_with("services/");
+
+    if !id.is_ascii() {
+        issue(None, "has a non-ascii ID");
+    }
+
+    if !is_app && !is_service {
+        issue(None, "is not in the apps/ or services/ namespace");
+    }
+
+    if entry.name.is_empty() {
+        issue(None, "has an empty name field");
+    }
+
+    if entry.description.is_empty() {
+        issue(None, "has an empty description field");
+    }
+
+    if entry.version.is_empty() {
+        issue(None, "has an empty version field");
+    }
+
+    if entry.configs.is_empty() {
+        issue(None, "does not have any configs");
+    }
+
+    for config_entry in entry.configs.iter() {
+        let platform = Some(config_entry.platform.as_str());
+
+        if config_entry.platform.is_empty() {
+            issue(None, "has a config which is missing the platform field");
+        } else if !PLATFORMS.contains(&config_entry.platform.as_str()) {
+            issue(
+                platform,
+                &format!(
+                    "has an unknown platform, it should be one of: {}",
+                    PLATFORMS.join(", ")
+                ),
+            );
+        }
+
+        if is_app && config_entry.app.is_none() {
+            issue(
+                platform,
+                "is in the apps/ namespace but has a configuration which is missing an app setting",
+            );
+        }
+
+        if is_service && config_entry.service.is_none() {
+            issue(
+                platform,
+                "is in the services/ namespace but has a configuration which is missing a service setting",
+            );
+        }
+
+        if let Some(app) = &config_entry.app {
+            for message in lint_app(config, app) {
+                issue(platform, &message);
+            }
+        }
+
+        if let Some(svc) = &config_entry.service {
+            for message in lint_service(svc) {
+                issue(platform, &message);
+            }
+        }
+    }
+
+    issues
+}
+
+fn lint_app(config: &Config, app: &EntryApp) -> Vec<String> {
+    let mut issues = vec![];
+
+    if app.name.is_empty() {
+        issues.push("has an app entry which is missing its name".to_string());
+    }
+
+    if app.command.is_empty() {
+        issues.push("has an app entry which is missing its command".to_string());
+        return issues;
+    }
+
+    let app: App = app.clone().into();
+    let targets: Vec<(&str, Box<dyn Target>)> = vec![
+        (
+            "repository",
+            Box::new(Repo::new(
+                "example.com/test/repo",
+                PathBuf::from("/dev/example.com/test/repo"),
+            )),
+        ),
+        (
+            "scratchpad",
+            Box::new(Scratchpad::new(
+                "2020w01",
+                PathBuf::from("/scratch/2020w01"),
+            )),
+        ),
+    ];
+
+    for (kind, target) in targets {
+        let context = target.template_context(config);
+
+        if let Err(err) = app.get_program(context.clone()) {
+            issues.push(format!(
+                "could not render the command for a {}: {}",
+                kind,
+                err.message()
+            ));
+        }
+
+        if let Err(err) = app.get_program_args(context.clone()) {
+            issues.push(format!(
+                "could not render the arguments for a {}: {}",
+                kind,
+                err.message()
+            ));
+        }
+
+        match app.get_program_environment(context) {
+            Ok(environment) => {
+                if let Some(env) = environment.iter().find(|e| !e.contains('=')) {
+                    issues.push(format!(
+                        "has an environment variable which is not in the KEY=value format: {}",
+                        env
+                    ));
+                }
+            }
+            Err(err) => issues.push(format!(
+                "could not render the environment for a {}: {}",
+                kind,
+                err.message()
+            )),
+        }
+    }
+
+    // Templates which can't be rendered for either kind of target should only be reported once.
+    let mut unique = vec![];
+    for issue in issues {
+        if !unique.contains(&issue) {
+            unique.push(issue);
+        }
+    }
+
+    unique
+}
+
+fn lint_service(svc: &EntryService) -> Vec<String> {
+    let mut issues = vec![];
+
+    if svc.domain.is_empty() {
+        issues.push("has a service entry which is missing its domain".to_string());
+    }
+
+    if svc.pattern.is_empty() {
+        issues.push("has a service entry which is missing its pattern".to_string());
+    } else if !valid_service_pattern(&svc.pattern) {
+        issues.push("has a service entry with an invalid pattern, it should match the regex: /^\\*(\\/\\*)*$/".to_string());
+    }
+
+    if svc.website.is_empty() {
+        issues.push("has a service entry which is missing its website template".to_string());
+    }
+
+    if svc.http_url.is_empty() {
+        issues.push("has a service entry which is missing its Git+HTTP template".to_string());
+    }
+
+    if svc.git_url.is_empty() {
+        issues.push("has a service entry which is missing its Git+SSH URL template".to_string());
+    }
+
+    if !issues.is_empty() {
+        return issues;
+    }
+
+    // We build a sample repo with as many namespace segments as the service's pattern expects.
+    let segments = svc.pattern.split('/').count().max(2);
+    let mut full_name = vec![svc.domain.clone()];
+    full_name.extend((1..segments).map(|i| format!("namespace{}", i)));
+    full_name.push("repo".to_string());
+
+    let test_repo = Repo::new(
+        &full_name.join("/"),
+        PathBuf::from("/dev").join(full_name.join("/")),
+    );
+    let test_service: Service = svc.clone().into();
+
+    if let Err(err) = test_service.get_website(&test_repo) {
+        issues.push(format!(
+            "could not render the website URL for a repository: {}",
+            err.message()
+        ));
+    }
+
+    if let Err(err) = test_service.get_git_url(&test_repo) {
+        issues.push(format!(
+            "could not render the Git+SSH URL for a repository: {}",
+            err.message()
+        ));
+    }
+
+    if let Err(err) = test_service.get_http_url(&test_repo) {
+        issues.push(format!(
+            "could not render the Git+HTTP URL for a repository: {}",
+            err.message()
+        ));
+    }
+
+    issues
+}
+
+fn valid_service_pattern(pattern: &str) -> bool {
+    let mut expecting_slash = false;
+    for c in pattern.chars() {
+        if expecting_slash && c != '/' {
+            return false;
+        }
+
+        if !expecting_slash && c != '*' {
+            return false;
+        }
+
+        expecting_slash = !expecting_slash;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lint_invalid_registry() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("apps")).unwrap();
+        std::fs::create_dir_all(temp.path().join("services")).unwrap();
+
+        std::fs::write(
+            temp.path().join("apps").join("code.yaml"),
+            "name: Code\ndescription: Launches VS Code.\nversion: 1.0.0\nconfigs:\n  - platform: any\n    app:\n      name: code\n      command: code\n      args: [\"{{ .Target.Path }}\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("apps").join("code-broken.yaml"),
+            "name: Code Broken\ndescription: Launches VS Code badly.\nconfigs:\n  - platform: macos\n    app:\n      name: code\n      command: \"{{ .Target.Path\"\n      environment: [\"NO_VALUE\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("services").join("example.yaml"),
+            "name: Example\ndescription: An example service.\nversion: 1.0.0\nconfigs:\n  - platform: any\n    service:\n      domain: example.com\n      website: \"https://{{ .Service.Domain }}/{{ .Repo.FullName }}\"\n      httpUrl: \"https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git\"\n      gitUrl: \"git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git\"\n      pattern: \"*/*\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("services").join("example-linux.yaml"),
+            "name: Example (Linux)\ndescription: An example service.\nversion: 1.0.0\nconfigs:\n  - platform: linux\n    service:\n      domain: example.com\n      website: \"https://{{ .Service.Domain }}/{{ .Repo.FullName }}\"\n      httpUrl: \"https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git\"\n      gitUrl: \"git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git\"\n      pattern: \"*/*\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("services").join("invalid.yaml"),
+            "name: [not, a, string]\n",
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default().build();
+        let registry = FileRegistry::new(temp.path().to_path_buf());
+        let (count, issues) = lint_registry(&core, &registry).await.unwrap();
+
+        assert_eq!(count, 5);
+
+        let issues: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        assert!(
+            issues.contains(&"apps/code-broken has an empty version field".to_string()),
+            "{:?}",
+            issues
+        );
+        assert!(
+            issues.contains(&"apps/code-broken#macos has an unknown platform, it should be one of: any, windows, linux, darwin".to_string()),
+            "{:?}",
+            issues
+        );
+        assert!(
+            issues.iter().any(|i| i.starts_with(
+                "apps/code-broken#macos could not render the command for a repository: "
+            )),
+            "{:?}",
+            issues
+        );
+        assert!(
+            issues.contains(&"apps/code-broken#macos has an environment variable which is not in the KEY=value format: NO_VALUE".to_string()),
+            "{:?}",
+            issues
+        );
+        assert!(
+            issues.contains(&"services/example-linux#linux adds the service 'example.com' which is also added by services/example".to_string()),
+            "{:?}",
+            issues
+        );
+        assert!(
+            !issues.iter().any(|i| i.contains("adds the app 'code'")),
+            "{:?}",
+            issues
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.starts_with("services/invalid could not be loaded: ")),
+            "{:?}",
+            issues
+        );
+        assert!(
+            !issues
+                .iter()
+                .any(|i| i.starts_with("apps/code ") || i.starts_with("services/example ")),
+            "{:?}",
+            issues
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use crate::core::*;
+use crate::errors;
 use serde::{Deserialize, Serialize};
 use std::env::consts::OS;
 
 mod file_registry;
 mod github_registry;
+mod http_registry;
+mod lint;
 
 pub use file_registry::FileRegistry;
 pub use github_registry::GitHubRegistry;
+pub use http_registry::HttpRegistry;
+pub use lint::lint_registry;
 
 #[async_trait::async_trait]
 pub trait Registry<C: Core>: Send + Sync {
@@ -14,14 +19,128 @@ pub trait Registry<C: Core>: Send + Sync {
     async fn get_entry(&self, core: &C, id: &str) -> Result<Entry, Error>;
 }
 
+/// Searches each of the registries in your config, namespacing their entries with the
+/// registry's name (like `git-tool:apps/bash`).
+pub struct ConfiguredRegistry;
+
+fn registry_for<C: Core>(source: &RegistrySource) -> Box<dyn Registry<C>> {
+    match source.get_kind() {
+        RegistryKind::GitHub {
+            repo,
+            git_ref,
+            path,
+        } => Box::new(GitHubRegistry::new(repo, git_ref, path)),
+        RegistryKind::Directory { path } => Box::new(FileRegistry::new(path.clone())),
+        RegistryKind::Http { url } => Box::new(HttpRegistry::new(url)),
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Core> Registry<C> for ConfiguredRegistry {
+    async fn get_entries(&self, core: &C) -> Result<Vec<String>, Error> {
+        let (entries, failures) = self.get_available_entries(core).await;
+        for (name, err) in failures {
+            warn!(
+                "Unable to get the entries from the {} registry: {}",
+                name, err
+            );
+        }
+
+        Ok(entries)
+    }
+
+    async fn get_entry(&self, core: &C, id: &str) -> Result<Entry, Error> {
+        self.find_entry(core, id).await.map(|(_, entry)| entry)
+    }
+}
+
+impl ConfiguredRegistry {
+    /// Gets the entries from each of your registries, skipping any which could not be read
+    /// and returning their names along with the reason they failed.
+    pub async fn get_available_entries<C: Core>(
+        &self,
+        core: &C,
+    ) -> (Vec<String>, Vec<(String, Error)>) {
+        let mut entries = Vec::new();
+        let mut failures = Vec::new();
+
+        for source in core.config().get_registries() {
+            match registry_for::<C>(&source).get_entries(core).await {
+                Ok(ids) => {
+                    for entry in ids {
+                        entries.push(format!("{}:{}", source.get_name(), entry));
+                    }
+                }
+                Err(err) => failures.push((source.get_name().to_string(), err)),
+            }
+        }
+
+        (entries, failures)
+    }
+
+    /// Gets an entry along with its fully qualified id (like `git-tool:apps/bash`), which
+    /// is used to keep track of the entries that have been added to your config.
+    pub async fn find_entry<C: Core>(&self, core: &C, id: &str) -> Result<(String, Entry), Error> {
+        let sources = core.config().get_registries();
+
+        if let Some((name, entry_id)) = id.split_once(':') {
+            if let Some(source) = sources.iter().find(|s| s.get_name() == name) {
+                let entry = registry_for::<C>(source).get_entry(core, entry_id).await?;
+                return Ok((id.to_string(), entry));
+            }
+        }
+
+        // Entries without a registry prefix are looked up in each of the registries in turn.
+        let mut error = None;
+        for source in sources.iter() {
+            match registry_for::<C>(source).get_entry(core, id).await {
+                Ok(entry) => return Ok((format!("{}:{}", source.get_name(), id), entry)),
+                Err(err) => error = Some(err),
+            }
+        }
+
+        Err(error.unwrap_or_else(|| {
+            errors::user(
+                &format!("Could not find {} in any of your registries.", id),
+                "Please make sure that you've selected a configuration entry which exists in one of your registries. You can check this with `git-tool config list`.",
+            )
+        }))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Entry {
     pub name: String,
     pub description: String,
     #[serde(default)]
+    pub version: String,
+    #[serde(default)]
     pub configs: Vec<EntryConfig>,
 }
 
+impl Entry {
+    /// Determines whether this entry has already been added to your config, by looking
+    /// for apps with the same name and services with the same domain.
+    pub fn is_installed(&self, config: &Config) -> bool {
+        self.configs.iter().filter(|c| c.is_compatible()).any(|c| {
+            c.app
+                .as_ref()
+                .map(|app| config.get_app(&app.name).is_some())
+                .unwrap_or_default()
+                || c.service
+                    .as_ref()
+                    .map(|svc| config.get_service(&svc.domain).is_some())
+                    .unwrap_or_default()
+        })
+    }
+
+    /// Determines whether this entry's name or description contains the given search term.
+    pub fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        self.name.to_lowercase().contains(&term) || self.description.to_lowercase().contains(&term)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct EntryConfig {
     pub platform: String,
@@ -67,20 +186,35 @@ pub struct EntryService {
     #[serde(rename = "gitUrl")]
     pub git_url: String,
     pub pattern: String,
+    #[serde(
+        rename = "oauthClientId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub oauth_client_id: Option<String>,
 }
 
 impl Into<Service> for EntryService {
     fn into(self) -> Service {
-        Service::builder()
+        let mut builder = Service::builder();
+        builder
             .with_domain(&self.domain)
             .with_website(&self.website)
             .with_git_url(&self.git_url)
             .with_http_url(&self.http_url)
-            .with_pattern(&self.pattern)
-            .into()
+            .with_pattern(&self.pattern);
+
+        if let Some(client_id) = &self.oauth_client_id {
+            builder.with_oauth_client_id(client_id);
+        }
+
+        (&mut builder).into()
     }
 }
 
+/// The platforms which registry entries may target, matching those used by `EntryConfig::is_compatible`.
+pub const PLATFORMS: &[&str] = &["any", "windows", "linux", "darwin"];
+
 fn translate_os_name(name: &str) -> &str {
     match name {
         "macos" => "darwin",
@@ -91,6 +225,121 @@ fn translate_os_name(name: &str) -> &str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test::get_repo_root;
+
+    #[tokio::test]
+    async fn configured_registry() {
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: /dev
+registries:
+  - name: git-tool
+    type: directory
+    path: "{}"
+"#,
+            get_repo_root()
+                .join("registry")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\")
+        ))
+        .unwrap();
+        let core = CoreBuilder::default().with_config(&cfg).build();
+
+        let entries = ConfiguredRegistry.get_entries(&core).await.unwrap();
+        assert!(entries.iter().any(|e| e == "git-tool:apps/bash"));
+
+        let entry = ConfiguredRegistry
+            .get_entry(&core, "git-tool:apps/bash")
+            .await
+            .unwrap();
+        assert_eq!(entry.name, "Bash");
+
+        let (id, entry) = ConfiguredRegistry
+            .find_entry(&core, "apps/bash")
+            .await
+            .unwrap();
+        assert_eq!(id, "git-tool:apps/bash");
+        assert_eq!(entry.name, "Bash");
+        assert_eq!(entry.version, "1.0.0");
+
+        assert!(ConfiguredRegistry
+            .get_entry(&core, "apps/missing")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn configured_registry_unavailable() {
+        let cfg = Config::from_str(&format!(
+            r#"
+directory: /dev
+registries:
+  - name: missing
+    type: directory
+    path: "{}"
+  - name: git-tool
+    type: directory
+    path: "{}"
+"#,
+            get_repo_root()
+                .join("missing")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\"),
+            get_repo_root()
+                .join("registry")
+                .to_str()
+                .unwrap()
+                .replace("\\", "\\\\")
+        ))
+        .unwrap();
+        let core = CoreBuilder::default().with_config(&cfg).build();
+
+        let (entries, failures) = ConfiguredRegistry.get_available_entries(&core).await;
+        assert!(entries.iter().any(|e| e == "git-tool:apps/bash"));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "missing");
+
+        let entries = ConfiguredRegistry.get_entries(&core).await.unwrap();
+        assert!(entries.iter().any(|e| e == "git-tool:apps/bash"));
+    }
+
+    #[test]
+    fn entry_installed() {
+        let entry = Entry {
+            name: "Example".to_string(),
+            description: "An example service for testing".to_string(),
+            version: "1.0.0".to_string(),
+            configs: vec![EntryConfig {
+                platform: "any".to_string(),
+                service: Some(EntryService {
+                    domain: "github.com".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        };
+
+        assert!(entry.is_installed(&Config::default()));
+        let cfg = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: example.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+"#,
+        )
+        .unwrap();
+        assert!(!entry.is_installed(&cfg));
+
+        assert!(entry.matches("example"));
+        assert!(entry.matches("TESTING"));
+        assert!(!entry.matches("gitlab"));
+    }
 
     #[test]
     fn is_compatible() {
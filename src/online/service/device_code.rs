@@ -0,0 +1,287 @@
+use super::*;
+use crate::errors;
+use http::{Request, Uri};
+use hyper::Body;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// An OAuth 2.0 device authorization flow (RFC 8628), which allows a user to sign in
+/// using their browser and returns an access token for the service.
+pub struct DeviceCodeFlow {
+    pub device_code_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    pub scope: String,
+}
+
+/// The code which the user must enter at the verification URI to authorize Git-Tool.
+#[derive(Debug, Deserialize)]
+pub struct DeviceCode {
+    device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    #[serde(default = "default_interval")]
+    interval: u64,
+    expires_in: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+impl DeviceCodeFlow {
+    /// Runs the device authorization flow, calling `prompt` with the code the user must
+    /// enter and then polling until they have authorized Git-Tool (or the code expires).
+    pub async fn authenticate<C, P>(&self, core: &C, prompt: P) -> Result<String, Error>
+    where
+        C: Core,
+        P: FnOnce(&DeviceCode) -> Result<(), Error> + Send,
+    {
+        let code: DeviceCode = self
+            .post(
+                core,
+                &self.device_code_url,
+                &[("client_id", &self.client_id), ("scope", &self.scope)],
+            )
+            .await?;
+
+        prompt(&code)?;
+
+        let started = Instant::now();
+        let mut interval = Duration::from_secs(code.interval);
+
+        loop {
+            if started.elapsed() > Duration::from_secs(code.expires_in) {
+                return Err(errors::user(
+                    "The sign in code expired before you authorized Git-Tool.",
+                    "Please run the command again and enter the code in your browser before it expires.",
+                ));
+            }
+
+            tokio::time::delay_for(interval).await;
+
+            let resp: TokenResponse = self
+                .post(
+                    core,
+                    &self.token_url,
+                    &[
+                        ("client_id", &self.client_id),
+                        ("device_code", &code.device_code),
+                        ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                    ],
+                )
+                .await?;
+
+            if let Some(token) = resp.access_token {
+                return Ok(token);
+            }
+
+            match resp.error.as_deref() {
+                Some("authorization_pending") => {}
+                Some("slow_down") => {
+                    interval = Duration::from_secs(resp.interval.unwrap_or(interval.as_secs() + 5));
+                }
+                Some("access_denied") => {
+                    return Err(errors::user(
+                        "You declined to authorize Git-Tool.",
+                        "If this was a mistake, please run the command again and approve the request in your browser.",
+                    ))
+                }
+                Some("expired_token") => {
+                    return Err(errors::user(
+                        "The sign in code expired before you authorized Git-Tool.",
+                        "Please run the command again and enter the code in your browser before it expires.",
+                    ))
+                }
+                error => {
+                    return Err(errors::system_with_internal(
+                        "The service returned an unexpected response while we were waiting for you to sign in.",
+                        "Please try again, or use a personal access token instead by running this command without `--web`.",
+                        errors::detailed_message(&format!(
+                            "{}: {}",
+                            error.unwrap_or("unknown_error"),
+                            resp.error_description.unwrap_or_default()
+                        )),
+                    ))
+                }
+            }
+        }
+    }
+
+    async fn post<C: Core, T: DeserializeOwned>(
+        &self,
+        core: &C,
+        uri: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        let uri: Uri = uri.parse()?;
+
+        let req = Request::builder()
+            .uri(&uri)
+            .method("POST")
+            .header("User-Agent", version!("Git-Tool/v"))
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Body::from(form_encode(form)))
+            .map_err(|e| {
+                errors::system_with_internal(
+                    "Unable to construct the web request used to sign in.",
+                    "Please report this error to us by opening a ticket in GitHub.",
+                    e,
+                )
+            })?;
+
+        let resp = core.http_client().request(req).await?;
+        let status = resp.status();
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+
+        // OAuth token endpoints report pending authorization using an error payload, often
+        // with a 400 status code, so we only treat unparseable responses as failures.
+        serde_json::from_slice(&body).map_err(|e| {
+            errors::system_with_internal(
+                &format!(
+                    "Received an unexpected HTTP {} response while signing in.",
+                    status.as_u16()
+                ),
+                "Please try again, or use a personal access token instead by running this command without `--web`.",
+                e,
+            )
+        })
+    }
+}
+
+fn form_encode(form: &[(&str, &str)]) -> String {
+    form.iter()
+        .map(|(key, value)| format!("{}={}", url_encode(key), url_encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mocks::*;
+    use super::*;
+
+    #[test]
+    fn test_form_encode() {
+        assert_eq!(
+            form_encode(&[
+                ("client_id", "abc123"),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code")
+            ]),
+            "client_id=abc123&grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() {
+        let core = CoreBuilder::default()
+            .with_http_connector(DeviceCodeSuccessFlow::default())
+            .build();
+
+        let flow = DeviceCodeFlow {
+            device_code_url: "https://example.com/login/device/code".into(),
+            token_url: "https://example.com/login/oauth/access_token".into(),
+            client_id: "test-client".into(),
+            scope: "repo".into(),
+        };
+
+        let mut user_code = String::new();
+        let token = flow
+            .authenticate(&core, |code| {
+                user_code = code.user_code.clone();
+                Ok(())
+            })
+            .await
+            .expect("the flow should complete successfully");
+
+        assert_eq!(user_code, "ABCD-1234");
+        assert_eq!(token, "test-token");
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_denied() {
+        let core = CoreBuilder::default()
+            .with_http_connector(DeviceCodeDeniedFlow::default())
+            .build();
+
+        let flow = DeviceCodeFlow {
+            device_code_url: "https://example.com/login/device/code".into(),
+            token_url: "https://example.com/login/oauth/access_token".into(),
+            client_id: "test-client".into(),
+            scope: "repo".into(),
+        };
+
+        flow.authenticate(&core, |_| Ok(()))
+            .await
+            .expect_err("the flow should fail when the user declines");
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    pub type DeviceCodeSuccessFlow = MockDeviceCodeSuccessFlow;
+    pub type DeviceCodeDeniedFlow = MockDeviceCodeDeniedFlow;
+
+    mock_connector_in_order!(MockDeviceCodeSuccessFlow {
+r#"HTTP/1.1 200 OK
+Content-Type: application/json
+Content-Length: 137
+
+{"device_code":"device-1234","user_code":"ABCD-1234","verification_uri":"https://example.com/login/device","interval":0,"expires_in":900}
+"#
+
+r#"HTTP/1.1 400 Bad Request
+Content-Type: application/json
+Content-Length: 33
+
+{"error":"authorization_pending"}
+"#
+
+r#"HTTP/1.1 200 OK
+Content-Type: application/json
+Content-Length: 66
+
+{"access_token":"test-token","token_type":"bearer","scope":"repo"}
+"#});
+
+    mock_connector_in_order!(MockDeviceCodeDeniedFlow {
+r#"HTTP/1.1 200 OK
+Content-Type: application/json
+Content-Length: 137
+
+{"device_code":"device-1234","user_code":"ABCD-1234","verification_uri":"https://example.com/login/device","interval":0,"expires_in":900}
+"#
+
+r#"HTTP/1.1 400 Bad Request
+Content-Type: application/json
+Content-Length: 25
+
+{"error":"access_denied"}
+"#});
+}
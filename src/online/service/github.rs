@@ -1,6 +1,6 @@
 use super::*;
 use crate::errors;
-use http::{Request, StatusCode, Uri};
+use http::{Request, Response, StatusCode, Uri};
 use hyper::Body;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -20,7 +20,13 @@ impl<C: Core> OnlineService<C> for GitHubService {
     }
 
     async fn ensure_created(&self, core: &C, repo: &Repo) -> Result<(), Error> {
-        let current_user = self.get_user_login(core).await?;
+        let token_name = core
+            .config()
+            .get_service(&repo.get_domain())
+            .map(|s| s.get_token_name(repo))
+            .unwrap_or_else(|| repo.get_domain());
+
+        let current_user = self.get_user_login(core, &token_name).await?;
 
         let uri = if repo.get_namespace() == current_user {
             format!("https://api.github.com/user/repos").parse()?
@@ -37,6 +43,7 @@ impl<C: Core> OnlineService<C> for GitHubService {
         let new_repo_resp: Result<NewRepoResponse, GitHubErrorResponse> = self
             .make_request(
                 core,
+                &token_name,
                 "POST",
                 uri,
                 Body::from(req_body),
@@ -50,14 +57,74 @@ impl<C: Core> OnlineService<C> for GitHubService {
             Err(e) => Err(e.into()),
         }
     }
+
+    async fn token_status(&self, core: &C, token_name: &str) -> Result<TokenStatus, Error> {
+        let uri: Uri = "https://api.github.com/user".parse()?;
+        let resp = self
+            .send_request(core, token_name, "GET", uri, Body::empty())
+            .await?;
+
+        // Classic tokens report their scopes and (optional) expiry in these headers, while
+        // fine-grained tokens omit the scopes header entirely.
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string())
+        };
+
+        let scopes: Option<Vec<String>> = header("X-OAuth-Scopes").map(|h| {
+            h.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        });
+        let expires = header("GitHub-Authentication-Token-Expiration");
+
+        let user: Result<UserProfile, GitHubErrorResponse> =
+            self.parse_response(resp, vec![StatusCode::OK]).await?;
+
+        let missing_scopes = match &scopes {
+            Some(scopes) if !scopes.iter().any(|s| s == "repo") => vec!["repo".to_string()],
+            _ => vec![],
+        };
+
+        match user {
+            Ok(user) => Ok(TokenStatus {
+                login: user.login,
+                scopes,
+                expires,
+                missing_scopes,
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn device_code_flow(&self, service: &Service) -> Option<DeviceCodeFlow> {
+        service
+            .get_oauth_client_id()
+            .map(|client_id| DeviceCodeFlow {
+                device_code_url: "https://github.com/login/device/code".into(),
+                token_url: "https://github.com/login/oauth/access_token".into(),
+                client_id,
+                scope: "repo".into(),
+            })
+    }
 }
 
 impl GitHubService {
-    async fn get_user_login<C: Core>(&self, core: &C) -> Result<String, Error> {
+    async fn get_user_login<C: Core>(&self, core: &C, token_name: &str) -> Result<String, Error> {
         let uri: Uri = "https://api.github.com/user".parse()?;
 
         let user: Result<UserProfile, GitHubErrorResponse> = self
-            .make_request(core, "GET", uri, Body::empty(), vec![StatusCode::OK])
+            .make_request(
+                core,
+                token_name,
+                "GET",
+                uri,
+                Body::empty(),
+                vec![StatusCode::OK],
+            )
             .await?;
 
         match user {
@@ -69,12 +136,27 @@ impl GitHubService {
     async fn make_request<C: Core, T: DeserializeOwned>(
         &self,
         core: &C,
+        token_name: &str,
         method: &str,
         uri: Uri,
         body: Body,
         acceptable: Vec<StatusCode>,
     ) -> Result<Result<T, GitHubErrorResponse>, Error> {
-        let token = core.keychain().get_token("github.com")?;
+        let resp = self
+            .send_request(core, token_name, method, uri, body)
+            .await?;
+        self.parse_response(resp, acceptable).await
+    }
+
+    async fn send_request<C: Core>(
+        &self,
+        core: &C,
+        token_name: &str,
+        method: &str,
+        uri: Uri,
+        body: Body,
+    ) -> Result<Response<Body>, Error> {
+        let token = core.keychain().get_token(token_name)?;
 
         let req = Request::builder()
             .uri(&uri)
@@ -91,8 +173,14 @@ impl GitHubService {
                 )
             })?;
 
-        let resp = core.http_client().request(req).await?;
+        Ok(core.http_client().request(req).await?)
+    }
 
+    async fn parse_response<T: DeserializeOwned>(
+        &self,
+        resp: Response<Body>,
+        acceptable: Vec<StatusCode>,
+    ) -> Result<Result<T, GitHubErrorResponse>, Error> {
         match resp.status() {
             status if acceptable.contains(&status) => {
                 let body = hyper::body::to_bytes(resp.into_body()).await?;
@@ -199,6 +287,64 @@ mod tests {
             .expect("No error should have been generated");
     }
 
+    #[tokio::test]
+    async fn test_account_token() {
+        let config = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    accounts:
+      - name: work
+        namespaces: ["work-org"]
+"#,
+        )
+        .unwrap();
+
+        let core = CoreBuilder::default()
+            .with_config(&config)
+            .with_mock_keychain(|s| {
+                s.set_token("github.com/work", "work_token").unwrap();
+            })
+            .with_http_connector(NewRepoSuccessFlow::default())
+            .build();
+
+        let repo = Repo::new("github.com/work-org/service", std::path::PathBuf::from("/"));
+        let service = GitHubService::default();
+        service
+            .ensure_created(&core, &repo)
+            .await
+            .expect("the work account's token should have been used");
+    }
+
+    #[tokio::test]
+    async fn test_token_status() {
+        let core = CoreBuilder::default()
+            .with_mock_keychain(|s| {
+                s.set_token("github.com", "test_token").unwrap();
+            })
+            .with_http_connector(TokenStatusFlow::default())
+            .build();
+
+        let service = GitHubService::default();
+        let status = service
+            .token_status(&core, "github.com")
+            .await
+            .expect("No error should have been generated");
+
+        assert_eq!(status.login, "test");
+        assert_eq!(
+            status.scopes,
+            Some(vec!["read:user".to_string(), "gist".to_string()])
+        );
+        assert_eq!(status.expires, Some("2021-08-01 00:00:00 UTC".to_string()));
+        assert_eq!(status.missing_scopes, vec!["repo"]);
+    }
+
     #[tokio::test]
     async fn test_happy_path_user_repo_exists() {
         let http = NewRepoExistsFlow::default();
@@ -221,6 +367,7 @@ mod tests {
 
 #[cfg(test)]
 pub mod mocks {
+    pub type TokenStatusFlow = MockGitHubTokenStatusFlow;
     pub type NewRepoSuccessFlow = MockGitHubNewRepoSuccessFlow;
     pub type NewRepoExistsFlow = MockGitHubNewRepoDuplicateFlow;
 
@@ -252,5 +399,15 @@ Content-Type: application/vnd.github.v3+json
 Content-Length: 225
 
 {"message":"Repository creation failed.","errors":[{"resource":"Repository","code":"custom","field":"name","message":"name already exists on this account"}],"documentation_url":"https://developer.github.com/v3/repos/#create"}
+"#});
+
+    mock_connector_in_order!(MockGitHubTokenStatusFlow {
+r#"HTTP/1.1 200 OK
+Content-Type: application/vnd.github.v3+json
+X-OAuth-Scopes: read:user, gist
+GitHub-Authentication-Token-Expiration: 2021-08-01 00:00:00 UTC
+Content-Length: 16
+
+{"login":"test"}
 "#});
 }
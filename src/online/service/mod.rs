@@ -2,12 +2,34 @@ use crate::core::*;
 use async_trait::async_trait;
 use std::sync::Arc;
 
+pub mod device_code;
 pub mod github;
 
+pub use device_code::DeviceCodeFlow;
+
 #[async_trait]
 pub trait OnlineService<C: Core>: Send + Sync {
     fn handles(&self, service: &Service) -> bool;
     async fn ensure_created(&self, core: &C, repo: &Repo) -> Result<(), Error>;
+
+    /// Checks the access token stored in the named keychain entry, reporting the
+    /// account it belongs to and the permissions it has been granted.
+    async fn token_status(&self, core: &C, token_name: &str) -> Result<TokenStatus, Error>;
+
+    /// Gets the OAuth device code flow which can be used to sign in to this service
+    /// from the command line, if it supports one.
+    fn device_code_flow(&self, _service: &Service) -> Option<DeviceCodeFlow> {
+        None
+    }
+}
+
+/// The details of an access token, as reported by the service it belongs to.
+#[derive(Debug, Clone)]
+pub struct TokenStatus {
+    pub login: String,
+    pub scopes: Option<Vec<String>>,
+    pub expires: Option<String>,
+    pub missing_scopes: Vec<String>,
 }
 
 pub fn services<C: Core>() -> Vec<Arc<dyn OnlineService<C>>> {
@@ -1,5 +1,8 @@
 use super::*;
-use crate::{core::Target, errors, git};
+use crate::{
+    core::{Output, Target},
+    errors, git,
+};
 
 pub struct GitClone {}
 
@@ -16,13 +19,38 @@ impl<C: Core> Task<C> for GitClone {
                 &format!("Ensure that your git-tool configuration has a service entry for this service, or add it with `git-tool config add service/{}`", repo.get_domain()))
         )?;
 
-        let url = if core.config().get_features().use_http_transport() {
-            service.get_http_url(repo)?
+        if core.config().get_features().use_http_transport() {
+            let url = service.get_http_url(repo)?;
+
+            // Configure Git-Tool as the credential helper for this repository so that
+            // the token stored with `git-tool auth` is used for clones and pushes.
+            let helper = format!(
+                "!{} credential",
+                shell_words::quote(&std::env::current_exe()?.to_string_lossy())
+            );
+
+            // We include the repository's path in credential requests so that the token for
+            // the account which owns this namespace can be selected.
+            git::git_clone_with_config(
+                &repo.get_path(),
+                &url,
+                &[
+                    ("credential.helper", &helper),
+                    ("credential.useHttpPath", "true"),
+                ],
+                core.config().get_clone_timeout(),
+                core.output().writer(),
+            )
+            .await?;
         } else {
-            service.get_git_url(repo)?
-        };
+            let url = service.get_git_url(repo)?;
+
+            git::backend(core.config())
+                .clone_repo(&repo.get_path(), &url, core.output().writer())
+                .await?;
+        }
 
-        git::git_clone(&repo.get_path(), &url).await
+        GitConfig {}.apply_repo(core, repo).await
     }
 
     async fn apply_scratchpad(
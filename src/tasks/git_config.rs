@@ -0,0 +1,85 @@
+use super::*;
+use crate::{core::Target, git};
+
+/// Applies the local Git configuration for a repository's service and account, such
+/// as the `user.name` and `user.email` used for commits.
+pub struct GitConfig {}
+
+#[async_trait::async_trait]
+impl<C: Core> Task<C> for GitConfig {
+    async fn apply_repo(&self, core: &C, repo: &core::Repo) -> Result<(), core::Error> {
+        let service = match core.config().get_service(&repo.get_domain()) {
+            Some(service) => service,
+            None => return Ok(()),
+        };
+
+        for (key, value) in service.get_git_config(repo) {
+            git::git_config_set(&repo.get_path(), &key, &value).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_scratchpad(
+        &self,
+        _core: &C,
+        _scratch: &core::Scratchpad,
+    ) -> Result<(), core::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Config;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_repo() {
+        let temp = tempdir().unwrap();
+        let repo = core::Repo::new(
+            "github.com/work-org/test-git-config",
+            temp.path().join("repo"),
+        );
+
+        let config = Config::from_str(
+            r#"
+directory: /dev
+services:
+  - domain: github.com
+    website: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}"
+    httpUrl: "https://{{ .Service.Domain }}/{{ .Repo.FullName }}.git"
+    gitUrl: "git@{{ .Service.Domain }}:{{ .Repo.FullName }}.git"
+    pattern: "*/*"
+    accounts:
+      - name: work
+        namespaces: ["work-org"]
+        userName: Work User
+        userEmail: me@work.example.com
+    gitConfig:
+      - key: commit.gpgsign
+        value: "true"
+        namespace: work-org
+"#,
+        )
+        .unwrap();
+
+        let core = core::CoreBuilder::default().with_config(&config).build();
+
+        GitInit {}.apply_repo(&core, &repo).await.unwrap();
+
+        let config = git2::Repository::open(repo.get_path())
+            .unwrap()
+            .config()
+            .unwrap()
+            .open_level(git2::ConfigLevel::Local)
+            .unwrap();
+        assert_eq!(
+            config.get_string("user.email").unwrap(),
+            "me@work.example.com"
+        );
+        assert_eq!(config.get_string("user.name").unwrap(), "Work User");
+        assert!(config.get_bool("commit.gpgsign").unwrap());
+    }
+}
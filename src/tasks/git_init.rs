@@ -5,8 +5,10 @@ pub struct GitInit {}
 
 #[async_trait::async_trait]
 impl<C: Core> Task<C> for GitInit {
-    async fn apply_repo(&self, _core: &C, repo: &core::Repo) -> Result<(), core::Error> {
-        git::git_init(&repo.get_path()).await
+    async fn apply_repo(&self, core: &C, repo: &core::Repo) -> Result<(), core::Error> {
+        git::git_init(&repo.get_path()).await?;
+
+        GitConfig {}.apply_repo(core, repo).await
     }
 
     async fn apply_scratchpad(
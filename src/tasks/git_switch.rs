@@ -8,14 +8,23 @@ pub struct GitSwitch {
 
 #[async_trait::async_trait]
 impl<C: Core> Task<C> for GitSwitch {
-    async fn apply_repo(&self, _core: &C, repo: &core::Repo) -> Result<(), core::Error> {
-        let mut create = self.create_if_missing;
-
-        if create && git::git_branches(&repo.get_path()).await?.contains(&self.branch) {
-            create = false;
+    async fn apply_repo(&self, core: &C, repo: &core::Repo) -> Result<(), core::Error> {
+        let branch = git::backend(core.config())
+            .branches(&repo.get_path())
+            .await?
+            .into_iter()
+            .find(|b| b.name == self.branch);
+
+        match branch {
+            Some(git::Branch { is_local: true, .. }) => {
+                git::git_switch(&repo.get_path(), &self.branch, false).await
+            }
+            Some(git::Branch {
+                remote: Some(remote),
+                ..
+            }) => git::git_switch_tracking(&repo.get_path(), &self.branch, &remote).await,
+            _ => git::git_switch(&repo.get_path(), &self.branch, self.create_if_missing).await,
         }
-
-        git::git_switch(&repo.get_path(), &self.branch, create).await
     }
 
     async fn apply_scratchpad(
@@ -115,6 +124,64 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_repo_remote_only() {
+        let temp = tempdir().unwrap();
+        let repo = core::Repo::new(
+            "github.com/sierrasoftworks/test-git-switch",
+            temp.path().join("repo"),
+        );
+
+        let core = core::CoreBuilder::default()
+            .with_config(&Config::for_dev_directory(temp.path()))
+            .with_mock_output()
+            .build();
+
+        sequence![
+            GitInit {},
+            GitRemote { name: "origin" },
+            GitCheckout { branch: "main" },
+            WriteFile {
+                path: "README.md".into(),
+                content: "This is an example README file.",
+            },
+            GitAdd {
+                paths: vec!["README.md"],
+            },
+            GitCommit {
+                message: "Add README.md",
+                paths: vec!["README.md"],
+            }
+        ]
+        .apply_repo(&core, &repo)
+        .await
+        .unwrap();
+
+        let sha = git::git_rev_parse(&repo.get_path(), "HEAD").await.unwrap();
+        git::git_update_ref(&repo.get_path(), "refs/remotes/origin/feature", &sha)
+            .await
+            .unwrap();
+
+        GitSwitch {
+            branch: "feature".into(),
+            create_if_missing: false,
+        }
+        .apply_repo(&core, &repo)
+        .await
+        .expect("switching to a remote-only branch should create a tracking branch");
+
+        assert_eq!(
+            git::git_current_branch(&repo.get_path()).await.unwrap(),
+            "feature"
+        );
+        assert_eq!(
+            git::git_rev_parse(&repo.get_path(), "feature@{upstream}")
+                .await
+                .expect("the new branch should track the remote branch"),
+            sha
+        );
+    }
+
     #[tokio::test]
     async fn test_scratch() {
         let temp = tempdir().unwrap();
@@ -23,6 +23,7 @@ mod git_add;
 mod git_checkout;
 mod git_clone;
 mod git_commit;
+mod git_config;
 mod git_init;
 mod git_remote;
 mod git_switch;
@@ -34,6 +35,7 @@ pub use git_add::GitAdd;
 pub use git_checkout::GitCheckout;
 pub use git_clone::GitClone;
 pub use git_commit::GitCommit;
+pub use git_config::GitConfig;
 pub use git_init::GitInit;
 pub use git_remote::GitRemote;
 pub use git_switch::GitSwitch;